@@ -1,35 +1,122 @@
+use std::fs::File;
+use std::io::Write;
 use std::time::Instant;
 
 use crate::framebuffer::PixelFramebuffer;
 use crate::input::{self, Action};
+use crate::post::PostEffect;
 use crate::sequencer::Sequencer;
 
+/// Radians of yaw per terminal column dragged; rows are two pixels tall,
+/// so pitch gets twice as much per row.
+const LOOK_PER_CELL: f64 = 0.04;
+/// Seconds without dragging before the camera eases back to its script.
+const LOOK_HOLD: f64 = 1.5;
+/// Longest step handed to the effects; a stall past this slows the show
+/// down instead of letting simulations jump.
+const MAX_DT: f64 = 0.1;
+
+/// Camera offsets from dragging the mouse in interactive mode.
+#[derive(Default)]
+pub struct MouseLook {
+    pub yaw: f64,
+    pub pitch: f64,
+    /// Cell the drag last reported, while the button is held
+    anchor: Option<(u16, u16)>,
+    idle: f64,
+}
+
+impl MouseLook {
+    fn drag_to(&mut self, column: u16, row: u16) {
+        if let Some((ax, ay)) = self.anchor {
+            self.yaw += (column as f64 - ax as f64) * LOOK_PER_CELL;
+            self.pitch = (self.pitch + (row as f64 - ay as f64) * LOOK_PER_CELL * 2.0).clamp(-1.2, 1.2);
+        }
+        self.anchor = Some((column, row));
+        self.idle = 0.0;
+    }
+
+    /// Once the mouse rests, decay the offsets so the scripted motion returns.
+    fn settle(&mut self, dt: f64) {
+        if self.anchor.is_some() {
+            return;
+        }
+        self.idle += dt;
+        if self.idle > LOOK_HOLD {
+            let keep = (-dt * 2.0).exp();
+            self.yaw *= keep;
+            self.pitch *= keep;
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum Mode {
     AutoPlay,
     Interactive,
+    /// Like autoplay, but each scene holds until Space advances it
+    Presentation,
 }
 
 pub struct App {
     pub fb: PixelFramebuffer,
     pub sequencer: Sequencer,
     pub mode: Mode,
+    /// The hands-off mode Tab returns to from interactive
+    show_mode: Mode,
     pub show_hud: bool,
+    /// Frame the effect with a titled border, shrinking its render area
+    pub show_border: bool,
+    /// Key list overlay, and how many lines it's scrolled down
+    pub show_help: bool,
+    pub help_scroll: usize,
     pub selected_param: usize,
     pub should_quit: bool,
+    /// Message being typed for the scrollers, while text entry is active
+    pub message_input: Option<String>,
+    /// Post-processes applied to every scene's output, in order
+    pub post: Vec<PostEffect>,
+    /// Post-processed copy of `fb`, so effects that build on their previous
+    /// frame never see the post-process compounding
+    post_fb: PixelFramebuffer,
+    /// The last frame handed to the terminal, see `output_changed`
+    drawn: PixelFramebuffer,
+    /// Where status lines go for external tools (`--status-fd`)
+    pub status_out: Option<File>,
+    status_elapsed: f64,
+    status_scene: usize,
     last_frame: Instant,
+    /// Drag-to-look camera state, steering effects that implement `set_camera`
+    pub mouse_look: MouseLook,
 }
 
 impl App {
-    pub fn new(sequencer: Sequencer, mode: Mode) -> Self {
+    pub fn new(mut sequencer: Sequencer, mode: Mode) -> Self {
+        sequencer.manual = mode == Mode::Presentation;
         Self {
             fb: PixelFramebuffer::new(0, 0),
             sequencer,
             mode,
+            show_mode: if mode == Mode::Presentation {
+                Mode::Presentation
+            } else {
+                Mode::AutoPlay
+            },
             show_hud: mode == Mode::Interactive,
+            show_border: false,
+            show_help: false,
+            help_scroll: 0,
             selected_param: 0,
             should_quit: false,
+            message_input: None,
+            post: Vec::new(),
+            post_fb: PixelFramebuffer::new(0, 0),
+            drawn: PixelFramebuffer::new(0, 0),
+            status_out: None,
+            status_elapsed: 0.0,
+            status_scene: usize::MAX,
             last_frame: Instant::now(),
+            mouse_look: MouseLook::default(),
         }
     }
 
@@ -44,20 +131,40 @@ impl App {
     }
 
     pub fn handle_input(&mut self) -> std::io::Result<()> {
-        match input::poll_action()? {
+        match input::poll_action(self.message_input.is_some())? {
+            // While the key list is open, quitting closes it and the
+            // arrows scroll it
+            Action::Quit | Action::ToggleHelp if self.show_help => self.show_help = false,
+            Action::ParamUp if self.show_help => {
+                self.help_scroll = self.help_scroll.saturating_sub(1);
+            }
+            Action::ParamDown if self.show_help => {
+                self.help_scroll = (self.help_scroll + 1).min(input::BINDINGS.len() - 1);
+            }
+            Action::ToggleHelp => {
+                self.show_help = true;
+                self.help_scroll = 0;
+            }
             Action::Quit => self.should_quit = true,
+            // Presenting, Space steps through the talk instead of pausing
+            Action::TogglePause if self.mode == Mode::Presentation => {
+                self.sequencer.next_scene();
+                self.selected_param = 0;
+            }
             Action::TogglePause => self.sequencer.toggle_pause(),
             Action::ToggleMode => {
                 self.mode = match self.mode {
-                    Mode::AutoPlay => {
+                    Mode::AutoPlay | Mode::Presentation => {
                         self.sequencer.looping = false;
+                        self.sequencer.manual = false;
                         self.show_hud = true;
                         Mode::Interactive
                     }
                     Mode::Interactive => {
                         self.sequencer.looping = true;
+                        self.sequencer.manual = self.show_mode == Mode::Presentation;
                         self.show_hud = false;
-                        Mode::AutoPlay
+                        self.show_mode
                     }
                 };
             }
@@ -74,7 +181,9 @@ impl App {
                 self.selected_param = 0;
             }
             Action::ToggleHud => self.show_hud = !self.show_hud,
+            Action::ToggleBorder => self.show_border = !self.show_border,
             Action::ToggleHold => self.sequencer.toggle_hold(),
+            Action::ToggleReverse => self.sequencer.toggle_reverse(),
             Action::ParamUp => self.adjust_param(0.05),
             Action::ParamDown => self.adjust_param(-0.05),
             Action::ParamPrev => {
@@ -88,6 +197,44 @@ impl App {
                     }
                 }
             }
+            Action::EditMessage => self.message_input = Some(String::new()),
+            Action::Trigger => {
+                if let Some(effect) = self.sequencer.current_effect_mut() {
+                    effect.trigger();
+                }
+            }
+            Action::Reshuffle => self.sequencer.reshuffle_current(),
+            Action::MouseDown(column, row) => {
+                if self.mode == Mode::Interactive {
+                    self.poke(column, row);
+                    self.mouse_look.drag_to(column, row);
+                }
+            }
+            Action::MouseDrag(column, row) => {
+                if self.mode == Mode::Interactive {
+                    self.mouse_look.drag_to(column, row);
+                }
+            }
+            Action::MouseUp => self.mouse_look.anchor = None,
+            Action::TextChar(c) => {
+                if let Some(text) = self.message_input.as_mut() {
+                    text.push(c);
+                }
+            }
+            Action::TextBackspace => {
+                if let Some(text) = self.message_input.as_mut() {
+                    text.pop();
+                }
+            }
+            Action::TextSubmit => {
+                if let Some(text) = self.message_input.take() {
+                    if !text.is_empty() {
+                        // Trailing gap so the loop doesn't run into itself
+                        self.sequencer.set_text(&format!("{}   ", text));
+                    }
+                }
+            }
+            Action::TextCancel => self.message_input = None,
             Action::None => {}
         }
         Ok(())
@@ -95,9 +242,87 @@ impl App {
 
     pub fn update(&mut self) {
         let now = Instant::now();
-        let dt = now.duration_since(self.last_frame).as_secs_f64();
+        let dt = now.duration_since(self.last_frame).as_secs_f64().min(MAX_DT);
         self.last_frame = now;
+        self.mouse_look.settle(dt);
+        let (yaw, pitch) = (self.mouse_look.yaw, self.mouse_look.pitch);
+        if let Some(effect) = self.sequencer.current_effect_mut() {
+            effect.set_camera(yaw, pitch);
+        }
         self.sequencer.update(dt, &mut self.fb.pixels);
+        if self.has_post() {
+            let (w, h) = (self.fb.width, self.fb.height);
+            self.post_fb.resize(w, h);
+            self.post_fb.pixels.copy_from_slice(&self.fb.pixels);
+            for post in &mut self.post {
+                post.apply(&mut self.post_fb.pixels, w, h);
+            }
+        }
+        self.report_status(dt);
+    }
+
+    /// Write a status line at 10 Hz, and immediately on a scene change.
+    fn report_status(&mut self, dt: f64) {
+        if self.status_out.is_none() {
+            return;
+        }
+        self.status_elapsed += dt;
+        let scene = self.sequencer.current;
+        if self.status_elapsed < 0.1 && scene == self.status_scene {
+            return;
+        }
+        self.status_elapsed = 0.0;
+        self.status_scene = scene;
+        let line = self.sequencer.status_line();
+        // A reader that went away just stops the reporting
+        if let Some(out) = &mut self.status_out {
+            if writeln!(out, "{}", line).is_err() {
+                self.status_out = None;
+            }
+        }
+    }
+
+    /// The frame to display: `fb` with any post-processing applied.
+    pub fn output(&self) -> &PixelFramebuffer {
+        if self.has_post() {
+            &self.post_fb
+        } else {
+            &self.fb
+        }
+    }
+
+    /// Whether the output differs from the frame last handed to the
+    /// terminal, remembering it as drawn when it does.
+    ///
+    /// ratatui already writes only the cells that differ from its previous
+    /// buffer; this lets the caller skip rendering and diffing the whole
+    /// buffer when nothing moved, e.g. while paused.
+    pub fn output_changed(&mut self) -> bool {
+        let out = if self.has_post() { &self.post_fb } else { &self.fb };
+        if out.width == self.drawn.width
+            && out.height == self.drawn.height
+            && out.pixels == self.drawn.pixels
+        {
+            return false;
+        }
+        self.drawn.resize(out.width, out.height);
+        self.drawn.pixels.copy_from_slice(&out.pixels);
+        true
+    }
+
+    /// Hand a click on terminal cell (column, row) to the effect, at the
+    /// middle of the cell's two pixels and inside the border when shown.
+    fn poke(&mut self, column: u16, row: u16) {
+        let inset = if self.show_border { 1.0 } else { 0.0 };
+        let x = column as f64 - inset;
+        let y = (row as f64 - inset) * 2.0 + 0.5;
+        if let Some(effect) = self.sequencer.current_effect_mut() {
+            effect.poke(x, y);
+        }
+    }
+
+    fn has_post(&self) -> bool {
+        !self.post.is_empty()
     }
 
     fn adjust_param(&mut self, delta: f64) {
@@ -0,0 +1,195 @@
+//! Headless per-effect timing for tracking performance across commits.
+//!
+//! Every effect runs offscreen at a fixed timestep; nothing touches the
+//! terminal, so the numbers reflect `init` and `update` cost alone.
+//! `FrameRates` is the on-screen counterpart, filled in by `--uncapped` runs
+//! through the real render path.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::effects;
+use crate::framebuffer::PixelFramebuffer;
+
+/// Fixed timestep matching the interactive frame rate.
+const DT: f64 = 1.0 / 60.0;
+
+/// Seed `--bench` uses without `--seed`, so runs from different commits set
+/// effects up identically.
+pub const DEFAULT_SEED: u64 = 1;
+
+pub struct Timing {
+    pub name: &'static str,
+    pub init: Duration,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+/// Time `init` once and `update` for `frames` frames of every effect whose
+/// name is in `names`.
+///
+/// Each effect gets a fresh RNG from `seed`, so its timings don't depend on
+/// which effects ran before it.
+pub fn run(names: &[&'static str], width: u32, height: u32, frames: usize, seed: u64) -> Vec<Timing> {
+    let mut fb = PixelFramebuffer::new(width, height);
+    names
+        .iter()
+        .filter_map(|&name| {
+            let mut effect = effects::create(name)?;
+            let mut rng = StdRng::seed_from_u64(seed);
+            fb.pixels.iter_mut().for_each(|px| *px = (0, 0, 0));
+
+            let start = Instant::now();
+            effect.init(width, height);
+            effect.randomize_init(&mut rng);
+            let init = start.elapsed();
+
+            let mut samples: Vec<Duration> = (0..frames.max(1))
+                .map(|frame| {
+                    let start = Instant::now();
+                    effect.update(frame as f64 * DT, DT, &mut fb.pixels);
+                    start.elapsed()
+                })
+                .collect();
+            samples.sort();
+
+            Some(Timing {
+                name,
+                init,
+                min: samples[0],
+                median: samples[samples.len() / 2],
+                max: samples[samples.len() - 1],
+            })
+        })
+        .collect()
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// Aligned table in registry order, followed by the slowest effects.
+pub fn write_table(out: &mut impl Write, timings: &[Timing]) -> io::Result<()> {
+    let name_w = timings.iter().map(|t| t.name.len()).max().unwrap_or(0).max(6);
+    writeln!(
+        out,
+        "{:<name_w$}  {:>9}  {:>9}  {:>9}  {:>9}",
+        "effect",
+        "init ms",
+        "min ms",
+        "median ms",
+        "max ms",
+        name_w = name_w
+    )?;
+    for t in timings {
+        writeln!(
+            out,
+            "{:<name_w$}  {:>9.3}  {:>9.3}  {:>9.3}  {:>9.3}",
+            t.name,
+            ms(t.init),
+            ms(t.min),
+            ms(t.median),
+            ms(t.max),
+            name_w = name_w
+        )?;
+    }
+
+    if let Some(t) = timings.iter().max_by_key(|t| t.median) {
+        writeln!(out, "\nslowest update: {} ({:.3} ms median)", t.name, ms(t.median))?;
+    }
+    if let Some(t) = timings.iter().max_by_key(|t| t.init) {
+        writeln!(out, "slowest init:   {} ({:.3} ms)", t.name, ms(t.init))?;
+    }
+    Ok(())
+}
+
+/// One JSON object per effect, times in milliseconds.
+pub fn write_json(out: &mut impl Write, timings: &[Timing]) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (i, t) in timings.iter().enumerate() {
+        let comma = if i + 1 < timings.len() { "," } else { "" };
+        writeln!(
+            out,
+            "  {{\"effect\": {:?}, \"init_ms\": {:.3}, \"min_ms\": {:.3}, \"median_ms\": {:.3}, \"max_ms\": {:.3}}}{}",
+            t.name,
+            ms(t.init),
+            ms(t.min),
+            ms(t.median),
+            ms(t.max),
+            comma
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+/// Frames drawn per effect by a show running without frame pacing.
+pub struct FrameRates {
+    /// Frames to draw before the run stops
+    pub limit: usize,
+    scenes: Vec<SceneFrames>,
+}
+
+struct SceneFrames {
+    name: String,
+    frames: usize,
+    elapsed: Duration,
+}
+
+impl FrameRates {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            scenes: Vec::new(),
+        }
+    }
+
+    /// Count one frame of `name` that took `elapsed` from input to flush.
+    pub fn record(&mut self, name: &str, elapsed: Duration) {
+        match self.scenes.iter_mut().find(|s| s.name == name) {
+            Some(scene) => {
+                scene.frames += 1;
+                scene.elapsed += elapsed;
+            }
+            None => self.scenes.push(SceneFrames {
+                name: name.to_string(),
+                frames: 1,
+                elapsed,
+            }),
+        }
+    }
+
+    pub fn total_frames(&self) -> usize {
+        self.scenes.iter().map(|s| s.frames).sum()
+    }
+}
+
+/// Sustained frame rate per effect in the order they played, then overall.
+pub fn write_frame_rates(out: &mut impl Write, rates: &FrameRates) -> io::Result<()> {
+    let fps = |frames: usize, elapsed: Duration| frames as f64 / elapsed.as_secs_f64().max(1e-9);
+    let name_w = rates.scenes.iter().map(|s| s.name.len()).max().unwrap_or(0).max(6);
+    writeln!(out, "{:<name_w$}  {:>7}  {:>9}", "effect", "frames", "fps", name_w = name_w)?;
+    for s in &rates.scenes {
+        writeln!(
+            out,
+            "{:<name_w$}  {:>7}  {:>9.1}",
+            s.name,
+            s.frames,
+            fps(s.frames, s.elapsed),
+            name_w = name_w
+        )?;
+    }
+
+    let frames = rates.total_frames();
+    let elapsed: Duration = rates.scenes.iter().map(|s| s.elapsed).sum();
+    writeln!(
+        out,
+        "\noverall: {} frames in {:.2}s, {:.1} fps",
+        frames,
+        elapsed.as_secs_f64(),
+        fps(frames, elapsed)
+    )
+}
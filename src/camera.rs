@@ -0,0 +1,135 @@
+//! Scripted camera flythroughs for 3D effects, loaded from TOML.
+//!
+//! ```toml
+//! [[key]]
+//! time = 0
+//! position = [5.0, 1.5, 0.0]
+//! look_at = [0.0, 0.0, 0.0]
+//! easing = "InOutCubic"   # shapes the move on to the next key
+//!
+//! [[key]]
+//! time = 6
+//! position = [0.0, 3.0, 4.0]
+//! look_at = [0.0, 0.5, 0.0]
+//! ```
+//!
+//! The tour repeats after its last key; end on the first key's position
+//! for a seamless loop.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use toml::{Table, Value};
+
+use crate::playlist::{self, number};
+use crate::transition::Easing;
+
+/// Where the camera is and what it faces at one moment of the tour.
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    /// Seconds into the scene
+    pub time: f64,
+    pub position: [f64; 3],
+    pub look_at: [f64; 3],
+    /// How the move from this key to the next speeds up and slows down
+    pub easing: Easing,
+}
+
+#[derive(Clone, Debug)]
+pub struct CameraPath {
+    /// In increasing time order, never empty
+    keys: Vec<Keyframe>,
+}
+
+impl CameraPath {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        parse(&text).map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+    }
+
+    /// Camera position and look-at point `t` seconds into the tour.
+    pub fn sample(&self, t: f64) -> ([f64; 3], [f64; 3]) {
+        let first = self.keys[0];
+        let last = self.keys[self.keys.len() - 1];
+        let span = last.time - first.time;
+        if span <= 0.0 {
+            return (first.position, first.look_at);
+        }
+        let t = first.time + (t - first.time).rem_euclid(span);
+
+        let next = self
+            .keys
+            .iter()
+            .position(|k| k.time > t)
+            .unwrap_or(self.keys.len() - 1);
+        let (a, b) = (self.keys[next.saturating_sub(1)], self.keys[next]);
+        let progress = if b.time > a.time {
+            a.easing.apply((t - a.time) / (b.time - a.time))
+        } else {
+            1.0
+        };
+        let lerp = |p: [f64; 3], q: [f64; 3]| {
+            [
+                p[0] + (q[0] - p[0]) * progress,
+                p[1] + (q[1] - p[1]) * progress,
+                p[2] + (q[2] - p[2]) * progress,
+            ]
+        };
+        (lerp(a.position, b.position), lerp(a.look_at, b.look_at))
+    }
+}
+
+fn parse(text: &str) -> Result<CameraPath, String> {
+    let doc: Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let entries = match doc.get("key") {
+        Some(Value::Array(entries)) if !entries.is_empty() => entries,
+        _ => return Err("no [[key]] entries".to_string()),
+    };
+
+    let keys = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let table = entry
+                .as_table()
+                .ok_or_else(|| format!("key {}: expected a table", i + 1))?;
+            parse_key(table).map_err(|msg| format!("key {}: {}", i + 1, msg))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if keys.windows(2).any(|pair| pair[1].time <= pair[0].time) {
+        return Err("key times must increase".to_string());
+    }
+    Ok(CameraPath { keys })
+}
+
+fn parse_key(table: &Table) -> Result<Keyframe, String> {
+    let time = table
+        .get("time")
+        .and_then(number)
+        .filter(|&t| t >= 0.0)
+        .ok_or("time must be a number of seconds")?;
+    let easing = match table.get("easing") {
+        Some(value) => playlist::easing(value)?,
+        None => Easing::InOutCubic,
+    };
+    Ok(Keyframe {
+        time,
+        position: point(table, "position")?,
+        look_at: point(table, "look_at")?,
+        easing,
+    })
+}
+
+/// A required `[x, y, z]` array of numbers.
+fn point(table: &Table, key: &str) -> Result<[f64; 3], String> {
+    let invalid = || format!("{} must be an [x, y, z] array of numbers", key);
+    let values = table
+        .get(key)
+        .and_then(Value::as_array)
+        .ok_or_else(invalid)?;
+    match values.iter().map(number).collect::<Option<Vec<f64>>>() {
+        Some(v) if v.len() == 3 => Ok([v[0], v[1], v[2]]),
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,63 @@
+//! Metronome sidecar for beat-synced recordings.
+//!
+//! Clicks fall on the same beat grid the sequencer follows under `--bpm`,
+//! counted from the start of the recording, so an editor can line music up
+//! with the scene changes. Downbeats get a higher, louder click.
+
+use std::io::{self, Write};
+
+use crate::sequencer::BEATS_PER_BAR;
+
+const SAMPLE_RATE: u32 = 44_100;
+/// Length of one click in seconds
+const CLICK_SECS: f64 = 0.03;
+
+/// Write a 16-bit mono PCM WAV of `secs` seconds with a click on every beat
+/// at `bpm`.
+pub fn write_click_track(out: &mut impl Write, bpm: f64, secs: f64) -> io::Result<()> {
+    let count = (secs * SAMPLE_RATE as f64).round() as usize;
+    let mut samples = vec![0i16; count];
+    let beat = 60.0 / bpm;
+    let click_len = (CLICK_SECS * SAMPLE_RATE as f64) as usize;
+
+    let mut index = 0;
+    loop {
+        let start = (index as f64 * beat * SAMPLE_RATE as f64).round() as usize;
+        if start >= count {
+            break;
+        }
+        let downbeat = index % BEATS_PER_BAR as usize == 0;
+        let (freq, gain) = if downbeat {
+            (1500.0, 0.8)
+        } else {
+            (1000.0, 0.5)
+        };
+        for (i, sample) in samples[start..].iter_mut().take(click_len).enumerate() {
+            let t = i as f64 / SAMPLE_RATE as f64;
+            let v = gain * (-t * 150.0).exp() * (std::f64::consts::TAU * freq * t).sin();
+            *sample = (v * i16::MAX as f64) as i16;
+        }
+        index += 1;
+    }
+
+    let data_len = (count * 2) as u32;
+    out.write_all(b"RIFF")?;
+    out.write_all(&(36 + data_len).to_le_bytes())?;
+    out.write_all(b"WAVEfmt ")?;
+    out.write_all(&16u32.to_le_bytes())?;
+    // PCM, one channel
+    out.write_all(&1u16.to_le_bytes())?;
+    out.write_all(&1u16.to_le_bytes())?;
+    out.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    // Byte rate and block alignment for 16-bit mono
+    out.write_all(&(SAMPLE_RATE * 2).to_le_bytes())?;
+    out.write_all(&2u16.to_le_bytes())?;
+    out.write_all(&16u16.to_le_bytes())?;
+    out.write_all(b"data")?;
+    out.write_all(&data_len.to_le_bytes())?;
+    let mut bytes = Vec::with_capacity(count * 2);
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    out.write_all(&bytes)
+}
@@ -0,0 +1,63 @@
+//! sRGB <-> linear light conversion, so additive blending sums light
+//! instead of gamma-encoded bytes, and HSV colors for hue cycling.
+
+/// sRGB byte to linear intensity in 0..1.
+pub fn to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Linear intensity to an sRGB byte, clamping out-of-range light.
+pub fn to_srgb(l: f32) -> u8 {
+    let l = l.clamp(0.0, 1.0);
+    let c = if l <= 0.003_130_8 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0 + 0.5) as u8
+}
+
+/// Add `amount` of `color` onto `dst` in linear light.
+pub fn add(dst: (u8, u8, u8), color: (u8, u8, u8), amount: f32) -> (u8, u8, u8) {
+    (
+        to_srgb(to_linear(dst.0) + to_linear(color.0) * amount),
+        to_srgb(to_linear(dst.1) + to_linear(color.1) * amount),
+        to_srgb(to_linear(dst.2) + to_linear(color.2) * amount),
+    )
+}
+
+/// Hue folded into 0..1, so cycling hues like `t * 0.1` or `-x` never leave
+/// the color wheel.
+pub fn wrap_hue(h: f64) -> f64 {
+    ((h % 1.0) + 1.0) % 1.0
+}
+
+/// HSV to RGB components in 0..1; the hue wraps, saturation and value are
+/// taken as given.
+pub fn hsv_to_rgb_f64(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
+    let h = wrap_hue(h);
+    let i = (h * 6.0).floor() as i32;
+    let f = h * 6.0 - i as f64;
+    let p = v * (1.0 - s);
+    let q = v * (1.0 - f * s);
+    let t = v * (1.0 - (1.0 - f) * s);
+    match i % 6 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    }
+}
+
+/// HSV to an RGB byte triple.
+pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let (r, g, b) = hsv_to_rgb_f64(h, s, v);
+    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
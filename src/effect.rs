@@ -1,5 +1,8 @@
 use rand::rngs::StdRng;
 
+use crate::camera::CameraPath;
+use crate::framebuffer::{ColorDepth, Rgba};
+
 pub struct ParamDesc {
     pub name: String,
     pub min: f64,
@@ -9,12 +12,58 @@ pub struct ParamDesc {
 
 pub trait Effect {
     fn name(&self) -> &str;
+    /// Scene length in seconds when the playlist doesn't set one.
+    fn preferred_duration(&self) -> f64 {
+        12.0
+    }
+    /// Time in seconds after which the animation repeats exactly, if it does.
+    fn loop_period(&self) -> Option<f64> {
+        None
+    }
+    /// Trail decay (fraction of brightness kept per 1/60 s) for effects that
+    /// want motion trails. Such effects draw each frame from black; effects
+    /// that fully repaint without trails return `None` so they aren't smeared.
+    fn wants_persistence(&self) -> Option<f64> {
+        None
+    }
+    /// Fewest colors the effect still reads well in. Effects built on
+    /// subtle gradients ask for more, so limited terminals can warn.
+    fn min_color_depth(&self) -> ColorDepth {
+        ColorDepth::Ansi16
+    }
     fn init(&mut self, width: u32, height: u32);
     fn randomize_init(&mut self, _rng: &mut StdRng) {}
+    /// The output size changed mid-scene. The default starts over with
+    /// `init` and `randomize_init`; effects with expensive or long-running
+    /// state override it to rebuild only what depends on the size.
+    fn on_resize(&mut self, width: u32, height: u32, rng: &mut StdRng) {
+        self.init(width, height);
+        self.randomize_init(rng);
+    }
     fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]);
+    /// Draw the frame with transparency, for compositing over another
+    /// effect. Effects that support it fill `pixels` and return true; the
+    /// rest return false and are drawn opaque through `update` instead.
+    /// Only `Compose` layers read this: scenes and transitions always cover
+    /// the whole screen, so they stay on opaque frames.
+    fn update_rgba(&mut self, _t: f64, _dt: f64, _pixels: &mut [Rgba]) -> bool {
+        false
+    }
     fn cleanup(&mut self) {}
     fn params(&self) -> Vec<ParamDesc> {
         vec![]
     }
     fn set_param(&mut self, _name: &str, _value: f64) {}
+    /// Replace the displayed message, for effects that render text.
+    fn set_text(&mut self, _text: &str) {}
+    /// Mouse-look offsets in radians added to the scripted camera or object
+    /// rotation; both are zero when the mouse is idle.
+    fn set_camera(&mut self, _yaw: f64, _pitch: f64) {}
+    /// Fly the camera along a scripted tour instead of the effect's own
+    /// motion, for 3D effects that support it.
+    fn set_camera_path(&mut self, _path: CameraPath) {}
+    /// Fire the effect's one-shot burst (e.g. Starfield's warp), on a key or beat.
+    fn trigger(&mut self) {}
+    /// A click on the frame at pixel (x, y), for effects the viewer can touch.
+    fn poke(&mut self, _x: f64, _y: f64) {}
 }
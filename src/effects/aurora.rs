@@ -1,11 +1,36 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::framebuffer::ColorDepth;
+use crate::rng;
 use std::f64::consts::PI;
 
+/// Curtain layers: (base_color_r, g, b, speed_mult, x_freq, x_offset,
+/// drop_center). The `bands` param draws the first few.
+const CURTAINS: [(f64, f64, f64, f64, f64, f64, f64); 8] = [
+    (0.1, 1.0, 0.4, 1.0, 3.0, 0.0, 0.35),  // green
+    (0.0, 0.8, 0.7, 0.7, 2.5, 1.5, 0.30),  // teal
+    (0.6, 0.2, 0.9, 1.3, 4.0, 3.0, 0.25),  // purple
+    (0.9, 0.3, 0.6, 0.5, 2.0, 5.0, 0.40),  // pink
+    (0.2, 0.9, 0.3, 0.85, 3.5, 2.2, 0.32), // green
+    (0.1, 0.7, 0.9, 1.1, 2.8, 4.1, 0.28),  // blue-teal
+    (0.3, 1.0, 0.5, 0.6, 4.5, 0.8, 0.38),  // pale green
+    (0.8, 0.2, 0.8, 1.2, 3.2, 6.3, 0.22),  // magenta
+];
+
+/// Color temperature ramp: oxygen green, then pink, then violet.
+const GREEN: (f64, f64, f64) = (0.1, 1.0, 0.4);
+const PINK: (f64, f64, f64) = (0.9, 0.3, 0.6);
+const VIOLET: (f64, f64, f64) = (0.6, 0.2, 0.9);
+
 pub struct Aurora {
     width: u32,
     height: u32,
     speed: f64,
     intensity: f64,
+    /// Number of curtains drawn
+    bands: f64,
+    /// 0 keeps each curtain's own color; higher pulls them all along the
+    /// green, pink, violet ramp
+    temperature: f64,
 }
 
 impl Aurora {
@@ -15,14 +40,30 @@ impl Aurora {
             height: 0,
             speed: 1.0,
             intensity: 1.0,
+            bands: 4.0,
+            temperature: 0.0,
         }
     }
 
-    /// Deterministic hash for star placement based on position.
-    fn star_hash(x: u32, y: u32) -> u32 {
-        let mut h = x.wrapping_mul(374761393).wrapping_add(y.wrapping_mul(668265263));
-        h = (h ^ (h >> 13)).wrapping_mul(1274126177);
-        h ^ (h >> 16)
+    /// Curtain colors after the temperature shift.
+    fn tinted_curtains(&self, count: usize) -> Vec<(f64, f64, f64)> {
+        let temp = self.temperature.clamp(0.0, 1.0);
+        let lerp = |a: (f64, f64, f64), b: (f64, f64, f64), f: f64| {
+            (
+                a.0 + (b.0 - a.0) * f,
+                a.1 + (b.1 - a.1) * f,
+                a.2 + (b.2 - a.2) * f,
+            )
+        };
+        let target = if temp < 0.5 {
+            lerp(GREEN, PINK, temp * 2.0)
+        } else {
+            lerp(PINK, VIOLET, temp * 2.0 - 1.0)
+        };
+        CURTAINS[..count]
+            .iter()
+            .map(|&(r, g, b, ..)| lerp((r, g, b), target, temp))
+            .collect()
     }
 }
 
@@ -31,6 +72,15 @@ impl Effect for Aurora {
         "Aurora Borealis"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
+    fn min_color_depth(&self) -> ColorDepth {
+        // Faint curtain gradients band into blocks with fewer colors
+        ColorDepth::TrueColor
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -45,15 +95,10 @@ impl Effect for Aurora {
         let wf = w as f64;
         let hf = h as f64;
         let t = t * self.speed;
-        let intensity = self.intensity;
-
-        // Curtain layer definitions: (base_color_r, g, b, speed_mult, x_freq, x_offset, drop_center)
-        let curtains: [(f64, f64, f64, f64, f64, f64, f64); 4] = [
-            (0.1, 1.0, 0.4, 1.0, 3.0, 0.0, 0.35),   // green
-            (0.0, 0.8, 0.7, 0.7, 2.5, 1.5, 0.30),    // teal
-            (0.6, 0.2, 0.9, 1.3, 4.0, 3.0, 0.25),    // purple
-            (0.9, 0.3, 0.6, 0.5, 2.0, 5.0, 0.40),    // pink
-        ];
+        let count = (self.bands.round() as usize).clamp(1, CURTAINS.len());
+        let colors = self.tinted_curtains(count);
+        // Past the default four, each curtain dims so the sky doesn't wash out
+        let intensity = self.intensity * (4.0 / count as f64).sqrt().min(1.0);
 
         for y in 0..h {
             let yf = y as f64 / hf;
@@ -70,9 +115,10 @@ impl Effect for Aurora {
                 let mut g = bg_g;
                 let mut b = bg_b;
 
-                // Deterministic stars
-                let sh = Self::star_hash(x, y);
-                if sh % 397 == 0 {
+                // Deterministic stars, about one pixel in 400
+                let sh = rng::hash_cell(x, y);
+                let star_roll = sh % 397;
+                if star_roll == 0 {
                     let brightness = 100.0 + ((sh % 156) as f64);
                     r = brightness;
                     g = brightness;
@@ -80,7 +126,9 @@ impl Effect for Aurora {
                 }
 
                 // Aurora curtain layers (additive)
-                for &(cr, cg, cb, spd, freq, off, drop_center) in &curtains {
+                for (&(_, _, _, spd, freq, off, drop_center), &(cr, cg, cb)) in
+                    CURTAINS.iter().zip(&colors)
+                {
                     let tt = t * spd;
 
                     // Horizontal wave displacement for curtain position
@@ -144,6 +192,18 @@ impl Effect for Aurora {
                 max: 2.0,
                 value: self.intensity,
             },
+            ParamDesc {
+                name: "bands".to_string(),
+                min: 1.0,
+                max: 8.0,
+                value: self.bands,
+            },
+            ParamDesc {
+                name: "temperature".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.temperature,
+            },
         ]
     }
 
@@ -151,6 +211,8 @@ impl Effect for Aurora {
         match name {
             "speed" => self.speed = value,
             "intensity" => self.intensity = value,
+            "bands" => self.bands = value,
+            "temperature" => self.temperature = value,
             _ => {}
         }
     }
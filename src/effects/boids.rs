@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use rand::Rng;
 use rand::rngs::StdRng;
 
@@ -37,6 +39,14 @@ impl Effect for Boids {
         "Boids"
     }
 
+    fn wants_persistence(&self) -> Option<f64> {
+        Some(0.92)
+    }
+
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -50,6 +60,23 @@ impl Effect for Boids {
         });
     }
 
+    fn on_resize(&mut self, width: u32, height: u32, rng: &mut StdRng) {
+        if self.width == 0 || self.height == 0 || self.boids.is_empty() {
+            self.init(width, height);
+            self.randomize_init(rng);
+            return;
+        }
+        // Keep the flock where it was, relative to the new bounds
+        let sx = width as f64 / self.width as f64;
+        let sy = height as f64 / self.height as f64;
+        for boid in &mut self.boids {
+            boid.x = (boid.x * sx).clamp(0.0, (width as f64 - 1.0).max(0.0));
+            boid.y = (boid.y * sy).clamp(0.0, (height as f64 - 1.0).max(0.0));
+        }
+        self.width = width;
+        self.height = height;
+    }
+
     fn randomize_init(&mut self, rng: &mut StdRng) {
         let wf = self.width as f64;
         let hf = self.height as f64;
@@ -74,12 +101,8 @@ impl Effect for Boids {
         let dt = dt * self.speed;
         let n = self.boids.len();
 
-        // Fade existing pixels for trails
-        for p in pixels.iter_mut() {
-            p.0 = p.0.saturating_sub(8);
-            p.1 = p.1.saturating_sub(8);
-            p.2 = p.2.saturating_sub(10);
-        }
+        // Trails come from the sequencer's persistence pass
+        pixels.fill((0, 0, 0));
 
         // Compute flocking forces
         // For performance, use a simple O(n²) with early distance rejection
@@ -199,32 +222,13 @@ impl Effect for Boids {
 
         // Draw boids
         for boid in &self.boids {
-            let ix = boid.x as i32;
-            let iy = boid.y as i32;
-
             // Color based on velocity direction + base hue
             let angle = boid.vy.atan2(boid.vx);
             let hue = (boid.hue + angle / std::f64::consts::TAU + t * 0.05) % 1.0;
             let speed = (boid.vx * boid.vx + boid.vy * boid.vy).sqrt();
             let brightness = (0.5 + speed / max_speed * 0.5).clamp(0.5, 1.0);
-            let (cr, cg, cb) = hsv_to_rgb(hue.abs(), 0.85, brightness);
-
-            // Draw 2x2 pixel dot
-            for dy in 0..2 {
-                for dx in 0..2 {
-                    let px = ix + dx;
-                    let py = iy + dy;
-                    if px >= 0 && px < w as i32 && py >= 0 && py < h as i32 {
-                        let idx = (py as u32 * w + px as u32) as usize;
-                        if idx < pixels.len() {
-                            let p = &mut pixels[idx];
-                            p.0 = p.0.max(cr);
-                            p.1 = p.1.max(cg);
-                            p.2 = p.2.max(cb);
-                        }
-                    }
-                }
-            }
+            let color = hsv_to_rgb(hue, 0.85, brightness);
+            splat::filled_circle(pixels, w, h, (boid.x, boid.y), 1.2, color);
         }
     }
 
@@ -253,20 +257,3 @@ impl Effect for Boids {
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 use std::f64::consts::PI;
 
@@ -6,6 +7,8 @@ pub struct BumpMapping {
     height: u32,
     light_speed: f64,
     texture_scale: f64,
+    /// Specular sharpness in 0–1; 0 disables the highlight pass
+    shininess: f64,
     heightmap: Vec<f64>,
 }
 
@@ -16,6 +19,7 @@ impl BumpMapping {
             height: 0,
             light_speed: 1.0,
             texture_scale: 1.0,
+            shininess: 0.0,
             heightmap: Vec::new(),
         }
     }
@@ -65,7 +69,8 @@ impl Effect for BumpMapping {
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width as usize;
         let h = self.height as usize;
-        if w < 2 || h < 2 || self.heightmap.is_empty() {
+        // The lighting pass needs at least one interior pixel to copy outward
+        if w < 3 || h < 3 || self.heightmap.is_empty() {
             return;
         }
 
@@ -82,6 +87,12 @@ impl Effect for BumpMapping {
         let light1_x = lx1 * w as f64;
         let light1_y = ly1 * h as f64;
 
+        // Blinn-Phong exponent, 2..256 across the param range
+        let spec_on = self.shininess > 0.0;
+        let exponent = 2.0f64.powf(1.0 + self.shininess * 7.0);
+        // Lights hover above the surface, viewer looks straight down
+        let light_z = (w.min(h) as f64 * 0.3).max(4.0);
+
         // Base color palette (warm copper/gold tones)
         let base_hue = (t * 0.03) % 1.0;
 
@@ -131,19 +142,32 @@ impl Effect for BumpMapping {
                 let b = ((bb as f64 + light0 * 80.0 + light1 * 200.0) * brightness.min(1.0))
                     .clamp(0.0, 255.0) as u8;
 
-                pixels[idx] = (r, g, b);
+                if spec_on {
+                    let s0 = specular(nx, ny, ldx0, ldy0, light_z, exponent) * atten0;
+                    let s1 = specular(nx, ny, ldx1, ldy1, light_z, exponent) * atten1;
+                    let add = |c: u8, k0: f64, k1: f64| {
+                        (c as f64 + s0 * k0 + s1 * k1).clamp(0.0, 255.0) as u8
+                    };
+                    pixels[idx] = (
+                        add(r, 255.0, 200.0),
+                        add(g, 230.0, 230.0),
+                        add(b, 190.0, 255.0),
+                    );
+                } else {
+                    pixels[idx] = (r, g, b);
+                }
             }
         }
 
-        // Fill edges (skipped by the 1..h-1 loop)
-        for x in 0..w {
-            pixels[x] = pixels[w + x.min(w - 2).max(1)];
-            pixels[(h - 1) * w + x] = pixels[(h - 2) * w + x.min(w - 2).max(1)];
-        }
-        for y in 0..h {
+        // Fill edges (skipped by the lighting pass) from the nearest lit pixel:
+        // side columns from their row neighbor, then whole top/bottom rows
+        // from the row beside them, which carries the corners along.
+        for y in 1..h - 1 {
             pixels[y * w] = pixels[y * w + 1];
             pixels[y * w + w - 1] = pixels[y * w + w - 2];
         }
+        pixels.copy_within(w..2 * w, 0);
+        pixels.copy_within((h - 2) * w..(h - 1) * w, (h - 1) * w);
     }
 
     fn params(&self) -> Vec<ParamDesc> {
@@ -160,12 +184,19 @@ impl Effect for BumpMapping {
                 max: 4.0,
                 value: self.texture_scale,
             },
+            ParamDesc {
+                name: "shininess".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.shininess,
+            },
         ]
     }
 
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "light_speed" => self.light_speed = value,
+            "shininess" => self.shininess = value,
             "texture_scale" => {
                 self.texture_scale = value;
                 if self.width > 0 && self.height > 0 {
@@ -177,19 +208,16 @@ impl Effect for BumpMapping {
     }
 }
 
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+/// Blinn-Phong highlight for a bump normal `(nx, ny, 1)` lit from offset
+/// `(lx, ly, lz)`, viewed from straight above.
+fn specular(nx: f64, ny: f64, lx: f64, ly: f64, lz: f64, exponent: f64) -> f64 {
+    // Steepen the normals so the shallow ripples catch distinct highlights
+    let (nx, ny) = (nx * 4.0, ny * 4.0);
+    let n_len = (nx * nx + ny * ny + 1.0).sqrt();
+    let l_len = (lx * lx + ly * ly + lz * lz).sqrt();
+    // Half vector between the light direction and the view vector (0, 0, 1)
+    let (hx, hy, hz) = (lx / l_len, ly / l_len, lz / l_len + 1.0);
+    let h_len = (hx * hx + hy * hy + hz * hz).sqrt();
+    let n_dot_h = (nx * hx + ny * hy + hz) / (n_len * h_len);
+    n_dot_h.max(0.0).powf(exponent)
 }
@@ -1,7 +1,16 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::palette::{Palette, Stops};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+/// Trail colors from long-quiet cells up to ones firing right now.
+const AGE_STOPS: &Stops = &[
+    (0, (5, 5, 20)),
+    (90, (60, 10, 90)),
+    (180, (230, 100, 30)),
+    (255, (200, 255, 255)),
+];
+
 /// Cell states for Brian's Brain automaton
 #[derive(Clone, Copy, PartialEq)]
 enum CellState {
@@ -15,8 +24,17 @@ pub struct CellularAutomata {
     height: u32,
     speed: f64,
     density: f64,
+    /// Steps a fired cell keeps glowing as it fades; 0 shows the plain
+    /// three cell states
+    trail: f64,
     grid: Vec<CellState>,
     next_grid: Vec<CellState>,
+    /// Steps since each cell last fired, 0 while it is on. In Brian's Brain
+    /// a cell is on for exactly one step, so an age counted while alive
+    /// would only ever read 0 or 1; counting up since the last firing is
+    /// what lets moving patterns leave a fading history behind them.
+    age: Vec<u16>,
+    age_colors: Palette,
     tick_accum: f64,
     rng: StdRng,
 }
@@ -28,8 +46,11 @@ impl CellularAutomata {
             height: 0,
             speed: 1.0,
             density: 0.3,
+            trail: 12.0,
             grid: Vec::new(),
             next_grid: Vec::new(),
+            age: Vec::new(),
+            age_colors: Palette::from_stops(AGE_STOPS),
             tick_accum: 0.0,
             rng: StdRng::seed_from_u64(0),
         }
@@ -47,6 +68,11 @@ impl CellularAutomata {
             })
             .collect();
         self.next_grid = vec![CellState::Off; size];
+        self.age = self
+            .grid
+            .iter()
+            .map(|&cell| if cell == CellState::On { 0 } else { u16::MAX })
+            .collect();
     }
 
     fn step(&mut self) {
@@ -87,6 +113,13 @@ impl CellularAutomata {
         }
 
         std::mem::swap(&mut self.grid, &mut self.next_grid);
+        for (age, &cell) in self.age.iter_mut().zip(&self.grid) {
+            *age = if cell == CellState::On {
+                0
+            } else {
+                age.saturating_add(1)
+            };
+        }
     }
 }
 
@@ -95,6 +128,10 @@ impl Effect for CellularAutomata {
         "CellularAutomata"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -122,6 +159,15 @@ impl Effect for CellularAutomata {
             self.step();
         }
 
+        if self.trail >= 1.0 {
+            // Fade each cell through the palette by how long ago it fired
+            let span = self.trail + 1.0;
+            for (px, &age) in pixels.iter_mut().zip(&self.age) {
+                *px = self.age_colors.lookup(1.0 - age as f64 / span);
+            }
+            return;
+        }
+
         // Render
         for i in 0..pixels.len().min(self.grid.len()) {
             pixels[i] = match self.grid[i] {
@@ -146,12 +192,19 @@ impl Effect for CellularAutomata {
                 max: 0.5,
                 value: self.density,
             },
+            ParamDesc {
+                name: "trail".to_string(),
+                min: 0.0,
+                max: 60.0,
+                value: self.trail,
+            },
         ]
     }
 
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "speed" => self.speed = value,
+            "trail" => self.trail = value,
             "density" => {
                 self.density = value;
                 // Re-seed if density changes and we have dimensions
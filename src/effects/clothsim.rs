@@ -1,10 +1,16 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
 
 const CLOTH_W: usize = 40;
 const CLOTH_H: usize = 30;
 const REST_DIST: f64 = 1.0;
 const CONSTRAINT_ITERS: usize = 4;
 const SUB_STEPS: usize = 3;
+const EDGE_EPS: f64 = 1e-9;
+/// Links are numbered horizontal first, then vertical.
+const H_LINKS: usize = (CLOTH_W - 1) * CLOTH_H;
+const LINKS: usize = H_LINKS + CLOTH_W * (CLOTH_H - 1);
 
 #[derive(Clone, Copy)]
 struct Particle {
@@ -22,7 +28,17 @@ pub struct ClothSim {
     height: u32,
     wind: f64,
     gravity: f64,
+    /// Pin layout: 0 = whole top edge, 1 = top corners, 2 = middle of the top
+    pins: f64,
+    /// 0 disables tearing; higher values break links at smaller stretches
+    tear: f64,
+    /// Radius of the moving sphere the cloth drapes over; 0 removes it
+    sphere_radius: f64,
+    /// Sphere center in cloth space, animated in `update`
+    sphere: (f64, f64, f64),
     particles: Vec<Particle>,
+    /// Per-link flag, set once a link has been stretched past breaking
+    broken: Vec<bool>,
 }
 
 impl ClothSim {
@@ -32,7 +48,12 @@ impl ClothSim {
             height: 0,
             wind: 1.0,
             gravity: 1.0,
+            pins: 0.0,
+            tear: 0.65,
+            sphere_radius: 6.0,
+            sphere: (0.0, 0.0, 0.0),
             particles: Vec::new(),
+            broken: Vec::new(),
         }
     }
 
@@ -40,18 +61,77 @@ impl ClothSim {
         cy * CLOTH_W + cx
     }
 
+    /// Link between (cx, cy) and its right neighbor.
+    fn h_link(cx: usize, cy: usize) -> usize {
+        cy * (CLOTH_W - 1) + cx
+    }
+
+    /// Link between (cx, cy) and the particle below it.
+    fn v_link(cx: usize, cy: usize) -> usize {
+        H_LINKS + cy * CLOTH_W + cx
+    }
+
+    /// Where the particle at grid position (cx, cy) hangs at rest.
+    fn rest_position(cx: usize, cy: usize) -> (f64, f64) {
+        (
+            (cx as f64 - CLOTH_W as f64 / 2.0) * REST_DIST,
+            (cy as f64 - CLOTH_H as f64 / 4.0) * REST_DIST,
+        )
+    }
+
+    fn is_pinned(&self, cx: usize, cy: usize) -> bool {
+        if cy != 0 {
+            return false;
+        }
+        match self.pins.round() as i32 {
+            1 => cx == 0 || cx == CLOTH_W - 1,
+            2 => (CLOTH_W / 3..2 * CLOTH_W / 3).contains(&cx),
+            _ => true,
+        }
+    }
+
+    /// Re-pin the top edge for the current layout; newly pinned particles
+    /// snap back to their rest position.
+    fn apply_pins(&mut self) {
+        for cx in 0..CLOTH_W {
+            let pinned = self.is_pinned(cx, 0);
+            let p = &mut self.particles[Self::particle_idx(cx, 0)];
+            if pinned && !p.pinned {
+                let (x, y) = Self::rest_position(cx, 0);
+                *p = Particle {
+                    x,
+                    y,
+                    z: 0.0,
+                    prev_x: x,
+                    prev_y: y,
+                    prev_z: 0.0,
+                    pinned,
+                };
+            }
+            p.pinned = pinned;
+        }
+    }
+
+    /// Stretch, as a multiple of the rest length, past which a link breaks.
+    fn tear_limit(&self) -> Option<f64> {
+        if self.tear > 0.0 {
+            // Squared so the top of the range, where taut cloth starts
+            // ripping, gets finer control
+            Some(1.0 + 0.5 * (1.0 - self.tear).powi(2))
+        } else {
+            None
+        }
+    }
+
     fn reset_cloth(&mut self) {
         self.particles.clear();
         self.particles.reserve(CLOTH_W * CLOTH_H);
 
         for cy in 0..CLOTH_H {
             for cx in 0..CLOTH_W {
-                let x = (cx as f64 - CLOTH_W as f64 / 2.0) * REST_DIST;
-                let y = (cy as f64 - CLOTH_H as f64 / 4.0) * REST_DIST;
+                let (x, y) = Self::rest_position(cx, cy);
                 let z = 0.0;
-
-                // Pin top-left and top-right corners, plus a few points along the top
-                let pinned = cy == 0 && (cx == 0 || cx == CLOTH_W - 1 || cx == CLOTH_W / 3 || cx == 2 * CLOTH_W / 3);
+                let pinned = self.is_pinned(cx, cy);
 
                 self.particles.push(Particle {
                     x,
@@ -64,6 +144,7 @@ impl ClothSim {
                 });
             }
         }
+        self.broken = vec![false; LINKS];
     }
 
     fn simulate(&mut self, t: f64, dt: f64) {
@@ -106,6 +187,7 @@ impl ClothSim {
                 for cy in 0..CLOTH_H {
                     for cx in 0..CLOTH_W - 1 {
                         self.satisfy_constraint(
+                            Self::h_link(cx, cy),
                             Self::particle_idx(cx, cy),
                             Self::particle_idx(cx + 1, cy),
                             REST_DIST,
@@ -116,6 +198,7 @@ impl ClothSim {
                 for cy in 0..CLOTH_H - 1 {
                     for cx in 0..CLOTH_W {
                         self.satisfy_constraint(
+                            Self::v_link(cx, cy),
                             Self::particle_idx(cx, cy),
                             Self::particle_idx(cx, cy + 1),
                             REST_DIST,
@@ -123,10 +206,35 @@ impl ClothSim {
                     }
                 }
             }
+            self.collide_sphere();
+        }
+    }
+
+    /// Push particles that ended up inside the sphere out to its surface.
+    fn collide_sphere(&mut self) {
+        if self.sphere_radius <= 0.0 {
+            return;
+        }
+        // Half a link of margin keeps flat quads between particles from
+        // cutting into the sphere
+        let r = self.sphere_radius + REST_DIST * 0.5;
+        let (sx, sy, sz) = self.sphere;
+        for p in self.particles.iter_mut().filter(|p| !p.pinned) {
+            let (dx, dy, dz) = (p.x - sx, p.y - sy, p.z - sz);
+            let dist = (dx * dx + dy * dy + dz * dz).sqrt();
+            if dist < r && dist > 0.0001 {
+                let push = r / dist;
+                p.x = sx + dx * push;
+                p.y = sy + dy * push;
+                p.z = sz + dz * push;
+            }
         }
     }
 
-    fn satisfy_constraint(&mut self, i: usize, j: usize, rest: f64) {
+    fn satisfy_constraint(&mut self, link: usize, i: usize, j: usize, rest: f64) {
+        if self.broken[link] {
+            return;
+        }
         let pi = self.particles[i];
         let pj = self.particles[j];
 
@@ -138,6 +246,14 @@ impl ClothSim {
         if dist < 0.0001 {
             return;
         }
+        if let Some(limit) = self.tear_limit() {
+            // Weak spots give way first, so rips start locally and spread
+            let strength = 0.8 + 0.4 * rng::hash_u(link as u32);
+            if dist > rest * (1.0 + (limit - 1.0) * strength) {
+                self.broken[link] = true;
+                return;
+            }
+        }
 
         let diff = (rest - dist) / dist;
         let offset_x = dx * diff * 0.5;
@@ -157,26 +273,13 @@ impl ClothSim {
     }
 }
 
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let h = ((h % 1.0) + 1.0) % 1.0;
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    (
-        (r * 255.0) as u8,
-        (g * 255.0) as u8,
-        (b * 255.0) as u8,
-    )
+/// Light direction (normalized), shared by the cloth and the sphere.
+fn light_dir() -> (f64, f64, f64) {
+    let light_x: f64 = -0.4;
+    let light_y: f64 = -0.6;
+    let light_z: f64 = -0.7;
+    let light_len = (light_x * light_x + light_y * light_y + light_z * light_z).sqrt();
+    (light_x / light_len, light_y / light_len, light_z / light_len)
 }
 
 impl Effect for ClothSim {
@@ -184,6 +287,10 @@ impl Effect for ClothSim {
         "Cloth Simulation"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -211,27 +318,48 @@ impl Effect for ClothSim {
 
         // Clamp dt to avoid simulation explosion
         let sim_dt = dt.min(0.033);
+        // The sphere swings side to side behind the cloth, where the wind
+        // presses the fabric onto it
+        self.sphere = (
+            (t * 0.6).sin() * CLOTH_W as f64 * 0.25,
+            CLOTH_H as f64 * 0.35,
+            self.sphere_radius + 1.0 + 1.5 * (t * 0.9).sin(),
+        );
         self.simulate(t, sim_dt);
 
         let cx = w as f64 / 2.0;
         let cy = h as f64 / 2.0;
         let scale = (cx.min(cy)) / (CLOTH_W as f64 * REST_DIST * 0.6);
 
-        // Light direction (normalized)
-        let light_x: f64 = -0.4;
-        let light_y: f64 = -0.6;
-        let light_z: f64 = -0.7;
-        let light_len = (light_x * light_x + light_y * light_y + light_z * light_z).sqrt();
-        let lx = light_x / light_len;
-        let ly = light_y / light_len;
-        let lz = light_z / light_len;
+        let (lx, ly, lz) = light_dir();
 
         // Z-buffer for proper depth handling
         let mut zbuf = vec![f64::MAX; (w * h) as usize];
 
+        if self.sphere_radius > 0.0 {
+            let (sx, sy, sz) = self.sphere;
+            draw_sphere(
+                pixels,
+                &mut zbuf,
+                w,
+                h,
+                (cx + sx * scale, cy + sy * scale, sz),
+                self.sphere_radius,
+                scale,
+            );
+        }
+
         // Render each grid cell as a filled quad
         for cy_idx in 0..CLOTH_H - 1 {
             for cx_idx in 0..CLOTH_W - 1 {
+                // A quad with any torn edge leaves a hole
+                if self.broken[Self::h_link(cx_idx, cy_idx)]
+                    || self.broken[Self::h_link(cx_idx, cy_idx + 1)]
+                    || self.broken[Self::v_link(cx_idx, cy_idx)]
+                    || self.broken[Self::v_link(cx_idx + 1, cy_idx)]
+                {
+                    continue;
+                }
                 let p00 = self.particles[Self::particle_idx(cx_idx, cy_idx)];
                 let p10 = self.particles[Self::particle_idx(cx_idx + 1, cy_idx)];
                 let p01 = self.particles[Self::particle_idx(cx_idx, cy_idx + 1)];
@@ -271,11 +399,9 @@ impl Effect for ClothSim {
 
                 let (cr, cg, cb) = hsv_to_rgb(hue, sat, diffuse);
 
-                // Project quad corners to screen
-                let avg_z = (p00.z + p10.z + p01.z + p11.z) / 4.0;
-
-                let screen = |p: &Particle| -> (f64, f64) {
-                    (cx + p.x * scale, cy + p.y * scale)
+                // Project quad corners to screen, keeping depth per vertex
+                let screen = |p: &Particle| -> (f64, f64, f64) {
+                    (cx + p.x * scale, cy + p.y * scale, p.z)
                 };
 
                 let s00 = screen(&p00);
@@ -290,7 +416,6 @@ impl Effect for ClothSim {
                     w,
                     h,
                     [s00, s10, s01],
-                    avg_z,
                     (cr, cg, cb),
                 );
                 fill_triangle_zbuf(
@@ -299,7 +424,6 @@ impl Effect for ClothSim {
                     w,
                     h,
                     [s10, s11, s01],
-                    avg_z,
                     (cr, cg, cb),
                 );
             }
@@ -320,6 +444,24 @@ impl Effect for ClothSim {
                 max: 2.0,
                 value: self.gravity,
             },
+            ParamDesc {
+                name: "pins".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.pins,
+            },
+            ParamDesc {
+                name: "tear".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.tear,
+            },
+            ParamDesc {
+                name: "sphere".to_string(),
+                min: 0.0,
+                max: 10.0,
+                value: self.sphere_radius,
+            },
         ]
     }
 
@@ -327,6 +469,14 @@ impl Effect for ClothSim {
         match name {
             "wind" => self.wind = value,
             "gravity" => self.gravity = value,
+            "pins" => {
+                self.pins = value;
+                if !self.particles.is_empty() {
+                    self.apply_pins();
+                }
+            }
+            "tear" => self.tear = value,
+            "sphere" => self.sphere_radius = value,
             _ => {}
         }
     }
@@ -337,8 +487,7 @@ fn fill_triangle_zbuf(
     zbuf: &mut [f64],
     w: u32,
     h: u32,
-    verts: [(f64, f64); 3],
-    z: f64,
+    verts: [(f64, f64, f64); 3],
     color: (u8, u8, u8),
 ) {
     let min_y = verts[0].1.min(verts[1].1).min(verts[2].1).max(0.0) as i32;
@@ -365,9 +514,12 @@ fn fill_triangle_zbuf(
             let w1 = ((v2.1 - v0.1) * (px - v2.0) + (v0.0 - v2.0) * (py - v2.1)) * inv_denom;
             let w2 = 1.0 - w0 - w1;
 
-            if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
+            // Small tolerance so pixels on a shared edge belong to both triangles
+            if w0 >= -EDGE_EPS && w1 >= -EDGE_EPS && w2 >= -EDGE_EPS {
                 let idx = (y as u32 * w + x as u32) as usize;
-                if idx < pixels.len() && z < zbuf[idx] {
+                let z = w0 * v0.2 + w1 * v1.2 + w2 * v2.2;
+                // Ties go to the later triangle so shared edges never drop out
+                if idx < pixels.len() && z <= zbuf[idx] {
                     zbuf[idx] = z;
                     pixels[idx] = color;
                 }
@@ -375,3 +527,50 @@ fn fill_triangle_zbuf(
         }
     }
 }
+
+/// Shaded sphere with the given screen-space center (depth in cloth units)
+/// and cloth-space radius, depth-tested against the cloth.
+fn draw_sphere(
+    pixels: &mut [(u8, u8, u8)],
+    zbuf: &mut [f64],
+    w: u32,
+    h: u32,
+    center: (f64, f64, f64),
+    radius: f64,
+    scale: f64,
+) {
+    let light = light_dir();
+    let (scx, scy, sz) = center;
+    let r_px = radius * scale;
+    let min_y = (scy - r_px).floor().max(0.0) as u32;
+    let max_y = ((scy + r_px).ceil().max(0.0) as u32).min(h);
+    let min_x = (scx - r_px).floor().max(0.0) as u32;
+    let max_x = ((scx + r_px).ceil().max(0.0) as u32).min(w);
+
+    for y in min_y..max_y {
+        let ny = (y as f64 + 0.5 - scy) / r_px;
+        for x in min_x..max_x {
+            let nx = (x as f64 + 0.5 - scx) / r_px;
+            let d2 = nx * nx + ny * ny;
+            if d2 >= 1.0 {
+                continue;
+            }
+            // The viewer looks along +z, so the visible surface faces -z
+            let nz = -(1.0 - d2).sqrt();
+            let z = sz + nz * radius;
+            let idx = (y * w + x) as usize;
+            if z > zbuf[idx] {
+                continue;
+            }
+            zbuf[idx] = z;
+            let diffuse = (nx * light.0 + ny * light.1 + nz * light.2).max(0.0);
+            let spec = diffuse.powi(24);
+            let v = 0.15 + diffuse * 0.6;
+            pixels[idx] = (
+                ((v * 0.8 + spec) * 255.0).min(255.0) as u8,
+                ((v * 0.85 + spec) * 255.0).min(255.0) as u8,
+                ((v + spec) * 255.0).min(255.0) as u8,
+            );
+        }
+    }
+}
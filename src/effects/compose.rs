@@ -0,0 +1,243 @@
+use rand::rngs::StdRng;
+
+use crate::camera::CameraPath;
+use crate::effect::{Effect, ParamDesc};
+use crate::framebuffer::{self, ColorDepth, Persistence, Rgba};
+
+/// How the second layer of a `Compose` combines with the first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    /// Sum, clipped to white
+    Add,
+    /// Product, so dark in either layer stays dark
+    Multiply,
+    /// Inverted product of inverses, so light in either layer shows through
+    Screen,
+    /// Plain crossfade toward the second layer
+    Alpha,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 4] = [
+        BlendMode::Add,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Alpha,
+    ];
+
+    /// Look up a blend mode by its variant name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|mode| format!("{:?}", mode).eq_ignore_ascii_case(name))
+    }
+
+    fn blend(self, a: u8, b: u8) -> u8 {
+        let (a, b) = (a as u32, b as u32);
+        match self {
+            BlendMode::Add => (a + b).min(255) as u8,
+            BlendMode::Multiply => (a * b / 255) as u8,
+            BlendMode::Screen => (255 - (255 - a) * (255 - b) / 255) as u8,
+            BlendMode::Alpha => b as u8,
+        }
+    }
+}
+
+/// One child effect and the frame it draws into.
+struct Layer {
+    effect: Box<dyn Effect>,
+    /// The frame drawn opaque, or flattened over black when it has alpha
+    frame: Vec<(u8, u8, u8)>,
+    /// The frame with its transparency, for children that draw one
+    rgba: Vec<Rgba>,
+    /// Whether the child drew `rgba` this frame
+    has_alpha: bool,
+    /// Trails for children that ask for them, as the sequencer would draw
+    trail: Persistence,
+}
+
+impl Layer {
+    fn new(effect: Box<dyn Effect>) -> Self {
+        Self {
+            effect,
+            frame: Vec::new(),
+            rgba: Vec::new(),
+            has_alpha: false,
+            trail: Persistence::default(),
+        }
+    }
+
+    /// Size the layer's buffers for the frame; the trail of the old size is dropped.
+    fn allocate(&mut self, width: u32, height: u32) {
+        self.frame = vec![(0, 0, 0); (width * height) as usize];
+        self.rgba = vec![(0, 0, 0, 0); (width * height) as usize];
+        self.trail.reset();
+    }
+
+    fn init(&mut self, width: u32, height: u32) {
+        self.allocate(width, height);
+        self.effect.init(width, height);
+    }
+
+    /// Resize the buffers but let the child keep what survives a resize.
+    fn resize(&mut self, width: u32, height: u32, rng: &mut StdRng) {
+        self.allocate(width, height);
+        self.effect.on_resize(width, height, rng);
+    }
+
+    fn update(&mut self, t: f64, dt: f64) {
+        self.has_alpha = self.effect.update_rgba(t, dt, &mut self.rgba);
+        if self.has_alpha {
+            self.frame.iter_mut().for_each(|px| *px = (0, 0, 0));
+            framebuffer::flatten(&self.rgba, &mut self.frame);
+            return;
+        }
+        self.effect.update(t, dt, &mut self.frame);
+        if let Some(decay) = self.effect.wants_persistence() {
+            self.trail.apply(&mut self.frame, decay, dt);
+        }
+    }
+
+    /// Coverage of pixel `i`: its alpha, or fully opaque without one.
+    fn coverage(&self, i: usize) -> f64 {
+        if self.has_alpha {
+            self.rgba[i].3 as f64 / 255.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Two effects running side by side, blended into one scene.
+pub struct Compose {
+    name: String,
+    a: Layer,
+    b: Layer,
+    mode: BlendMode,
+    /// How strongly the blended result replaces the first layer
+    mix: f64,
+}
+
+impl Compose {
+    pub fn new(a: Box<dyn Effect>, b: Box<dyn Effect>, mode: BlendMode) -> Self {
+        Self {
+            name: format!("{} + {}", a.name(), b.name()),
+            a: Layer::new(a),
+            b: Layer::new(b),
+            mode,
+            // A full alpha mix would hide the first layer entirely
+            mix: if mode == BlendMode::Alpha { 0.5 } else { 1.0 },
+        }
+    }
+}
+
+impl Effect for Compose {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn preferred_duration(&self) -> f64 {
+        self.a
+            .effect
+            .preferred_duration()
+            .max(self.b.effect.preferred_duration())
+    }
+
+    fn loop_period(&self) -> Option<f64> {
+        match (self.a.effect.loop_period(), self.b.effect.loop_period()) {
+            (Some(p), Some(q)) if p == q => Some(p),
+            _ => None,
+        }
+    }
+
+    fn min_color_depth(&self) -> ColorDepth {
+        self.a
+            .effect
+            .min_color_depth()
+            .max(self.b.effect.min_color_depth())
+    }
+
+    fn init(&mut self, width: u32, height: u32) {
+        self.a.init(width, height);
+        self.b.init(width, height);
+    }
+
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        self.a.effect.randomize_init(rng);
+        self.b.effect.randomize_init(rng);
+    }
+
+    fn on_resize(&mut self, width: u32, height: u32, rng: &mut StdRng) {
+        self.a.resize(width, height, rng);
+        self.b.resize(width, height, rng);
+    }
+
+    fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]) {
+        self.a.update(t, dt);
+        self.b.update(t, dt);
+
+        let mode = self.mode;
+        let mix = self.mix.clamp(0.0, 1.0);
+        // Where the second layer is transparent the first shows through
+        // untouched; its color there is taken before premultiplying
+        let channel = |a: u8, b: u8, cover: f64| {
+            let b = if cover > 0.0 {
+                (b as f64 / cover).min(255.0) as u8
+            } else {
+                b
+            };
+            let blended = mode.blend(a, b) as f64;
+            (a as f64 + (blended - a as f64) * mix * cover).round() as u8
+        };
+        for (i, out) in pixels.iter_mut().enumerate() {
+            let (pa, pb) = (self.a.frame[i], self.b.frame[i]);
+            let cover = self.b.coverage(i);
+            *out = (
+                channel(pa.0, pb.0, cover),
+                channel(pa.1, pb.1, cover),
+                channel(pa.2, pb.2, cover),
+            );
+        }
+    }
+
+    fn params(&self) -> Vec<ParamDesc> {
+        vec![ParamDesc {
+            name: "mix".to_string(),
+            min: 0.0,
+            max: 1.0,
+            value: self.mix,
+        }]
+    }
+
+    fn set_param(&mut self, name: &str, value: f64) {
+        if name == "mix" {
+            self.mix = value;
+        }
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.a.effect.set_text(text);
+        self.b.effect.set_text(text);
+    }
+
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.a.effect.set_camera(yaw, pitch);
+        self.b.effect.set_camera(yaw, pitch);
+    }
+
+    fn set_camera_path(&mut self, path: CameraPath) {
+        self.a.effect.set_camera_path(path.clone());
+        self.b.effect.set_camera_path(path);
+    }
+
+    fn trigger(&mut self) {
+        self.a.effect.trigger();
+        self.b.effect.trigger();
+    }
+
+    fn poke(&mut self, x: f64, y: f64) {
+        self.a.effect.poke(x, y);
+        self.b.effect.poke(x, y);
+    }
+}
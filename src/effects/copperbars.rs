@@ -31,6 +31,10 @@ impl Effect for CopperBars {
         "CopperBars"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        10.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
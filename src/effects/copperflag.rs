@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 use std::f64::consts::PI;
 
@@ -173,20 +174,3 @@ impl Effect for CopperFlag {
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let tv = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, tv, p),
-        1 => (q, v, p),
-        2 => (p, v, tv),
-        3 => (p, q, v),
-        4 => (tv, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
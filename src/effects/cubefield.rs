@@ -1,4 +1,8 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
+use rand::rngs::StdRng;
+use rand::Rng;
 
 const MAX_CUBES: usize = 80;
 const FAR_Z: f64 = 40.0;
@@ -11,6 +15,8 @@ pub struct CubeField {
     height: u32,
     speed: f64,
     density: f64,
+    /// Mixed into every hash seed; `randomize_init` picks a new layout
+    salt: u64,
 }
 
 impl CubeField {
@@ -20,42 +26,11 @@ impl CubeField {
             height: 0,
             speed: 1.0,
             density: 1.0,
+            salt: 0,
         }
     }
 }
 
-/// Deterministic pseudo-random from a seed
-fn hash_f64(seed: u64) -> f64 {
-    let mut x = seed;
-    x = x.wrapping_mul(0x517CC1B727220A95);
-    x ^= x >> 32;
-    x = x.wrapping_mul(0x6C62272E07BB0142);
-    x ^= x >> 32;
-    (x & 0x00FF_FFFF) as f64 / 0x0100_0000 as f64
-}
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let h = ((h % 1.0) + 1.0) % 1.0;
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    (
-        (r * 255.0) as u8,
-        (g * 255.0) as u8,
-        (b * 255.0) as u8,
-    )
-}
-
 /// Project a 3D point to 2D screen coordinates
 /// Returns (screen_x, screen_y, z) or None if behind camera
 fn project(
@@ -179,11 +154,19 @@ impl Effect for CubeField {
         "Cube Field"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
     }
 
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        self.salt = rng.gen();
+    }
+
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width;
         let h = self.height;
@@ -213,9 +196,9 @@ impl Effect for CubeField {
         for i in 0..num_cubes {
             let seed = i as u64;
             // Deterministic position in world space
-            let wx = (hash_f64(seed * 3 + 1) - 0.5) * 12.0;
-            let wy = (hash_f64(seed * 3 + 2) - 0.5) * 6.0;
-            let wz_base = hash_f64(seed * 3 + 3) * FAR_Z;
+            let wx = (rng::hash_f64((seed * 3 + 1) ^ self.salt) - 0.5) * 12.0;
+            let wy = (rng::hash_f64((seed * 3 + 2) ^ self.salt) - 0.5) * 6.0;
+            let wz_base = rng::hash_f64((seed * 3 + 3) ^ self.salt) * FAR_Z;
 
             // Repeat cubes along Z
             let wz_rel = ((wz_base - cam_z) % FAR_Z + FAR_Z) % FAR_Z + NEAR_Z;
@@ -237,7 +220,7 @@ impl Effect for CubeField {
             }
 
             // Cube color
-            let hue = hash_f64(seed * 7 + 100);
+            let hue = rng::hash_f64((seed * 7 + 100) ^ self.salt);
             let (base_r, base_g, base_b) = hsv_to_rgb(hue, 0.7, 0.9);
 
             // 8 vertices of a cube centered at (rx, ry, rz)
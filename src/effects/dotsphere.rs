@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use std::f64::consts::PI;
 
 struct Point3D {
@@ -11,7 +13,17 @@ pub struct DotSphere {
     width: u32,
     height: u32,
     rot_speed: f64,
-    dot_count: u32,
+    /// Kept fractional so small param steps accumulate; the sphere uses
+    /// the rounded count
+    dot_count: f64,
+    /// Tilt of the spin axis toward the viewer, in radians
+    axis_tilt: f64,
+    /// Secondary rotation: 1 is the classic X wobble, above 1 it also rolls
+    tumble: f64,
+    /// Depth-of-field strength; 0 keeps every point sharp
+    dof: f64,
+    /// Depth of the focus plane, from the front (-1) to the back (1)
+    focus: f64,
     points: Vec<Point3D>,
 }
 
@@ -21,12 +33,20 @@ impl DotSphere {
             width: 0,
             height: 0,
             rot_speed: 1.0,
-            dot_count: 300,
+            dot_count: 300.0,
+            axis_tilt: 0.0,
+            tumble: 1.0,
+            dof: 0.0,
+            focus: 0.0,
             points: Vec::new(),
         }
     }
 
-    fn generate_points(count: u32) -> Vec<Point3D> {
+    fn point_count(&self) -> usize {
+        self.dot_count.round() as usize
+    }
+
+    fn generate_points(count: usize) -> Vec<Point3D> {
         // Fibonacci spiral for even distribution on sphere
         let golden_ratio = (1.0 + 5.0_f64.sqrt()) / 2.0;
         let n = count as f64;
@@ -52,7 +72,7 @@ impl Effect for DotSphere {
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
-        self.points = Self::generate_points(self.dot_count);
+        self.points = Self::generate_points(self.point_count());
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -69,19 +89,22 @@ impl Effect for DotSphere {
 
         let t_scaled = t * self.rot_speed;
         let angle_y = t_scaled * 0.6;
-        let angle_x = t_scaled * 0.4;
+        let angle_x = self.axis_tilt + t_scaled * 0.4 * self.tumble.min(1.0);
+        let angle_z = t_scaled * 0.25 * (self.tumble - 1.0).max(0.0);
 
         let cos_y = angle_y.cos();
         let sin_y = angle_y.sin();
         let cos_x = angle_x.cos();
         let sin_x = angle_x.sin();
+        let cos_z = angle_z.cos();
+        let sin_z = angle_z.sin();
 
         let cx = w as f64 / 2.0;
         let cy = h as f64 / 2.0;
         let radius = cx.min(cy) * 0.7;
 
         for point in &self.points {
-            // Rotate Y then X
+            // Rotate Y, then X, then roll around the view axis
             let x1 = point.x * cos_y + point.z * sin_y;
             let z1 = -point.x * sin_y + point.z * cos_y;
             let y1 = point.y;
@@ -89,6 +112,9 @@ impl Effect for DotSphere {
             let y2 = y1 * cos_x - z1 * sin_x;
             let z2 = y1 * sin_x + z1 * cos_x;
 
+            let x3 = x1 * cos_z - y2 * sin_z;
+            let y3 = x1 * sin_z + y2 * cos_z;
+
             // Back-face cull
             if z2 < -0.1 {
                 continue;
@@ -97,8 +123,8 @@ impl Effect for DotSphere {
             // Perspective projection
             let camera_z = 3.0;
             let persp = camera_z / (camera_z + z2);
-            let sx = cx + x1 * radius * persp;
-            let sy = cy + y2 * radius * persp;
+            let sx = cx + x3 * radius * persp;
+            let sy = cy + y3 * radius * persp;
 
             // Brightness by z (closer = brighter)
             let brightness = (0.3 + z2 * 0.7).clamp(0.2, 1.0);
@@ -109,6 +135,14 @@ impl Effect for DotSphere {
 
             // Dot size 1–2 px based on depth
             let dot_size = if z2 > 0.5 { 2 } else { 1 };
+            if self.dof > 0.0 {
+                // The sphere's visible side spans z from 0 to 1
+                let focus = self.focus * 0.5 + 0.5;
+                let blur = splat::defocus(z2, focus, self.dof, radius);
+                let center = (sx + 0.5 * dot_size as f64, sy + 0.5 * dot_size as f64);
+                splat::soft_dot(pixels, w, h, center, dot_size as f64 * 0.7, blur, (cr, cg, cb));
+                continue;
+            }
 
             for dy in 0..dot_size {
                 for dx in 0..dot_size {
@@ -138,9 +172,33 @@ impl Effect for DotSphere {
             },
             ParamDesc {
                 name: "dot_count".to_string(),
-                min: 100.0,
-                max: 600.0,
-                value: self.dot_count as f64,
+                min: 20.0,
+                max: 2000.0,
+                value: self.dot_count,
+            },
+            ParamDesc {
+                name: "axis_tilt".to_string(),
+                min: 0.0,
+                max: PI / 2.0,
+                value: self.axis_tilt,
+            },
+            ParamDesc {
+                name: "tumble".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.tumble,
+            },
+            ParamDesc {
+                name: "dof".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.dof,
+            },
+            ParamDesc {
+                name: "focus".to_string(),
+                min: -1.0,
+                max: 1.0,
+                value: self.focus,
             },
         ]
     }
@@ -149,30 +207,18 @@ impl Effect for DotSphere {
         match name {
             "rot_speed" => self.rot_speed = value,
             "dot_count" => {
-                let new_count = value as u32;
-                if new_count != self.dot_count {
-                    self.dot_count = new_count;
-                    self.points = Self::generate_points(self.dot_count);
+                self.dot_count = value;
+                // Only rebuild when the visible count changes
+                let count = self.point_count();
+                if count != self.points.len() && self.width > 0 {
+                    self.points = Self::generate_points(count);
                 }
             }
+            "axis_tilt" => self.axis_tilt = value,
+            "tumble" => self.tumble = value,
+            "dof" => self.dof = value,
+            "focus" => self.focus = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
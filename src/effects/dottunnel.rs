@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use std::f64::consts::TAU;
 
 const NUM_RINGS: usize = 32;
@@ -25,28 +27,6 @@ impl DotTunnel {
     }
 }
 
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let h = ((h % 1.0) + 1.0) % 1.0;
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    (
-        (r * 255.0) as u8,
-        (g * 255.0) as u8,
-        (b * 255.0) as u8,
-    )
-}
-
 impl Effect for DotTunnel {
     fn name(&self) -> &str {
         "Dot Tunnel"
@@ -140,36 +120,7 @@ impl Effect for DotTunnel {
             let hue = (dot.ring_idx as f64 / NUM_RINGS as f64 + t_speed * 0.05) % 1.0;
             let (cr, cg, cb) = hsv_to_rgb(hue, 0.85, brightness);
 
-            // Draw filled circle
-            let ri = radius.ceil() as i32;
-            let r_sq = radius * radius;
-
-            for dy in -ri..=ri {
-                for dx in -ri..=ri {
-                    let dist_sq = (dx * dx + dy * dy) as f64;
-                    if dist_sq > r_sq {
-                        continue;
-                    }
-
-                    let px = dot.sx as i32 + dx;
-                    let py = dot.sy as i32 + dy;
-
-                    if px >= 0 && px < w as i32 && py >= 0 && py < h as i32 {
-                        let idx = (py as u32 * w + px as u32) as usize;
-                        // Soft edge: fade at the border of the dot
-                        let edge = 1.0 - (dist_sq / r_sq).sqrt();
-                        let edge = edge.clamp(0.0, 1.0);
-
-                        let existing = pixels[idx];
-                        // Additive-ish blend: take max to make dots glow
-                        pixels[idx] = (
-                            existing.0.max((cr as f64 * edge) as u8),
-                            existing.1.max((cg as f64 * edge) as u8),
-                            existing.2.max((cb as f64 * edge) as u8),
-                        );
-                    }
-                }
-            }
+            splat::filled_circle(pixels, w, h, (dot.sx, dot.sy), radius, (cr, cg, cb));
         }
 
         // Add subtle center glow
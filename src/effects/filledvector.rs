@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 
 pub struct FilledVector {
@@ -5,6 +6,12 @@ pub struct FilledVector {
     height: u32,
     rot_speed: f64,
     scale: f64,
+    /// 0 = flat per-face shading, 1 = Gouraud (smooth) shading
+    shading: f64,
+    /// Per-vertex normals: the average of the adjacent face normals
+    vertex_normals: Vec<[f64; 3]>,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
 }
 
 impl FilledVector {
@@ -14,10 +21,34 @@ impl FilledVector {
             height: 0,
             rot_speed: 1.0,
             scale: 1.0,
+            shading: 0.0,
+            vertex_normals: Vec::new(),
+            look: (0.0, 0.0),
         }
     }
 }
 
+/// Average the normals of the faces around each vertex.
+fn vertex_normals(verts: &[[f64; 3]], faces: &[[usize; 3]]) -> Vec<[f64; 3]> {
+    let mut sums = vec![[0.0; 3]; verts.len()];
+    for face in faces {
+        let n = face_normal(&verts[face[0]], &verts[face[1]], &verts[face[2]]);
+        for &vi in face {
+            sums[vi] = [sums[vi][0] + n[0], sums[vi][1] + n[1], sums[vi][2] + n[2]];
+        }
+    }
+    sums.iter()
+        .map(|s| {
+            let len = dot3(s, s).sqrt();
+            if len < 1e-10 {
+                [0.0, 0.0, 1.0]
+            } else {
+                [s[0] / len, s[1] / len, s[2] / len]
+            }
+        })
+        .collect()
+}
+
 /// Icosahedron geometry: 12 vertices, 20 triangular faces
 fn icosahedron_vertices() -> Vec<[f64; 3]> {
     let phi = (1.0 + 5.0_f64.sqrt()) / 2.0;
@@ -61,10 +92,12 @@ fn dot3(a: &[f64; 3], b: &[f64; 3]) -> f64 {
     a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
 }
 
-struct SortedFace {
-    screen_verts: [(f64, f64); 3],
-    depth: f64,
+struct VisibleFace {
+    /// Screen x, y and view-space distance from the camera per corner
+    screen_verts: [(f64, f64, f64); 3],
     color: (u8, u8, u8),
+    /// Lit colors at each corner, interpolated across the face when smooth
+    vertex_colors: [(u8, u8, u8); 3],
 }
 
 impl Effect for FilledVector {
@@ -75,6 +108,7 @@ impl Effect for FilledVector {
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
+        self.vertex_normals = vertex_normals(&icosahedron_vertices(), &icosahedron_faces());
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -102,8 +136,8 @@ impl Effect for FilledVector {
         }
 
         let ts = t * self.rot_speed;
-        let angle_y = ts * 0.7;
-        let angle_x = ts * 0.5 + 0.3;
+        let angle_y = ts * 0.7 + self.look.0;
+        let angle_x = ts * 0.5 + 0.3 + self.look.1;
 
         let cos_y = angle_y.cos();
         let sin_y = angle_y.sin();
@@ -125,27 +159,38 @@ impl Effect for FilledVector {
             [lx / len, ly / len, lz / len]
         };
 
+        let rotate = |v: &[f64; 3]| {
+            // Rotate Y
+            let x1 = v[0] * cos_y + v[2] * sin_y;
+            let z1 = -v[0] * sin_y + v[2] * cos_y;
+            let y1 = v[1];
+            // Rotate X
+            let y2 = y1 * cos_x - z1 * sin_x;
+            let z2 = y1 * sin_x + z1 * cos_x;
+            [x1, y2, z2]
+        };
+
         // Transform all vertices
-        let transformed: Vec<[f64; 3]> = verts
-            .iter()
-            .map(|v| {
-                // Rotate Y
-                let x1 = v[0] * cos_y + v[2] * sin_y;
-                let z1 = -v[0] * sin_y + v[2] * cos_y;
-                let y1 = v[1];
-                // Rotate X
-                let y2 = y1 * cos_x - z1 * sin_x;
-                let z2 = y1 * sin_x + z1 * cos_x;
-                [x1, y2, z2]
-            })
-            .collect();
+        let transformed: Vec<[f64; 3]> = verts.iter().map(rotate).collect();
+        let smooth = self.shading.round() >= 1.0 && self.vertex_normals.len() == verts.len();
+
+        // Gouraud: light each vertex once from its rotated averaged normal
+        let vertex_light: Vec<f64> = if smooth {
+            self.vertex_normals
+                .iter()
+                .map(|n| (0.2 + dot3(&rotate(n), &light_dir).max(0.0) * 0.8).clamp(0.0, 1.0))
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-        // Project vertices to screen
-        let projected: Vec<(f64, f64)> = transformed
+        // Project vertices to screen, keeping view distance for the z-buffer
+        let projected: Vec<(f64, f64, f64)> = transformed
             .iter()
             .map(|v| {
-                let persp = camera_z / (camera_z + v[2]);
-                (cx + v[0] * proj_scale * persp, cy + v[1] * proj_scale * persp)
+                let dist = camera_z + v[2];
+                let persp = camera_z / dist;
+                (cx + v[0] * proj_scale * persp, cy + v[1] * proj_scale * persp, dist)
             })
             .collect();
 
@@ -159,8 +204,8 @@ impl Effect for FilledVector {
             (5.0 / 6.0 + t * 0.05) % 1.0,
         ];
 
-        // Build sorted face list
-        let mut sorted_faces: Vec<SortedFace> = Vec::with_capacity(faces.len());
+        // Build front-facing face list
+        let mut visible_faces: Vec<VisibleFace> = Vec::with_capacity(faces.len());
 
         for (fi, face) in faces.iter().enumerate() {
             let v0 = &transformed[face[0]];
@@ -196,8 +241,6 @@ impl Effect for FilledVector {
                 continue;
             }
 
-            let avg_z = face_center[2];
-
             // Lighting: diffuse shading
             let ndotl = dot3(&normal, &light_dir).max(0.0);
             let ambient = 0.2;
@@ -207,46 +250,52 @@ impl Effect for FilledVector {
             // Face color based on hue cycling
             let hue = hues[fi % 6];
             let (cr, cg, cb) = hsv_to_rgb(hue, 0.75, brightness);
-
-            sorted_faces.push(SortedFace {
+            let vertex_colors = if smooth {
+                // Each corner takes its vertex's hue so neighboring faces agree
+                [0, 1, 2].map(|k| hsv_to_rgb(hues[face[k] % 6], 0.75, vertex_light[face[k]]))
+            } else {
+                [(cr, cg, cb); 3]
+            };
+
+            visible_faces.push(VisibleFace {
                 screen_verts: [
                     projected[face[0]],
                     projected[face[1]],
                     projected[face[2]],
                 ],
-                depth: avg_z,
                 color: (cr, cg, cb),
+                vertex_colors,
             });
         }
 
-        // Sort back-to-front (painter's algorithm): largest Z = furthest = draw first
-        sorted_faces
-            .sort_by(|a, b| b.depth.partial_cmp(&a.depth).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Draw each face with flat shading using scanline fill
-        for face in &sorted_faces {
-            fill_triangle(pixels, w, h, &face.screen_verts, face.color);
+        // Depth-tested fill, so draw order doesn't matter
+        let mut zbuf = vec![f64::MAX; (w * h) as usize];
+        for face in &visible_faces {
+            fill_triangle_zbuf(pixels, &mut zbuf, w, h, &face.screen_verts, &face.vertex_colors);
         }
 
-        // Draw edges over the filled faces for definition
-        for face in &sorted_faces {
-            let edge_color = (
-                (face.color.0 as u16 * 3 / 4) as u8,
-                (face.color.1 as u16 * 3 / 4) as u8,
-                (face.color.2 as u16 * 3 / 4) as u8,
-            );
-            for i in 0..3 {
-                let j = (i + 1) % 3;
-                draw_line(
-                    pixels,
-                    w,
-                    h,
-                    face.screen_verts[i].0,
-                    face.screen_verts[i].1,
-                    face.screen_verts[j].0,
-                    face.screen_verts[j].1,
-                    edge_color,
+        // Draw edges over the filled faces for definition (flat shading only,
+        // smooth shading would lose its rounded look)
+        if !smooth {
+            for face in &visible_faces {
+                let edge_color = (
+                    (face.color.0 as u16 * 3 / 4) as u8,
+                    (face.color.1 as u16 * 3 / 4) as u8,
+                    (face.color.2 as u16 * 3 / 4) as u8,
                 );
+                for i in 0..3 {
+                    let j = (i + 1) % 3;
+                    draw_line(
+                        pixels,
+                        w,
+                        h,
+                        face.screen_verts[i].0,
+                        face.screen_verts[i].1,
+                        face.screen_verts[j].0,
+                        face.screen_verts[j].1,
+                        edge_color,
+                    );
+                }
             }
         }
     }
@@ -265,25 +314,40 @@ impl Effect for FilledVector {
                 max: 2.0,
                 value: self.scale,
             },
+            ParamDesc {
+                name: "shading".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.shading,
+            },
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "rot_speed" => self.rot_speed = value,
             "scale" => self.scale = value,
+            "shading" => self.shading = value,
             _ => {}
         }
     }
 }
 
-/// Scanline triangle fill with solid color (overwrites pixels)
-fn fill_triangle(
+/// Scanline triangle fill with a depth test, interpolating the corner colors.
+///
+/// Each vertex carries its view distance; 1/distance is linear in screen
+/// space, so depth and colors are interpolated perspective-correctly.
+fn fill_triangle_zbuf(
     pixels: &mut [(u8, u8, u8)],
+    zbuf: &mut [f64],
     w: u32,
     h: u32,
-    verts: &[(f64, f64); 3],
-    color: (u8, u8, u8),
+    verts: &[(f64, f64, f64); 3],
+    colors: &[(u8, u8, u8); 3],
 ) {
     let min_y = verts[0].1.min(verts[1].1).min(verts[2].1).max(0.0) as i32;
     let max_y = verts[0]
@@ -333,8 +397,26 @@ fn fill_triangle(
 
             if w0 >= -0.001 && w1 >= -0.001 && w2 >= -0.001 {
                 let idx = (y as u32 * w + x as u32) as usize;
-                if idx < pixels.len() {
-                    pixels[idx] = color;
+                let (q0, q1, q2) = (w0 / v0.2, w1 / v1.2, w2 / v2.2);
+                let inv_z = q0 + q1 + q2;
+                if inv_z <= 0.0 {
+                    continue;
+                }
+                let z = 1.0 / inv_z;
+                if idx < pixels.len() && z <= zbuf[idx] {
+                    zbuf[idx] = z;
+                    let (w0, w1, w2) = (q0 * z, q1 * z, q2 * z);
+                    let mix = |c0: u8, c1: u8, c2: u8| {
+                        (c0 as f64 * w0 + c1 as f64 * w1 + c2 as f64 * w2)
+                            .round()
+                            .clamp(0.0, 255.0) as u8
+                    };
+                    let [c0, c1, c2] = *colors;
+                    pixels[idx] = (
+                        mix(c0.0, c1.0, c2.0),
+                        mix(c0.1, c1.1, c2.1),
+                        mix(c0.2, c1.2, c2.2),
+                    );
                 }
             }
         }
@@ -383,25 +465,3 @@ fn draw_line(
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let h = ((h % 1.0) + 1.0) % 1.0;
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let tv = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, tv, p),
-        1 => (q, v, p),
-        2 => (p, v, tv),
-        3 => (p, q, v),
-        4 => (tv, p, v),
-        _ => (v, p, q),
-    };
-    (
-        (r * 255.0).clamp(0.0, 255.0) as u8,
-        (g * 255.0).clamp(0.0, 255.0) as u8,
-        (b * 255.0).clamp(0.0, 255.0) as u8,
-    )
-}
@@ -1,12 +1,40 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::palette::{Palette, Stops};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+/// Heat-to-color stops: fire, ice, toxic.
+const PALETTES: [&Stops; 3] = [
+    &[
+        (0, (0, 0, 0)),
+        (60, (128, 0, 0)),
+        (150, (255, 128, 0)),
+        (220, (255, 255, 0)),
+        (255, (255, 255, 255)),
+    ],
+    &[
+        (0, (0, 0, 0)),
+        (60, (0, 10, 110)),
+        (150, (0, 110, 255)),
+        (220, (120, 230, 255)),
+        (255, (255, 255, 255)),
+    ],
+    &[
+        (0, (0, 0, 0)),
+        (60, (20, 80, 0)),
+        (150, (90, 230, 0)),
+        (220, (220, 255, 60)),
+        (255, (255, 255, 220)),
+    ],
+];
+
 pub struct Fire {
     width: u32,
     height: u32,
     heat: Vec<f64>,
-    palette: [(u8, u8, u8); 256],
+    /// Index into `PALETTES`: fire, ice, toxic
+    palette: f64,
+    colors: Palette,
     cooling: f64,
     intensity: f64,
     rng: StdRng,
@@ -18,41 +46,17 @@ impl Fire {
             width: 0,
             height: 0,
             heat: Vec::new(),
-            palette: Self::build_palette(),
+            palette: 0.0,
+            colors: Palette::from_stops(PALETTES[0]),
             cooling: 0.4,
             intensity: 1.0,
             rng: StdRng::seed_from_u64(0),
         }
     }
 
-    fn build_palette() -> [(u8, u8, u8); 256] {
-        let mut palette = [(0u8, 0u8, 0u8); 256];
-        let control_points: &[(usize, (u8, u8, u8))] = &[
-            (0, (0, 0, 0)),
-            (60, (128, 0, 0)),
-            (150, (255, 128, 0)),
-            (220, (255, 255, 0)),
-            (255, (255, 255, 255)),
-        ];
-
-        for window in control_points.windows(2) {
-            let (i0, c0) = window[0];
-            let (i1, c1) = window[1];
-            for i in i0..=i1 {
-                let t = if i1 == i0 {
-                    0.0
-                } else {
-                    (i - i0) as f64 / (i1 - i0) as f64
-                };
-                palette[i] = (
-                    (c0.0 as f64 + (c1.0 as f64 - c0.0 as f64) * t) as u8,
-                    (c0.1 as f64 + (c1.1 as f64 - c0.1 as f64) * t) as u8,
-                    (c0.2 as f64 + (c1.2 as f64 - c0.2 as f64) * t) as u8,
-                );
-            }
-        }
-
-        palette
+    fn apply_palette(&mut self) {
+        let index = (self.palette.round().max(0.0) as usize).min(PALETTES.len() - 1);
+        self.colors = Palette::from_stops(PALETTES[index]);
     }
 }
 
@@ -79,14 +83,15 @@ impl Effect for Fire {
         }
 
         // Seed bottom 2 rows with random heat
-        for y in (h - 2)..h {
+        let seed_top = h.saturating_sub(2);
+        for y in seed_top..h {
             for x in 0..w {
                 self.heat[y * w + x] = self.rng.gen_range(0.0..1.0) * self.intensity;
             }
         }
 
         // Propagate heat upward: process from top so reads from below are unmodified
-        for y in 0..(h - 2) {
+        for y in 0..seed_top {
             for x in 0..w {
                 let below = self.heat[(y + 1) * w + x];
                 let below_left = if x > 0 {
@@ -108,8 +113,7 @@ impl Effect for Fire {
 
         // Render heat to pixels via palette
         for i in 0..pixels.len().min(self.heat.len()) {
-            let idx = (self.heat[i].clamp(0.0, 1.0) * 255.0) as usize;
-            pixels[i] = self.palette[idx];
+            pixels[i] = self.colors.lookup(self.heat[i]);
         }
     }
 
@@ -127,6 +131,12 @@ impl Effect for Fire {
                 max: 2.0,
                 value: self.intensity,
             },
+            ParamDesc {
+                name: "palette".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.palette,
+            },
         ]
     }
 
@@ -134,6 +144,10 @@ impl Effect for Fire {
         match name {
             "cooling" => self.cooling = value,
             "intensity" => self.intensity = value,
+            "palette" => {
+                self.palette = value;
+                self.apply_palette();
+            }
             _ => {}
         }
     }
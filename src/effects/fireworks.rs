@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::f64::consts::TAU;
@@ -53,6 +55,14 @@ impl Effect for Fireworks {
         "Fireworks"
     }
 
+    fn wants_persistence(&self) -> Option<f64> {
+        Some(0.9)
+    }
+
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -76,12 +86,8 @@ impl Effect for Fireworks {
         let hf = h as f64;
         let grav = self.gravity * 120.0;
 
-        // Fade existing pixels (night sky with trails)
-        for p in pixels.iter_mut() {
-            p.0 = p.0.saturating_sub(10);
-            p.1 = p.1.saturating_sub(10);
-            p.2 = p.2.saturating_sub(12);
-        }
+        // Night sky; trails come from the sequencer's persistence pass
+        pixels.fill((0, 0, 0));
 
         // Launch rockets
         self.launch_accum += dt * self.intensity * 2.5;
@@ -170,22 +176,13 @@ impl Effect for Fireworks {
                 return false;
             }
 
-            let ix = s.x as i32;
-            let iy = s.y as i32;
-            if ix >= 0 && ix < w as i32 && iy >= 0 && iy < h as i32 {
-                // Color: saturated at birth, fades to orange/red then dark
-                let brightness = s.life.clamp(0.0, 1.0);
-                let sat = (0.5 + s.life * 0.5).clamp(0.0, 1.0);
-                let (cr, cg, cb) = hsv_to_rgb(s.hue, sat, brightness);
-
-                let idx = (iy as u32 * w + ix as u32) as usize;
-                if idx < pixels.len() {
-                    let p = &mut pixels[idx];
-                    p.0 = p.0.max(cr);
-                    p.1 = p.1.max(cg);
-                    p.2 = p.2.max(cb);
-                }
-            }
+            // Color: saturated at birth, fades to orange/red then dark
+            let brightness = s.life.clamp(0.0, 1.0);
+            let sat = (0.5 + s.life * 0.5).clamp(0.0, 1.0);
+            let color = hsv_to_rgb(s.hue, sat, brightness);
+            // Sparks shrink a little as they burn out
+            let radius = 0.6 + brightness * 0.4;
+            splat::filled_circle(pixels, w, h, (s.x, s.y), radius, color);
 
             true
         });
@@ -216,20 +213,3 @@ impl Effect for Fireworks {
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
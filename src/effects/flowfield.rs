@@ -64,6 +64,10 @@ impl Effect for FlowField {
         "Flow Field"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
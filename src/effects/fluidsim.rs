@@ -1,8 +1,33 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::palette::{Palette, Stops};
 use rand::rngs::StdRng;
 use rand::Rng;
 use std::f64::consts::PI;
 
+/// Density-to-color stops: heat, ice, ink.
+const PALETTES: [&Stops; 3] = [
+    &[
+        (0, (0, 0, 0)),
+        (51, (0, 0, 127)),
+        (115, (178, 0, 178)),
+        (178, (255, 127, 51)),
+        (255, (255, 255, 255)),
+    ],
+    &[
+        (0, (0, 0, 0)),
+        (60, (0, 20, 90)),
+        (140, (0, 120, 220)),
+        (210, (140, 230, 255)),
+        (255, (255, 255, 255)),
+    ],
+    &[
+        (0, (240, 236, 225)),
+        (90, (150, 160, 190)),
+        (170, (40, 50, 110)),
+        (255, (5, 5, 30)),
+    ],
+];
+
 pub struct FluidSim {
     width: u32,
     height: u32,
@@ -16,6 +41,11 @@ pub struct FluidSim {
     dens_prev: Vec<f64>,
     viscosity: f64,
     diffusion: f64,
+    /// Number of orbiting emitters, rounded
+    emitters: f64,
+    /// Index into `PALETTES`: heat, ice, ink
+    palette: f64,
+    colors: Palette,
 }
 
 fn set_bnd(gw: usize, gh: usize, b: i32, field: &mut [f64]) {
@@ -54,6 +84,19 @@ fn set_bnd(gw: usize, gh: usize, b: i32, field: &mut [f64]) {
         0.5 * (field[(gh - 1) * gw + gw - 2] + field[(gh - 2) * gw + gw - 1]);
 }
 
+/// Nearest-cell copy of a `gw`x`gh` grid onto `nw`x`nh`, scaling values by `scale`.
+fn resample(field: &[f64], gw: usize, gh: usize, nw: usize, nh: usize, scale: f64) -> Vec<f64> {
+    let mut out = vec![0.0; nw * nh];
+    for y in 0..nh {
+        let sy = (y * gh / nh).min(gh - 1);
+        for x in 0..nw {
+            let sx = (x * gw / nw).min(gw - 1);
+            out[y * nw + x] = field[sy * gw + sx] * scale;
+        }
+    }
+    out
+}
+
 fn diffuse(gw: usize, gh: usize, b: i32, x: &mut [f64], x0: &[f64], diff: f64, dt: f64) {
     if gw < 3 || gh < 3 {
         return;
@@ -166,6 +209,37 @@ impl FluidSim {
             dens_prev: Vec::new(),
             viscosity: 0.001,
             diffusion: 0.001,
+            emitters: 3.0,
+            palette: 0.0,
+            colors: Palette::from_stops(PALETTES[0]),
+        }
+    }
+
+    fn apply_palette(&mut self) {
+        let index = (self.palette.round().max(0.0) as usize).min(PALETTES.len() - 1);
+        self.colors = Palette::from_stops(PALETTES[index]);
+    }
+
+    /// Add `amount` of dye and a push of `force` (cells per second squared)
+    /// in a 3x3 patch around `(x, y)`, given as fractions of the screen.
+    /// Takes effect on the next simulation step.
+    pub fn inject(&mut self, (x, y): (f64, f64), (fx, fy): (f64, f64), amount: f64) {
+        let gw = self.gw;
+        let gh = self.gh;
+        if gw < 3 || gh < 3 {
+            return;
+        }
+        let ix = ((x * gw as f64) as usize).clamp(1, gw - 2);
+        let iy = ((y * gh as f64) as usize).clamp(1, gh - 2);
+        for yy in iy - 1..=iy + 1 {
+            for xx in ix - 1..=ix + 1 {
+                if xx > 0 && xx < gw - 1 && yy > 0 && yy < gh - 1 {
+                    let idx = yy * gw + xx;
+                    self.u_prev[idx] += fx;
+                    self.v_prev[idx] += fy;
+                    self.dens_prev[idx] += amount;
+                }
+            }
         }
     }
 
@@ -239,29 +313,6 @@ impl FluidSim {
             *d *= 0.99;
         }
     }
-
-    fn heat_color(val: f64) -> (u8, u8, u8) {
-        let v = val.clamp(0.0, 1.0);
-        // black -> deep blue -> magenta -> orange -> white
-        let (r, g, b) = if v < 0.2 {
-            let t = v / 0.2;
-            (0.0, 0.0, t * 0.5)
-        } else if v < 0.45 {
-            let t = (v - 0.2) / 0.25;
-            (t * 0.7, 0.0, 0.5 + t * 0.2)
-        } else if v < 0.7 {
-            let t = (v - 0.45) / 0.25;
-            (0.7 + t * 0.3, t * 0.5, 0.7 - t * 0.5)
-        } else {
-            let t = (v - 0.7) / 0.3;
-            (1.0, 0.5 + t * 0.5, 0.2 + t * 0.8)
-        };
-        (
-            (r * 255.0) as u8,
-            (g * 255.0) as u8,
-            (b * 255.0) as u8,
-        )
-    }
 }
 
 impl Effect for FluidSim {
@@ -269,6 +320,10 @@ impl Effect for FluidSim {
         "Fluid Simulation"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        18.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -283,6 +338,32 @@ impl Effect for FluidSim {
         self.dens_prev = vec![0.0; n];
     }
 
+    fn on_resize(&mut self, width: u32, height: u32, rng: &mut StdRng) {
+        let (old_w, old_h) = (self.gw, self.gh);
+        if old_w == 0 || old_h == 0 || self.density.is_empty() {
+            self.init(width, height);
+            self.randomize_init(rng);
+            return;
+        }
+        self.width = width;
+        self.height = height;
+        self.gw = (width / 4).max(8) as usize;
+        self.gh = (height / 4).max(8) as usize;
+        let (gw, gh) = (self.gw, self.gh);
+
+        // Carry the flow over to the new grid; velocities are in cells,
+        // so they stretch with it
+        let sx = gw as f64 / old_w as f64;
+        let sy = gh as f64 / old_h as f64;
+        self.density = resample(&self.density, old_w, old_h, gw, gh, 1.0);
+        self.u_vel = resample(&self.u_vel, old_w, old_h, gw, gh, sx);
+        self.v_vel = resample(&self.v_vel, old_w, old_h, gw, gh, sy);
+        let n = gw * gh;
+        self.u_prev = vec![0.0; n];
+        self.v_prev = vec![0.0; n];
+        self.dens_prev = vec![0.0; n];
+    }
+
     fn randomize_init(&mut self, rng: &mut StdRng) {
         let gw = self.gw;
         let gh = self.gh;
@@ -298,10 +379,8 @@ impl Effect for FluidSim {
             let strength = rng.gen_range(20.0..80.0);
             let vx = rng.gen_range(-5.0..5.0);
             let vy = rng.gen_range(-5.0..5.0);
-            for dy in 0..=(radius * 2) {
-                for dx in 0..=(radius * 2) {
-                    let x = cx + dx - radius;
-                    let y = cy + dy - radius;
+            for y in cy.saturating_sub(radius)..=cy + radius {
+                for x in cx.saturating_sub(radius)..=cx + radius {
                     if x > 0 && x < gw - 1 && y > 0 && y < gh - 1 {
                         let idx = y * gw + x;
                         self.density[idx] += strength;
@@ -325,31 +404,17 @@ impl Effect for FluidSim {
         let sim_dt = 0.05;
 
         // Add rotating injection points
-        let num_emitters = 3;
+        let num_emitters = self.emitters.round().max(1.0) as usize;
         for i in 0..num_emitters {
             let angle =
                 t * (0.5 + i as f64 * 0.3) + i as f64 * PI * 2.0 / num_emitters as f64;
-            let cx = gw as f64 * 0.5 + angle.cos() * gw as f64 * 0.2;
-            let cy = gh as f64 * 0.5 + (angle * 0.7).sin() * gh as f64 * 0.2;
-            let ix = (cx as usize).clamp(1, gw - 2);
-            let iy = (cy as usize).clamp(1, gh - 2);
+            let x = 0.5 + angle.cos() * 0.2;
+            let y = 0.5 + (angle * 0.7).sin() * 0.2;
 
             // Force direction tangent to circle
             let fx = -(angle * 0.7).cos() * 20.0;
             let fy = angle.sin() * 20.0;
-
-            for dy in 0..3_usize {
-                for dx in 0..3_usize {
-                    let xx = (ix + dx).saturating_sub(1);
-                    let yy = (iy + dy).saturating_sub(1);
-                    if xx > 0 && xx < gw - 1 && yy > 0 && yy < gh - 1 {
-                        let idx = yy * gw + xx;
-                        self.u_prev[idx] += fx;
-                        self.v_prev[idx] += fy;
-                        self.dens_prev[idx] += 30.0;
-                    }
-                }
-            }
+            self.inject((x, y), (fx, fy), 30.0);
         }
 
         self.vel_step(sim_dt);
@@ -378,7 +443,7 @@ impl Effect for FluidSim {
                         + fx * self.density[gy1 * gw + gx1]);
 
                 let idx = (y * w + x) as usize;
-                pixels[idx] = Self::heat_color(d * color_scale);
+                pixels[idx] = self.colors.lookup(d * color_scale);
             }
         }
     }
@@ -397,6 +462,18 @@ impl Effect for FluidSim {
                 max: 0.01,
                 value: self.diffusion,
             },
+            ParamDesc {
+                name: "emitters".to_string(),
+                min: 1.0,
+                max: 8.0,
+                value: self.emitters,
+            },
+            ParamDesc {
+                name: "palette".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.palette,
+            },
         ]
     }
 
@@ -404,6 +481,11 @@ impl Effect for FluidSim {
         match name {
             "viscosity" => self.viscosity = value,
             "diffusion" => self.diffusion = value,
+            "emitters" => self.emitters = value,
+            "palette" => {
+                self.palette = value;
+                self.apply_palette();
+            }
             _ => {}
         }
     }
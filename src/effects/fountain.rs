@@ -15,6 +15,11 @@ pub struct Fountain {
     height: u32,
     gravity: f64,
     emission: f64,
+    /// Half-width of the launch cone, as horizontal speed per unit of
+    /// upward speed
+    spread: f64,
+    /// Trail decay handed to the persistence pass; 0 turns trails off
+    trail: f64,
     particles: Vec<Particle>,
     emit_accum: f64,
     rng: StdRng,
@@ -27,6 +32,8 @@ impl Fountain {
             height: 0,
             gravity: 1.0,
             emission: 80.0,
+            spread: 0.4,
+            trail: 0.85,
             particles: Vec::new(),
             emit_accum: 0.0,
             rng: StdRng::seed_from_u64(0),
@@ -41,6 +48,14 @@ impl Effect for Fountain {
         "Fountain"
     }
 
+    fn wants_persistence(&self) -> Option<f64> {
+        if self.trail > 0.0 {
+            Some(self.trail)
+        } else {
+            None
+        }
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -62,19 +77,20 @@ impl Effect for Fountain {
         let wf = w as f64;
         let hf = h as f64;
 
-        // Fade existing pixels for trails
-        for p in pixels.iter_mut() {
-            p.0 = p.0.saturating_sub(12);
-            p.1 = p.1.saturating_sub(12);
-            p.2 = p.2.saturating_sub(15);
-        }
+        // Trails come from the sequencer's persistence pass
+        pixels.fill((0, 0, 0));
+
+        // Launch fast enough to peak near the top whatever the gravity, so
+        // lower gravity gives slower, floatier arcs rather than taller ones
+        let gravity = self.gravity * 200.0;
+        let max_speed = (2.0 * gravity * hf * 0.9).sqrt();
 
         // Emit new particles from bottom-center
         self.emit_accum += dt * self.emission;
         while self.emit_accum >= 1.0 && self.particles.len() < MAX_PARTICLES {
             self.emit_accum -= 1.0;
-            let angle = self.rng.gen_range(-0.4..0.4);
-            let speed = self.rng.gen_range(150.0..300.0);
+            let angle = self.rng.gen_range(-self.spread..self.spread);
+            let speed = max_speed * self.rng.gen_range(0.6..1.0);
             self.particles.push(Particle {
                 x: wf * 0.5 + self.rng.gen_range(-3.0..3.0),
                 y: hf - 1.0,
@@ -84,13 +100,13 @@ impl Effect for Fountain {
             });
         }
 
-        // Update particles
-        let gravity = self.gravity * 200.0;
+        // Update particles; they live about as long as their flight lasts
+        let aging = 0.5 * self.gravity.sqrt();
         self.particles.retain_mut(|p| {
             p.vy += gravity * dt;
             p.x += p.vx * dt;
             p.y += p.vy * dt;
-            p.life -= dt * 0.5;
+            p.life -= dt * aging;
             p.life > 0.0
         });
 
@@ -119,7 +135,7 @@ impl Effect for Fountain {
         vec![
             ParamDesc {
                 name: "gravity".to_string(),
-                min: 0.3,
+                min: 0.1,
                 max: 3.0,
                 value: self.gravity,
             },
@@ -129,6 +145,18 @@ impl Effect for Fountain {
                 max: 200.0,
                 value: self.emission,
             },
+            ParamDesc {
+                name: "spread".to_string(),
+                min: 0.05,
+                max: 1.2,
+                value: self.spread,
+            },
+            ParamDesc {
+                name: "trail".to_string(),
+                min: 0.0,
+                max: 0.98,
+                value: self.trail,
+            },
         ]
     }
 
@@ -136,6 +164,8 @@ impl Effect for Fountain {
         match name {
             "gravity" => self.gravity = value,
             "emission" => self.emission = value,
+            "spread" => self.spread = value,
+            "trail" => self.trail = value,
             _ => {}
         }
     }
@@ -5,8 +5,13 @@ pub struct FractalZoom {
     height: u32,
     zoom_speed: f64,
     max_iter: f64,
-    center_re: f64,
-    center_im: f64,
+    /// Zoom center, kept in double-double so it stays exact far below
+    /// what a plain `f64` can resolve
+    center_re: DoubleDouble,
+    center_im: DoubleDouble,
+    /// Reference orbit of the center, rounded to `f64`; every pixel
+    /// iterates only its small offset from it
+    orbit: Vec<(f64, f64)>,
 }
 
 impl FractalZoom {
@@ -18,32 +23,145 @@ impl FractalZoom {
             max_iter: 100.0,
             center_re: TARGET_RE,
             center_im: TARGET_IM,
+            orbit: Vec::new(),
         }
     }
+
+    /// Iterate the center at full precision for up to `max_iter` steps or
+    /// until it escapes.
+    fn compute_orbit(&mut self, max_iter: u32) {
+        let (c_re, c_im) = (self.center_re, self.center_im);
+        let mut z_re = DoubleDouble::from(0.0);
+        let mut z_im = DoubleDouble::from(0.0);
+        self.orbit.clear();
+        self.orbit.push((0.0, 0.0));
+        for _ in 0..max_iter {
+            let re = z_re.mul(z_re).sub(z_im.mul(z_im)).add(c_re);
+            z_im = z_re.mul(z_im).scale(2.0).add(c_im);
+            z_re = re;
+            let (re, im) = (z_re.hi, z_im.hi);
+            self.orbit.push((re, im));
+            if re * re + im * im > ESCAPE_SQ {
+                break;
+            }
+        }
+    }
+}
+
+// Misiurewicz point M(24,1) in Seahorse Valley, split into double-double
+// halves. Its neighborhood keeps spiraling at every depth, and escape
+// times only grow linearly as the view shrinks.
+const TARGET_RE: DoubleDouble = DoubleDouble {
+    hi: -0.7756837680090538,
+    lo: 1.1497740127108711e-17,
+};
+const TARGET_IM: DoubleDouble = DoubleDouble {
+    hi: 0.1364673682946901,
+    lo: 1.3525418034032015e-17,
+};
+
+/// Squared bailout radius; a large one smooths the escape-time coloring
+const ESCAPE_SQ: f64 = 256.0;
+
+/// Zoom depth in doublings before the cycle restarts. Pixel offsets live
+/// in `f64` and can shrink almost indefinitely, so the limit comes from
+/// the double-double center, good to about 1e-30.
+const MAX_DOUBLINGS: f64 = 95.0;
+
+/// An unevaluated sum `hi + lo` of two `f64`s, for about 106 bits of
+/// mantissa (Dekker/Knuth error-free transformations).
+#[derive(Clone, Copy)]
+struct DoubleDouble {
+    hi: f64,
+    lo: f64,
 }
 
-// Seahorse Valley target
-const TARGET_RE: f64 = -0.743643887037158;
-const TARGET_IM: f64 = 0.131825904205330;
+impl DoubleDouble {
+    fn from(v: f64) -> Self {
+        Self { hi: v, lo: 0.0 }
+    }
+
+    fn normalize(hi: f64, lo: f64) -> Self {
+        let s = hi + lo;
+        Self {
+            hi: s,
+            lo: lo - (s - hi),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        let s = self.hi + other.hi;
+        let bb = s - self.hi;
+        let err = (self.hi - (s - bb)) + (other.hi - bb);
+        Self::normalize(s, err + self.lo + other.lo)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.add(Self {
+            hi: -other.hi,
+            lo: -other.lo,
+        })
+    }
+
+    fn mul(self, other: Self) -> Self {
+        let p = self.hi * other.hi;
+        let err = self.hi.mul_add(other.hi, -p);
+        Self::normalize(p, err + self.hi * other.lo + self.lo * other.hi)
+    }
+
+    /// Multiply by a power of two, which is exact.
+    fn scale(self, k: f64) -> Self {
+        Self {
+            hi: self.hi * k,
+            lo: self.lo * k,
+        }
+    }
+}
 
-fn mandelbrot_iter(c_re: f64, c_im: f64, max_iter: u32) -> u32 {
-    let mut z_re = 0.0;
-    let mut z_im = 0.0;
+/// Iterate the point at offset `(dc_re, dc_im)` from the reference orbit's
+/// center by perturbation, returning the iteration count and `|z|^2` at
+/// escape. Whenever the full `z` gets smaller than its offset, or the
+/// reference runs out, the offset is rebased onto the start of the orbit,
+/// which avoids the usual perturbation glitches with a single reference.
+fn perturbed_iter(orbit: &[(f64, f64)], dc_re: f64, dc_im: f64, max_iter: u32) -> (u32, f64) {
+    let mut dz_re = 0.0;
+    let mut dz_im = 0.0;
+    let mut m = 0;
     let mut iter = 0u32;
+    let mut mag_sq = 0.0;
     while iter < max_iter {
-        let z_re2 = z_re * z_re;
-        let z_im2 = z_im * z_im;
-        if z_re2 + z_im2 > 4.0 {
+        let (z_re, z_im) = orbit[m];
+        let re = 2.0 * (z_re * dz_re - z_im * dz_im) + dz_re * dz_re - dz_im * dz_im + dc_re;
+        dz_im = 2.0 * (z_re * dz_im + z_im * dz_re + dz_re * dz_im) + dc_im;
+        dz_re = re;
+        m += 1;
+        iter += 1;
+
+        let (z_re, z_im) = orbit[m];
+        let full_re = z_re + dz_re;
+        let full_im = z_im + dz_im;
+        mag_sq = full_re * full_re + full_im * full_im;
+        if mag_sq > ESCAPE_SQ {
             break;
         }
-        z_im = 2.0 * z_re * z_im + c_im;
-        z_re = z_re2 - z_im2 + c_re;
-        iter += 1;
+        if mag_sq < dz_re * dz_re + dz_im * dz_im || m + 1 == orbit.len() {
+            dz_re = full_re;
+            dz_im = full_im;
+            m = 0;
+        }
     }
-    iter
+    (iter, mag_sq)
 }
 
-fn sample_variance(center_re: f64, center_im: f64, scale: f64, max_iter: u32) -> f64 {
+/// Iteration-count variance over a 16x16 grid around `(off_re, off_im)`
+/// from the orbit's center; low variance means a featureless view.
+fn sample_variance(
+    orbit: &[(f64, f64)],
+    off_re: f64,
+    off_im: f64,
+    scale: f64,
+    max_iter: u32,
+) -> f64 {
     let grid = 16;
     let mut sum = 0.0;
     let mut sum_sq = 0.0;
@@ -52,9 +170,8 @@ fn sample_variance(center_re: f64, center_im: f64, scale: f64, max_iter: u32) ->
         for gx in 0..grid {
             let nx = (gx as f64 / grid as f64 - 0.5) * 2.0;
             let ny = (gy as f64 / grid as f64 - 0.5) * 2.0;
-            let c_re = center_re + nx * scale;
-            let c_im = center_im + ny * scale;
-            let it = mandelbrot_iter(c_re, c_im, max_iter) as f64;
+            let (it, _) = perturbed_iter(orbit, off_re + nx * scale, off_im + ny * scale, max_iter);
+            let it = it as f64;
             sum += it;
             sum_sq += it * it;
         }
@@ -68,6 +185,10 @@ impl Effect for FractalZoom {
         "FractalZoom"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -83,13 +204,13 @@ impl Effect for FractalZoom {
         let wf = w as f64;
         let hf = h as f64;
         let aspect = wf / hf;
-        // Exponential zoom: doubles every 1/zoom_speed seconds
-        // Cycle to avoid f64 precision loss (~47 doublings is the limit)
-        let cycle_period = 45.0 / self.zoom_speed;
+        // Exponential zoom: doubles every 1/zoom_speed seconds, cycling
+        // once the center's precision runs out
+        let cycle_period = MAX_DOUBLINGS / self.zoom_speed;
         let cycle_t = t % cycle_period;
 
         // Scale max_iter with zoom depth so detail persists at deep zoom
-        let dynamic_max_iter = (self.max_iter + cycle_t * self.zoom_speed * 8.0) as u32;
+        let dynamic_max_iter = (self.max_iter + cycle_t * self.zoom_speed * 24.0) as u32;
 
         // Reset center on cycle wrap (when cycle_t is near zero)
         if cycle_t < 0.05 {
@@ -99,29 +220,16 @@ impl Effect for FractalZoom {
 
         let zoom = 2.0_f64.powf(cycle_t * self.zoom_speed);
         let scale = 1.5 / zoom;
+        self.compute_orbit(dynamic_max_iter);
+        let orbit = &self.orbit;
 
         for y in 0..h {
             for x in 0..w {
                 let nx = (x as f64 / wf - 0.5) * 2.0 * aspect;
                 let ny = (y as f64 / hf - 0.5) * 2.0;
 
-                let c_re = self.center_re + nx * scale;
-                let c_im = self.center_im + ny * scale;
-
-                let mut z_re = 0.0;
-                let mut z_im = 0.0;
-                let mut iter = 0u32;
-
-                while iter < dynamic_max_iter {
-                    let z_re2 = z_re * z_re;
-                    let z_im2 = z_im * z_im;
-                    if z_re2 + z_im2 > 256.0 {
-                        break;
-                    }
-                    z_im = 2.0 * z_re * z_im + c_im;
-                    z_re = z_re2 - z_im2 + c_re;
-                    iter += 1;
-                }
+                let (iter, z_mag_sq) =
+                    perturbed_iter(orbit, nx * scale, ny * scale, dynamic_max_iter);
 
                 let idx = (y * w + x) as usize;
 
@@ -129,7 +237,6 @@ impl Effect for FractalZoom {
                     pixels[idx] = (0, 0, 0);
                 } else {
                     // Smooth iteration count for band-free coloring
-                    let z_mag_sq = z_re * z_re + z_im * z_im;
                     let smooth = if z_mag_sq > 1.0 {
                         iter as f64 + 1.0
                             - (z_mag_sq.ln() / 2.0).ln() / std::f64::consts::LN_2
@@ -145,7 +252,7 @@ impl Effect for FractalZoom {
         }
 
         // Steer toward interesting regions if current view is too uniform
-        let current_var = sample_variance(self.center_re, self.center_im, scale, dynamic_max_iter);
+        let current_var = sample_variance(orbit, 0.0, 0.0, scale, dynamic_max_iter);
         if current_var < 5.0 {
             let probe_dist = scale * 0.3;
             let directions: [(f64, f64); 4] = [
@@ -155,19 +262,17 @@ impl Effect for FractalZoom {
                 (0.0, -probe_dist),
             ];
             let mut best_var = current_var;
-            let mut best_re = self.center_re;
-            let mut best_im = self.center_im;
-            for (dre, dim) in &directions {
-                let v = sample_variance(self.center_re + dre, self.center_im + dim, scale, dynamic_max_iter);
+            let mut best = (0.0, 0.0);
+            for &(dre, dim) in &directions {
+                let v = sample_variance(orbit, dre, dim, scale, dynamic_max_iter);
                 if v > best_var {
                     best_var = v;
-                    best_re = self.center_re + dre;
-                    best_im = self.center_im + dim;
+                    best = (dre, dim);
                 }
             }
             // Nudge center 10% toward best direction
-            self.center_re += (best_re - self.center_re) * 0.1;
-            self.center_im += (best_im - self.center_im) * 0.1;
+            self.center_re = self.center_re.add(DoubleDouble::from(best.0 * 0.1));
+            self.center_im = self.center_im.add(DoubleDouble::from(best.1 * 0.1));
         }
     }
 
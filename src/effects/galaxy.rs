@@ -4,14 +4,43 @@ use rand::Rng;
 use std::f64::consts::TAU;
 
 const NUM_STARS: usize = 4000;
-const NUM_ARMS: usize = 4;
+/// Inward pull of the central mass at `mass` 1, in galaxy radii per s².
+/// It matches the density-wave rotation curve, so both modes spin alike.
+const CENTRAL_PULL: f64 = 0.0225;
+/// Softening radius that keeps the pull finite near each attractor.
+const SOFTENING: f64 = 0.08;
+/// Speed limit for N-body stars, in galaxy radii per second.
+const MAX_SPEED: f64 = 0.6;
+/// N-body stars flung past this radius are put back on a circular orbit.
+const ESCAPE_RADIUS: f64 = 1.8;
 
 struct Star {
     r: f64,
-    arm_angle: f64,
+    /// Which arm the star belongs to, as a fraction of the arm count, so
+    /// changing `arms` redistributes stars without regenerating them
+    arm_pick: f64,
+    /// Angular offset from the arm's center line
+    spread: f64,
+    /// Field stars ignore the arms
+    in_arm: bool,
     brightness: f64,
     twinkle_phase: f64,
     size: u8,
+    /// Position and velocity in the galaxy plane for N-body motion
+    pos: (f64, f64),
+    vel: (f64, f64),
+}
+
+impl Star {
+    /// Angle of the arm center line this star starts from.
+    fn arm_angle(&self, arms: usize) -> f64 {
+        if self.in_arm {
+            let arm = (self.arm_pick * arms as f64).floor();
+            arm * TAU / arms as f64 + self.spread
+        } else {
+            self.arm_pick * TAU
+        }
+    }
 }
 
 pub struct Galaxy {
@@ -19,7 +48,16 @@ pub struct Galaxy {
     height: u32,
     speed: f64,
     twist: f64,
+    /// Central black-hole mass, scaling rotation speed and pull
+    mass: f64,
+    arms: f64,
+    /// Mass of a second attractor orbiting outside the disc; 0 removes it
+    companion: f64,
+    /// 0 = density-wave rotation, 1 = N-body-lite integration
+    motion: f64,
     stars: Vec<Star>,
+    /// Whether `pos`/`vel` hold a live N-body state to continue from
+    nbody_live: bool,
 }
 
 impl Galaxy {
@@ -29,7 +67,102 @@ impl Galaxy {
             height: 0,
             speed: 1.0,
             twist: 1.0,
+            mass: 1.0,
+            arms: 4.0,
+            companion: 0.0,
+            motion: 0.0,
             stars: Vec::new(),
+            nbody_live: false,
+        }
+    }
+
+    fn arm_count(&self) -> usize {
+        self.arms.round().max(1.0) as usize
+    }
+
+    /// Angular velocity of the density-wave rotation curve at radius `r`.
+    fn angular_vel(&self, r: f64) -> f64 {
+        0.15 * self.mass.sqrt() / r.max(0.05).sqrt()
+    }
+
+    /// Where the companion sits at (speed-scaled) time `t`, on a slow orbit
+    /// just outside the disc.
+    fn companion_pos(t: f64) -> (f64, f64) {
+        let a = t * 0.12 + 1.0;
+        (1.15 * a.cos(), 0.95 * a.sin())
+    }
+
+    /// Density-wave position of `star` at time `t`.
+    fn wave_pos(&self, star: &Star, t: f64) -> (f64, f64) {
+        let spiral = star.r * TAU * 0.75 * self.twist;
+        let angle = star.arm_angle(self.arm_count()) + spiral + t * self.angular_vel(star.r);
+        (star.r * angle.cos(), star.r * angle.sin())
+    }
+
+    /// Softened pull towards a point mass, as (ax, ay).
+    fn pull(from: (f64, f64), to: (f64, f64), strength: f64) -> (f64, f64) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let d2 = dx * dx + dy * dy + SOFTENING * SOFTENING;
+        let k = strength / (d2 * d2.sqrt());
+        (dx * k, dy * k)
+    }
+
+    /// Start every star on a circular orbit from its density-wave position.
+    fn seed_nbody(&mut self, t: f64) {
+        for i in 0..self.stars.len() {
+            let (x, y) = self.wave_pos(&self.stars[i], t);
+            let omega = self.angular_vel(self.stars[i].r);
+            let star = &mut self.stars[i];
+            star.pos = (x, y);
+            star.vel = (-y * omega, x * omega);
+        }
+        self.nbody_live = true;
+    }
+
+    /// Advance the N-body stars by `dt` under the central mass and the
+    /// companion, with semi-implicit Euler sub-steps.
+    fn step_nbody(&mut self, t: f64, dt: f64) {
+        let steps = 4;
+        let h = dt / steps as f64;
+        let central = CENTRAL_PULL * self.mass;
+        let companion = CENTRAL_PULL * self.companion * 0.15;
+        for step in 0..steps {
+            let comp = Self::companion_pos(t - dt + h * (step + 1) as f64);
+            for star in self.stars.iter_mut() {
+                let (x, y) = star.pos;
+                // The central pull is constant beyond the softening radius,
+                // which is what gives the density-wave rotation curve
+                let r = (x * x + y * y + SOFTENING * SOFTENING).sqrt();
+                let mut ax = -x / r * central;
+                let mut ay = -y / r * central;
+                if companion > 0.0 {
+                    let (cx, cy) = Self::pull(star.pos, comp, companion);
+                    ax += cx;
+                    ay += cy;
+                }
+                let mut vx = star.vel.0 + ax * h;
+                let mut vy = star.vel.1 + ay * h;
+                let speed = (vx * vx + vy * vy).sqrt();
+                if speed > MAX_SPEED {
+                    vx *= MAX_SPEED / speed;
+                    vy *= MAX_SPEED / speed;
+                }
+                star.vel = (vx, vy);
+                star.pos = (x + vx * h, y + vy * h);
+            }
+        }
+
+        // Recycle stars the companion has flung away
+        for i in 0..self.stars.len() {
+            let (x, y) = self.stars[i].pos;
+            if x * x + y * y > ESCAPE_RADIUS * ESCAPE_RADIUS {
+                let r = self.stars[i].r;
+                let omega = self.angular_vel(r);
+                let a = self.stars[i].twinkle_phase;
+                let star = &mut self.stars[i];
+                star.pos = (r * a.cos(), r * a.sin());
+                star.vel = (-star.pos.1 * omega, star.pos.0 * omega);
+            }
         }
     }
 }
@@ -39,10 +172,15 @@ impl Effect for Galaxy {
         "Galaxy"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
         self.stars.clear();
+        self.nbody_live = false;
     }
 
     fn randomize_init(&mut self, rng: &mut StdRng) {
@@ -50,26 +188,29 @@ impl Effect for Galaxy {
         for i in 0..NUM_STARS {
             let r = rng.gen_range(0.01f64..1.0).powf(0.7);
 
-            let arm_angle = if i < NUM_STARS * 85 / 100 {
-                let arm = rng.gen_range(0..NUM_ARMS);
-                let base = arm as f64 * TAU / NUM_ARMS as f64;
-                let spread = rng.gen_range(-1.0f64..1.0) * (0.08 + r * 0.25);
-                base + spread
+            let in_arm = i < NUM_STARS * 85 / 100;
+            let arm_pick = rng.gen_range(0.0..1.0);
+            let spread = if in_arm {
+                rng.gen_range(-1.0f64..1.0) * (0.08 + r * 0.25)
             } else {
-                rng.gen_range(0.0..TAU)
+                0.0
             };
 
             self.stars.push(Star {
                 r,
-                arm_angle,
+                arm_pick,
+                spread,
+                in_arm,
                 brightness: rng.gen_range(0.4..1.0),
                 twinkle_phase: rng.gen_range(0.0..TAU),
                 size: if rng.gen_range(0.0f64..1.0) < 0.12 { 2 } else { 1 },
+                pos: (0.0, 0.0),
+                vel: (0.0, 0.0),
             });
         }
     }
 
-    fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
+    fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width;
         let h = self.height;
         if w == 0 || h == 0 {
@@ -82,6 +223,16 @@ impl Effect for Galaxy {
         let cy = hf / 2.0;
         let scale = cx.min(cy) * 0.85;
         let t = t * self.speed;
+        let nbody = self.motion.round() >= 1.0;
+        if nbody {
+            if !self.nbody_live {
+                self.seed_nbody(t);
+            }
+            self.step_nbody(t, dt * self.speed);
+        } else {
+            self.nbody_live = false;
+        }
+        let comp = Self::companion_pos(t);
 
         for p in pixels.iter_mut() {
             *p = (1, 1, 5);
@@ -92,8 +243,8 @@ impl Effect for Galaxy {
         let cos_tilt = tilt.cos();
         let sin_tilt = tilt.sin();
 
-        // Central glow
-        let glow_r = (scale * 0.18) as i32;
+        // Central glow, growing with the black-hole mass
+        let glow_r = (scale * 0.18 * self.mass.powf(0.3)) as i32;
         for dy in -glow_r..=glow_r {
             for dx in -glow_r..=glow_r {
                 let dist_sq = dx * dx + dy * dy;
@@ -116,13 +267,24 @@ impl Effect for Galaxy {
             }
         }
 
-        for star in &self.stars {
-            let angular_vel = 1.0 / star.r.max(0.05).sqrt();
-            let spiral = star.r * TAU * 0.75 * self.twist;
-            let angle = star.arm_angle + spiral + t * 0.15 * angular_vel;
+        if self.companion > 0.0 {
+            let sx = cx + comp.0 * scale;
+            let sy = cy + comp.1 * cos_tilt * scale;
+            draw_glow(pixels, w, h, (sx, sy), scale * 0.08 * self.companion.sqrt());
+        }
 
-            let gx = star.r * angle.cos();
-            let gy = star.r * angle.sin();
+        for star in &self.stars {
+            let (gx, gy) = if nbody {
+                star.pos
+            } else {
+                let (x, y) = self.wave_pos(star, t);
+                // Tidal tug towards the companion, capped so close passes
+                // bend the arms rather than collapse them
+                let (ax, ay) = Self::pull((x, y), comp, self.companion * 0.01);
+                let len = (ax * ax + ay * ay).sqrt();
+                let k = if len > 0.25 { 0.25 / len } else { 1.0 };
+                (x + ax * k, y + ay * k)
+            };
 
             // Apply tilt
             let proj_x = gx;
@@ -171,6 +333,30 @@ impl Effect for Galaxy {
                 max: 3.0,
                 value: self.twist,
             },
+            ParamDesc {
+                name: "mass".to_string(),
+                min: 0.2,
+                max: 4.0,
+                value: self.mass,
+            },
+            ParamDesc {
+                name: "arms".to_string(),
+                min: 1.0,
+                max: 6.0,
+                value: self.arms,
+            },
+            ParamDesc {
+                name: "companion".to_string(),
+                min: 0.0,
+                max: 3.0,
+                value: self.companion,
+            },
+            ParamDesc {
+                name: "motion".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.motion,
+            },
         ]
     }
 
@@ -178,11 +364,37 @@ impl Effect for Galaxy {
         match name {
             "speed" => self.speed = value,
             "twist" => self.twist = value,
+            "mass" => self.mass = value,
+            "arms" => self.arms = value,
+            "companion" => self.companion = value,
+            "motion" => self.motion = value,
             _ => {}
         }
     }
 }
 
+/// Soft warm glow of radius `r` pixels centered on `(x, y)`.
+fn draw_glow(pixels: &mut [(u8, u8, u8)], w: u32, h: u32, (x, y): (f64, f64), r: f64) {
+    let ri = r.ceil() as i32;
+    for dy in -ri..=ri {
+        for dx in -ri..=ri {
+            let px = x as i32 + dx;
+            let py = y as i32 + dy;
+            if px < 0 || px >= w as i32 || py < 0 || py >= h as i32 {
+                continue;
+            }
+            let d = ((dx * dx + dy * dy) as f64).sqrt() / r.max(1.0);
+            if d < 1.0 {
+                let bright = ((1.0 - d) * (1.0 - d) * 160.0) as u8;
+                let p = &mut pixels[(py as u32 * w + px as u32) as usize];
+                p.0 = p.0.saturating_add(bright);
+                p.1 = p.1.saturating_add((bright as f64 * 0.75) as u8);
+                p.2 = p.2.saturating_add((bright as f64 * 0.6) as u8);
+            }
+        }
+    }
+}
+
 fn star_color(r: f64, brightness: f64) -> (u8, u8, u8) {
     let b = brightness.clamp(0.0, 1.0);
     if r < 0.15 {
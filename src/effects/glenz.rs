@@ -1,3 +1,4 @@
+use crate::color::{self, hsv_to_rgb};
 use crate::effect::{Effect, ParamDesc};
 
 pub struct Glenz {
@@ -5,6 +6,8 @@ pub struct Glenz {
     height: u32,
     rot_speed: f64,
     zoom: f64,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
 }
 
 impl Glenz {
@@ -14,6 +17,7 @@ impl Glenz {
             height: 0,
             rot_speed: 1.0,
             zoom: 1.0,
+            look: (0.0, 0.0),
         }
     }
 }
@@ -73,8 +77,8 @@ impl Effect for Glenz {
         }
 
         let t = t * self.rot_speed;
-        let ay = t * 0.6;
-        let ax = t * 0.4;
+        let ay = t * 0.6 + self.look.0;
+        let ax = t * 0.4 + self.look.1;
         let az = t * 0.25;
 
         let cos_y = ay.cos();
@@ -136,20 +140,15 @@ impl Effect for Glenz {
             let hue = (tri.face_idx as f64 / 20.0 + t * 0.05) % 1.0;
             let (cr, cg, cb) = hsv_to_rgb(hue, 0.7, 0.8);
 
-            // Transparency: scale color down, then add
-            let alpha = 0.35;
-            let ar = (cr as f64 * alpha) as u8;
-            let ag = (cg as f64 * alpha) as u8;
-            let ab = (cb as f64 * alpha) as u8;
-
-            fill_triangle_additive(pixels, w, h, &tri.verts, (ar, ag, ab));
+            // Transparency: add a fraction of the face's light
+            fill_triangle_additive(pixels, w, h, &tri.verts, (cr, cg, cb), 0.12);
         }
 
         // Draw edges for wireframe outline
         for tri in &tris {
             let hue = (tri.face_idx as f64 / 20.0 + t * 0.05) % 1.0;
             let (cr, cg, cb) = hsv_to_rgb(hue, 0.5, 1.0);
-            let edge_color = (cr / 2, cg / 2, cb / 2);
+            let edge_color = (cr, cg, cb);
             for i in 0..3 {
                 let j = (i + 1) % 3;
                 draw_line_additive(
@@ -161,6 +160,7 @@ impl Effect for Glenz {
                     tri.verts[j].0,
                     tri.verts[j].1,
                     edge_color,
+                    0.22,
                 );
             }
         }
@@ -183,6 +183,10 @@ impl Effect for Glenz {
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "rot_speed" => self.rot_speed = value,
@@ -192,13 +196,14 @@ impl Effect for Glenz {
     }
 }
 
-/// Rasterize a filled triangle with additive blending using scanline algorithm
+/// Rasterize a filled triangle, adding `amount` of `color` in linear light
 fn fill_triangle_additive(
     pixels: &mut [(u8, u8, u8)],
     w: u32,
     h: u32,
     verts: &[(f64, f64); 3],
     color: (u8, u8, u8),
+    amount: f32,
 ) {
     // Bounding box
     let min_y = verts[0].1.min(verts[1].1).min(verts[2].1).max(0.0) as i32;
@@ -230,10 +235,7 @@ fn fill_triangle_additive(
             if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
                 let idx = (y as u32 * w + x as u32) as usize;
                 if idx < pixels.len() {
-                    let p = &mut pixels[idx];
-                    p.0 = p.0.saturating_add(color.0);
-                    p.1 = p.1.saturating_add(color.1);
-                    p.2 = p.2.saturating_add(color.2);
+                    pixels[idx] = color::add(pixels[idx], color, amount);
                 }
             }
         }
@@ -249,6 +251,7 @@ fn draw_line_additive(
     x1: f64,
     y1: f64,
     color: (u8, u8, u8),
+    amount: f32,
 ) {
     let mut ix0 = x0 as i32;
     let mut iy0 = y0 as i32;
@@ -265,10 +268,7 @@ fn draw_line_additive(
         if ix0 >= 0 && ix0 < w as i32 && iy0 >= 0 && iy0 < h as i32 {
             let idx = (iy0 as u32 * w + ix0 as u32) as usize;
             if idx < pixels.len() {
-                let p = &mut pixels[idx];
-                p.0 = p.0.saturating_add(color.0);
-                p.1 = p.1.saturating_add(color.1);
-                p.2 = p.2.saturating_add(color.2);
+                pixels[idx] = color::add(pixels[idx], color, amount);
             }
         }
         if ix0 == ix1 && iy0 == iy1 {
@@ -285,20 +285,3 @@ fn draw_line_additive(
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
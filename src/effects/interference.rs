@@ -6,8 +6,24 @@ pub struct Interference {
     height: u32,
     frequency: f64,
     speed: f64,
+    /// Number of wave sources, 2 to 5
+    sources: f64,
+    /// How fast the rings travel outward from each source
+    phase_speed: f64,
+    /// Source positions for the current frame
+    positions: Vec<(f64, f64)>,
 }
 
+/// Circular source paths as (radius, x rate, y rate, phase), the radius a
+/// fraction of the shorter screen side
+const PATHS: [(f64, f64, f64, f64); 5] = [
+    (0.25, 0.4, 0.4, 0.0),
+    (0.3, 0.3, 0.35, PI * 2.0 / 3.0),
+    (0.2, 0.5, 0.45, PI * 4.0 / 3.0),
+    (0.35, 0.25, 0.3, PI / 3.0),
+    (0.15, 0.6, 0.55, PI),
+];
+
 impl Interference {
     pub fn new() -> Self {
         Self {
@@ -15,13 +31,16 @@ impl Interference {
             height: 0,
             frequency: 3.0,
             speed: 1.0,
+            sources: 3.0,
+            phase_speed: 1.0,
+            positions: Vec::new(),
         }
     }
 
     fn palette(v: f64) -> (u8, u8, u8) {
-        // v is in range [-2, 2] from sum of two sin waves, but with 3 sources [-3, 3]
+        // v is the wave sum averaged over the sources, in [-1, 1]
         // Normalize to [0, 1]
-        let n = (v / 3.0 + 1.0) * 0.5;
+        let n = (v + 1.0) * 0.5;
         let n = n.clamp(0.0, 1.0);
 
         // Dark blue -> purple -> cyan -> white
@@ -80,39 +99,36 @@ impl Effect for Interference {
 
         let ts = t * self.speed;
 
-        // Three sources moving in circular paths
-        let r1 = wf.min(hf) * 0.25;
-        let r2 = wf.min(hf) * 0.3;
-        let r3 = wf.min(hf) * 0.2;
-
-        let s1x = cx + r1 * (ts * 0.4).cos();
-        let s1y = cy + r1 * (ts * 0.4).sin();
-
-        let s2x = cx + r2 * (ts * 0.3 + PI * 2.0 / 3.0).cos();
-        let s2y = cy + r2 * (ts * 0.35 + PI * 2.0 / 3.0).sin();
-
-        let s3x = cx + r3 * (ts * 0.5 + PI * 4.0 / 3.0).cos();
-        let s3y = cy + r3 * (ts * 0.45 + PI * 4.0 / 3.0).sin();
+        // Sources moving in circular paths
+        let count = (self.sources.round() as usize).clamp(2, PATHS.len());
+        self.positions.clear();
+        for &(radius, rate_x, rate_y, phase) in &PATHS[..count] {
+            let r = wf.min(hf) * radius;
+            self.positions.push((
+                cx + r * (ts * rate_x + phase).cos(),
+                cy + r * (ts * rate_y + phase).sin(),
+            ));
+        }
 
         let freq = self.frequency * 0.15;
+        let phase = ts * 3.0 * self.phase_speed;
 
         for y in 0..h {
             let fy = y as f64;
             for x in 0..w {
                 let fx = x as f64;
 
-                let d1 = ((fx - s1x) * (fx - s1x) + (fy - s1y) * (fy - s1y)).sqrt();
-                let d2 = ((fx - s2x) * (fx - s2x) + (fy - s2y) * (fy - s2y)).sqrt();
-                let d3 = ((fx - s3x) * (fx - s3x) + (fy - s3y) * (fy - s3y)).sqrt();
-
-                let v1 = (d1 * freq - ts * 3.0).sin();
-                let v2 = (d2 * freq - ts * 3.0).sin();
-                let v3 = (d3 * freq - ts * 3.0).sin();
-
-                let combined = v1 + v2 + v3;
+                let combined: f64 = self
+                    .positions
+                    .iter()
+                    .map(|&(sx, sy)| {
+                        let d = ((fx - sx) * (fx - sx) + (fy - sy) * (fy - sy)).sqrt();
+                        (d * freq - phase).sin()
+                    })
+                    .sum();
 
                 let idx = (y * w + x) as usize;
-                pixels[idx] = Self::palette(combined);
+                pixels[idx] = Self::palette(combined / count as f64);
             }
         }
     }
@@ -131,6 +147,18 @@ impl Effect for Interference {
                 max: 3.0,
                 value: self.speed,
             },
+            ParamDesc {
+                name: "sources".to_string(),
+                min: 2.0,
+                max: PATHS.len() as f64,
+                value: self.sources,
+            },
+            ParamDesc {
+                name: "phase_speed".to_string(),
+                min: 0.0,
+                max: 3.0,
+                value: self.phase_speed,
+            },
         ]
     }
 
@@ -138,6 +166,8 @@ impl Effect for Interference {
         match name {
             "frequency" => self.frequency = value,
             "speed" => self.speed = value,
+            "sources" => self.sources = value,
+            "phase_speed" => self.phase_speed = value,
             _ => {}
         }
     }
@@ -1,10 +1,21 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use std::f64::consts::TAU;
+
+/// Index of the fixed path in the `path` param; the others move `c`.
+const FIXED_PATH: f64 = 4.0;
 
 pub struct Julia {
     width: u32,
     height: u32,
     morph_speed: f64,
     max_iter: u32,
+    /// How `c` moves: 0 drift, 1 circle, 2 cardioid walk, 3 figure-eight,
+    /// 4 fixed at `c_re`/`c_im`
+    path: f64,
+    /// The current `c`, written every frame by the moving paths
+    c_re: f64,
+    c_im: f64,
 }
 
 impl Julia {
@@ -14,6 +25,40 @@ impl Julia {
             height: 0,
             morph_speed: 1.0,
             max_iter: 80,
+            path: 0.0,
+            c_re: -0.75,
+            c_im: 0.1,
+        }
+    }
+
+    /// Where `c` is at time `t` on the selected path.
+    fn path_c(&self, t: f64) -> (f64, f64) {
+        let a = t * self.morph_speed;
+        match self.path.round() as i64 {
+            // Slow radius-0.7885 orbit, the classic Julia animation
+            1 => {
+                let u = a * 0.25;
+                (0.7885 * u.cos(), 0.7885 * u.sin())
+            }
+            // Walk the Mandelbrot main cardioid, breathing slightly in and
+            // out so the set keeps snapping between connected and dust
+            2 => {
+                let u = (a * 0.12) % TAU;
+                let r = 1.0 + 0.05 * (a * 0.7).sin();
+                let re = 0.5 * u.cos() - 0.25 * (2.0 * u).cos();
+                let im = 0.5 * u.sin() - 0.25 * (2.0 * u).sin();
+                (re * r, im * r)
+            }
+            // Figure-eight around the seahorse valley at -0.75
+            3 => {
+                let u = a * 0.2;
+                (-0.75 + 0.12 * u.sin(), 0.15 * (2.0 * u).sin())
+            }
+            4 => (self.c_re, self.c_im),
+            _ => (
+                0.35 * (a * 0.2).cos() - 0.1 * (a * 0.15).sin(),
+                0.35 * (a * 0.2).sin() + 0.1 * (a * 0.3).cos(),
+            ),
         }
     }
 }
@@ -23,6 +68,10 @@ impl Effect for Julia {
         "Julia"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -39,11 +88,9 @@ impl Effect for Julia {
         let hf = h as f64;
         let aspect = wf / hf;
         let max_iter = self.max_iter;
-        let s = self.morph_speed;
-
-        // c parameter traces an interesting path
-        let c_re = 0.35 * (t * s * 0.2).cos() - 0.1 * (t * s * 0.15).sin();
-        let c_im = 0.35 * (t * s * 0.2).sin() + 0.1 * (t * s * 0.3).cos();
+        let (c_re, c_im) = self.path_c(t);
+        self.c_re = c_re;
+        self.c_im = c_im;
 
         let view = 1.5;
 
@@ -102,6 +149,24 @@ impl Effect for Julia {
                 max: 300.0,
                 value: self.max_iter as f64,
             },
+            ParamDesc {
+                name: "path".to_string(),
+                min: 0.0,
+                max: FIXED_PATH,
+                value: self.path,
+            },
+            ParamDesc {
+                name: "c_re".to_string(),
+                min: -1.5,
+                max: 0.5,
+                value: self.c_re,
+            },
+            ParamDesc {
+                name: "c_im".to_string(),
+                min: -1.0,
+                max: 1.0,
+                value: self.c_im,
+            },
         ]
     }
 
@@ -109,24 +174,17 @@ impl Effect for Julia {
         match name {
             "morph_speed" => self.morph_speed = value,
             "max_iter" => self.max_iter = value as u32,
+            "path" => self.path = value,
+            // Steering `c` by hand pins it where it is
+            "c_re" => {
+                self.c_re = value;
+                self.path = FIXED_PATH;
+            }
+            "c_im" => {
+                self.c_im = value;
+                self.path = FIXED_PATH;
+            }
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
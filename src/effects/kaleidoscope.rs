@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::media::SourceImage;
 use std::f64::consts::PI;
 
 pub struct Kaleidoscope {
@@ -6,6 +8,11 @@ pub struct Kaleidoscope {
     height: u32,
     speed: f64,
     segments: f64,
+    /// 0 reflects every other wedge, 1 repeats the first wedge by pure
+    /// rotation
+    mirror: f64,
+    /// Picture cut into wedges instead of the plasma pattern
+    image: Option<SourceImage>,
 }
 
 impl Kaleidoscope {
@@ -15,8 +22,15 @@ impl Kaleidoscope {
             height: 0,
             speed: 1.0,
             segments: 6.0,
+            mirror: 0.0,
+            image: None,
         }
     }
+
+    pub fn with_image(mut self, image: SourceImage) -> Self {
+        self.image = Some(image);
+        self
+    }
 }
 
 impl Effect for Kaleidoscope {
@@ -27,6 +41,9 @@ impl Effect for Kaleidoscope {
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
+        if let Some(image) = &mut self.image {
+            image.fit(width, height);
+        }
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -41,7 +58,8 @@ impl Effect for Kaleidoscope {
         let cx = wf / 2.0;
         let cy = hf / 2.0;
         let t = t * self.speed;
-        let num_segments = self.segments.round().max(2.0) as u32;
+        let num_segments = self.segments.round().max(3.0) as u32;
+        let reflect = self.mirror.round() < 1.0;
         let segment_angle = PI * 2.0 / num_segments as f64;
 
         for y in 0..h {
@@ -56,11 +74,13 @@ impl Effect for Kaleidoscope {
                     angle += PI * 2.0;
                 }
 
-                // Mirror into first segment
+                // Fold into the first segment
                 let seg = angle / segment_angle;
+                let wedge = seg.floor() as u32;
                 let mut local_angle = (seg.fract()) * segment_angle;
-                // Mirror odd segments for seamless reflection
-                if seg as u32 % 2 == 1 {
+                // Reflecting odd wedges keeps neighbors seamless; rotation
+                // shows a hard edge between every pair instead
+                if reflect && wedge % 2 == 1 {
                     local_angle = segment_angle - local_angle;
                 }
 
@@ -69,6 +89,12 @@ impl Effect for Kaleidoscope {
                 let rot = t * 0.2;
                 let sx = r * (local_angle + rot).cos();
                 let sy = r * (local_angle + rot).sin();
+                let idx = (y * w + x) as usize;
+
+                if let Some(image) = &self.image {
+                    pixels[idx] = image.sample(sx, sy);
+                    continue;
+                }
 
                 // Normalize to a reasonable scale
                 let scale = 0.02;
@@ -97,7 +123,6 @@ impl Effect for Kaleidoscope {
 
                 let (cr, cg, cb) = hsv_to_rgb(hue.abs(), sat.clamp(0.0, 1.0), brightness);
 
-                let idx = (y * w + x) as usize;
                 pixels[idx] = (cr, cg, cb);
             }
         }
@@ -113,10 +138,16 @@ impl Effect for Kaleidoscope {
             },
             ParamDesc {
                 name: "segments".to_string(),
-                min: 2.0,
-                max: 12.0,
+                min: 3.0,
+                max: 16.0,
                 value: self.segments,
             },
+            ParamDesc {
+                name: "mirror".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.mirror,
+            },
         ]
     }
 
@@ -124,24 +155,8 @@ impl Effect for Kaleidoscope {
         match name {
             "speed" => self.speed = value,
             "segments" => self.segments = value,
+            "mirror" => self.mirror = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb_f64;
 use crate::effect::{Effect, ParamDesc};
 use std::f64::consts::PI;
 
@@ -19,23 +20,6 @@ impl KefrensBars {
     }
 }
 
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
-    let h = ((h % 1.0) + 1.0) % 1.0;
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    }
-}
-
 impl Effect for KefrensBars {
     fn name(&self) -> &str {
         "Kefrens Bars"
@@ -83,7 +67,7 @@ impl Effect for KefrensBars {
 
                 // Bar color: rainbow gradient along height, shifted per bar
                 let hue = (yf * 1.0 + bi / num_bars as f64 + t * 0.1) % 1.0;
-                let (cr, cg, cb) = hsv_to_rgb(hue, 0.8, 1.0);
+                let (cr, cg, cb) = hsv_to_rgb_f64(hue, 0.8, 1.0);
 
                 let half_w = bar_width / 2.0;
                 let x_start = (x_center - half_w).max(0.0) as u32;
@@ -1,4 +1,7 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::f64::consts::PI;
 
 const MAX_BLOBS: usize = 10;
@@ -8,6 +11,8 @@ pub struct LavaLamp {
     height: u32,
     speed: f64,
     blob_count: f64,
+    /// Mixed into every hash seed; `randomize_init` picks a new layout
+    salt: u32,
 }
 
 struct Blob {
@@ -29,32 +34,24 @@ impl LavaLamp {
             height: 0,
             speed: 0.7,
             blob_count: 6.0,
+            salt: 0,
         }
     }
 
-    /// Deterministic pseudo-random from seed.
-    fn rng(seed: u32) -> f64 {
-        let mut h = seed;
-        h = h.wrapping_mul(747796405).wrapping_add(2891336453);
-        h = ((h >> ((h >> 28).wrapping_add(4))) ^ h).wrapping_mul(277803737);
-        h = h ^ (h >> 22);
-        (h & 0x00FFFFFF) as f64 / 0x01000000 as f64
-    }
-
-    fn make_blobs(count: usize) -> Vec<Blob> {
+    fn make_blobs(count: usize, salt: u32) -> Vec<Blob> {
         let mut blobs = Vec::with_capacity(count);
         for i in 0..count {
-            let seed = i as u32 * 7 + 42;
+            let seed = (i as u32 * 7 + 42) ^ salt;
             blobs.push(Blob {
-                base_x: 0.3 + Self::rng(seed) * 0.4,
-                base_y: 0.2 + Self::rng(seed + 1) * 0.6,
-                radius: 0.06 + Self::rng(seed + 2) * 0.06,
-                freq_y: 0.3 + Self::rng(seed + 3) * 0.5,
-                freq_x: 0.15 + Self::rng(seed + 4) * 0.3,
-                phase_y: Self::rng(seed + 5) * PI * 2.0,
-                phase_x: Self::rng(seed + 6) * PI * 2.0,
-                amp_y: 0.15 + Self::rng(seed + 7) * 0.2,
-                amp_x: 0.04 + Self::rng(seed + 8) * 0.06,
+                base_x: 0.3 + rng::hash_u(seed) * 0.4,
+                base_y: 0.2 + rng::hash_u(seed + 1) * 0.6,
+                radius: 0.06 + rng::hash_u(seed + 2) * 0.06,
+                freq_y: 0.3 + rng::hash_u(seed + 3) * 0.5,
+                freq_x: 0.15 + rng::hash_u(seed + 4) * 0.3,
+                phase_y: rng::hash_u(seed + 5) * PI * 2.0,
+                phase_x: rng::hash_u(seed + 6) * PI * 2.0,
+                amp_y: 0.15 + rng::hash_u(seed + 7) * 0.2,
+                amp_x: 0.04 + rng::hash_u(seed + 8) * 0.06,
             });
         }
         blobs
@@ -95,11 +92,19 @@ impl Effect for LavaLamp {
         "LavaLamp"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
     }
 
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        self.salt = rng.gen();
+    }
+
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width as usize;
         let h = self.height as usize;
@@ -110,7 +115,7 @@ impl Effect for LavaLamp {
         let hf = h as f64;
 
         let count = (self.blob_count as usize).clamp(3, MAX_BLOBS);
-        let blobs = Self::make_blobs(count);
+        let blobs = Self::make_blobs(count, self.salt);
         let ts = t * self.speed;
 
         // Lamp geometry (tall rounded rectangle centered on screen)
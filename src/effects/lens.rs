@@ -1,10 +1,18 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::media::SourceImage;
 
 pub struct Lens {
     width: u32,
     height: u32,
-    lens_size: f64,
-    strength: f64,
+    /// Lens radius as a fraction of a quarter of the shorter frame side
+    radius: f64,
+    /// How strongly the middle of the lens enlarges what's beneath
+    magnify: f64,
+    /// 0 spherical, 1 barrel, 2 fisheye, from gentle to strong bulge
+    distortion: f64,
+    /// Picture under the lens instead of the checkerboard
+    image: Option<SourceImage>,
 }
 
 impl Lens {
@@ -12,8 +20,31 @@ impl Lens {
         Self {
             width: 0,
             height: 0,
-            lens_size: 1.0,
-            strength: 0.5,
+            radius: 1.0,
+            magnify: 0.5,
+            distortion: 0.0,
+            image: None,
+        }
+    }
+
+    pub fn with_image(mut self, image: SourceImage) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    /// Where a point `r` of the way out from the lens center (0..1) samples
+    /// from, as a fraction of `r`. Every profile leaves the rim in place so
+    /// the lens meets the background without a seam.
+    fn displacement(&self, r: f64) -> f64 {
+        let m = self.magnify;
+        let spherical = 1.0 - m * (1.0 - r * r);
+        match self.distortion.round() as u32 {
+            0 => spherical,
+            // Squaring the spherical profile enlarges the middle further
+            1 => spherical * spherical,
+            // Projected onto a hemisphere, magnification grows without bound
+            // toward the center
+            _ => (1.0 - (1.0 - r * r).sqrt()).powf(m),
         }
     }
 }
@@ -26,6 +57,9 @@ impl Effect for Lens {
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
+        if let Some(image) = &mut self.image {
+            image.fit(width, height);
+        }
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -42,7 +76,7 @@ impl Effect for Lens {
         // Lens center on Lissajous path
         let lcx = 0.5 + 0.3 * (t * 0.5).sin();
         let lcy = 0.5 + 0.3 * (t * 0.7).cos();
-        let radius = self.lens_size * dim * 0.25;
+        let radius = self.radius * dim * 0.25;
 
         for y in 0..h {
             let ny = y as f64 / hf;
@@ -58,7 +92,7 @@ impl Effect for Lens {
                 let (sx, sy) = if dist < radius {
                     let norm_dist = dist / radius;
                     // Displacement toward center
-                    let displacement = 1.0 - self.strength * (1.0 - norm_dist * norm_dist);
+                    let displacement = self.displacement(norm_dist);
                     let sample_x = lcx * wf + dx * displacement;
                     let sample_y = lcy * hf + dy * displacement;
                     (sample_x / wf, sample_y / hf)
@@ -66,18 +100,10 @@ impl Effect for Lens {
                     (nx, ny)
                 };
 
-                // Background: checkerboard + plasma tint
-                let check_size = 0.05;
-                let cx = (sx / check_size).floor() as i32;
-                let cy = (sy / check_size).floor() as i32;
-                let checker = ((cx + cy) & 1) as f64;
-
-                // Plasma tint
-                let plasma = (sx * 10.0 + t).sin() * 0.5 + 0.5;
-                let hue = (plasma + t * 0.1) % 1.0;
-                let base_v = 0.3 + checker * 0.4;
-
-                let (mut r, mut g, mut b) = hsv_to_rgb(hue, 0.7, base_v);
+                let (mut r, mut g, mut b) = match &self.image {
+                    Some(image) => image.sample((sx - 0.5) * wf, (sy - 0.5) * hf),
+                    None => background(sx, sy, t),
+                };
 
                 // Edge highlight ring at lens boundary
                 if dist > radius * 0.9 && dist < radius * 1.1 {
@@ -97,42 +123,47 @@ impl Effect for Lens {
     fn params(&self) -> Vec<ParamDesc> {
         vec![
             ParamDesc {
-                name: "lens_size".to_string(),
+                name: "radius".to_string(),
                 min: 0.3,
                 max: 2.0,
-                value: self.lens_size,
+                value: self.radius,
             },
             ParamDesc {
-                name: "strength".to_string(),
+                name: "magnify".to_string(),
                 min: 0.1,
                 max: 0.9,
-                value: self.strength,
+                value: self.magnify,
+            },
+            ParamDesc {
+                name: "distortion".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.distortion,
             },
         ]
     }
 
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
-            "lens_size" => self.lens_size = value,
-            "strength" => self.strength = value,
+            "radius" => self.radius = value,
+            "magnify" => self.magnify = value,
+            "distortion" => self.distortion = value,
             _ => {}
         }
     }
 }
 
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+/// Checkerboard with a plasma tint, at normalized frame position (x, y).
+fn background(x: f64, y: f64, t: f64) -> (u8, u8, u8) {
+    let check_size = 0.05;
+    let cx = (x / check_size).floor() as i32;
+    let cy = (y / check_size).floor() as i32;
+    let checker = ((cx + cy) & 1) as f64;
+
+    // Plasma tint
+    let plasma = (x * 10.0 + t).sin() * 0.5 + 0.5;
+    let hue = (plasma + t * 0.1) % 1.0;
+    let base_v = 0.3 + checker * 0.4;
+
+    hsv_to_rgb(hue, 0.7, base_v)
 }
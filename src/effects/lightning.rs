@@ -1,4 +1,6 @@
+use crate::color;
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
 use rand::rngs::StdRng;
 use rand::Rng;
 
@@ -27,25 +29,6 @@ impl Lightning {
         }
     }
 
-    /// Deterministic hash function.
-    fn hash(mut seed: u32) -> u32 {
-        seed = seed.wrapping_mul(747796405).wrapping_add(2891336453);
-        seed = ((seed >> ((seed >> 28).wrapping_add(4))) ^ seed).wrapping_mul(277803737);
-        seed ^ (seed >> 22)
-    }
-
-    /// Deterministic float from seed in -1.0..1.0.
-    fn hash_f(seed: u32) -> f64 {
-        let h = Self::hash(seed);
-        (h & 0x00FFFFFF) as f64 / 0x00800000 as f64 - 1.0
-    }
-
-    /// Deterministic float from seed in 0.0..1.0.
-    fn hash_u(seed: u32) -> f64 {
-        let h = Self::hash(seed);
-        (h & 0x00FFFFFF) as f64 / 0x01000000 as f64
-    }
-
     /// Generate bolt path from (x0, y0) heading downward.
     fn generate_bolt(
         x0: f64,
@@ -68,12 +51,13 @@ impl Lightning {
                 .wrapping_mul(1000)
                 .wrapping_add(sub_seed.wrapping_mul(100))
                 .wrapping_add(i);
-            let jitter = Self::hash_f(seed) * width * 0.08;
+            let jitter = rng::hash_f(seed) * width * 0.08;
             cx += jitter;
             cy += step_y;
 
-            // Keep within bounds
-            cx = cx.clamp(2.0, width - 2.0);
+            // Keep within bounds, narrowing the margin on tiny frames
+            let margin = width.min(4.0) * 0.5;
+            cx = cx.clamp(margin, width - margin);
 
             segments.push(BoltSegment { x: cx, y: cy });
         }
@@ -94,6 +78,11 @@ impl Lightning {
         if segments.len() < 2 {
             return;
         }
+        let color = (
+            (color.0.clamp(0.0, 1.0) * 255.0) as u8,
+            (color.1.clamp(0.0, 1.0) * 255.0) as u8,
+            (color.2.clamp(0.0, 1.0) * 255.0) as u8,
+        );
         for pair in segments.windows(2) {
             let (x0, y0) = (pair[0].x, pair[0].y);
             let (x1, y1) = (pair[1].x, pair[1].y);
@@ -119,12 +108,8 @@ impl Lightning {
                                 let fade = (1.0 - dist / (thickness + 0.5)).clamp(0.0, 1.0);
                                 let a = alpha * fade;
                                 let idx = (sy as u32 * w + sx as u32) as usize;
-                                let (pr, pg, pb) = pixels[idx];
-                                // Additive blending
-                                let r = (pr as f64 + color.0 * a * 255.0).min(255.0);
-                                let g = (pg as f64 + color.1 * a * 255.0).min(255.0);
-                                let b = (pb as f64 + color.2 * a * 255.0).min(255.0);
-                                pixels[idx] = (r as u8, g as u8, b as u8);
+                                // Additive blending in linear light
+                                pixels[idx] = color::add(pixels[idx], color, a as f32);
                             }
                         }
                     }
@@ -220,11 +205,11 @@ impl Effect for Lightning {
 
         // Generate and draw lightning bolt if visible
         if flash_alpha > 0.01 {
-            let strike_seed = Self::hash(strike_index.wrapping_mul(7919).wrapping_add(self.seed_offset));
+            let strike_seed = rng::hash_u32(strike_index.wrapping_mul(7919).wrapping_add(self.seed_offset));
 
             // Main bolt: top-center to random bottom point
-            let start_x = wf * 0.5 + Self::hash_f(strike_seed) * wf * 0.15;
-            let end_x = wf * 0.2 + Self::hash_u(strike_seed.wrapping_add(1)) * wf * 0.6;
+            let start_x = wf * 0.5 + rng::hash_f(strike_seed) * wf * 0.15;
+            let end_x = wf * 0.2 + rng::hash_u(strike_seed.wrapping_add(1)) * wf * 0.6;
             let _ = end_x; // Target is implicit in the bolt generation
 
             let main_bolt = Self::generate_bolt(
@@ -263,13 +248,13 @@ impl Effect for Lightning {
             for b in 0..num_branches {
                 let branch_seed = strike_seed.wrapping_add(b + 100);
                 // Pick a split point along the main bolt
-                let split_idx_f = Self::hash_u(branch_seed) * 0.6 + 0.1;
+                let split_idx_f = rng::hash_u(branch_seed) * 0.6 + 0.1;
                 let split_idx = ((main_bolt.len() as f64 * split_idx_f) as usize)
                     .min(main_bolt.len().saturating_sub(1));
 
                 let split_point = &main_bolt[split_idx];
                 let branch_end_y =
-                    split_point.y + (hf - split_point.y) * (Self::hash_u(branch_seed + 50) * 0.5 + 0.3);
+                    split_point.y + (hf - split_point.y) * (rng::hash_u(branch_seed + 50) * 0.5 + 0.3);
 
                 let branch = Self::generate_bolt(
                     split_point.x,
@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 
 const TRAIL_LENGTH: usize = 800;
 
@@ -7,6 +9,10 @@ pub struct Lissajous3D {
     height: u32,
     speed: f64,
     complexity: f64,
+    /// Depth-of-field strength; 0 keeps every point sharp
+    dof: f64,
+    /// Depth of the focus plane, from the front (-1) to the back (1)
+    focus: f64,
     trail: Vec<(f64, f64, f64)>, // 3D positions in trail
     trail_head: usize,
     trail_filled: bool,
@@ -19,6 +25,8 @@ impl Lissajous3D {
             height: 0,
             speed: 1.0,
             complexity: 1.0,
+            dof: 0.0,
+            focus: 0.0,
             trail: Vec::new(),
             trail_head: 0,
             trail_filled: false,
@@ -138,6 +146,13 @@ impl Effect for Lissajous3D {
             let hue = (age * 2.0 + t * 0.1) % 1.0;
             let (cr, cg, cb) = hsv_to_rgb(hue, 0.8, brightness);
 
+            if self.dof > 0.0 {
+                let blur = splat::defocus(z2, self.focus, self.dof, scale);
+                let radius = dot_size as f64 * 0.6;
+                splat::soft_dot(pixels, w, h, (sx, sy), radius, blur, (cr, cg, cb));
+                continue;
+            }
+
             // Draw dot
             for dy in 0..dot_size {
                 for dx in 0..dot_size {
@@ -214,6 +229,18 @@ impl Effect for Lissajous3D {
                 max: 3.0,
                 value: self.complexity,
             },
+            ParamDesc {
+                name: "dof".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.dof,
+            },
+            ParamDesc {
+                name: "focus".to_string(),
+                min: -1.0,
+                max: 1.0,
+                value: self.focus,
+            },
         ]
     }
 
@@ -221,24 +248,9 @@ impl Effect for Lissajous3D {
         match name {
             "speed" => self.speed = value,
             "complexity" => self.complexity = value,
+            "dof" => self.dof = value,
+            "focus" => self.focus = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
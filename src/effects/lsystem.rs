@@ -1,13 +1,121 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::f64::consts::PI;
 
+/// Longest string `generate_string` builds; growth stops a generation early
+/// rather than pass it.
+const MAX_SYMBOLS: usize = 4_000_000;
+
+/// Fewest generations a ruleset is drawn at, the bottom of the `generations` range.
+const MIN_GENERATIONS: u32 = 3;
+
+/// Length of `symbols` after rewriting it `gens` times, without building it.
+fn expanded_len(symbols: &[u8], rules: &[(u8, Vec<u8>)], gens: u32) -> u64 {
+    let mut counts = [0u64; 256];
+    for &ch in symbols {
+        counts[ch as usize] += 1;
+    }
+    for _ in 0..gens {
+        let mut next = [0u64; 256];
+        for (ch, &n) in counts.iter().enumerate().filter(|(_, &n)| n > 0) {
+            match rules.iter().find(|(symbol, _)| *symbol as usize == ch) {
+                Some((_, replacement)) => {
+                    for &r in replacement {
+                        next[r as usize] = next[r as usize].saturating_add(n);
+                    }
+                }
+                None => next[ch] = next[ch].saturating_add(n),
+            }
+        }
+        counts = next;
+    }
+    counts.iter().fold(0, |sum, &n| sum.saturating_add(n))
+}
+
+/// A named L-system: axiom, production rules, and turtle settings.
+struct Ruleset {
+    axiom: Vec<u8>,
+    rules: Vec<(u8, Vec<u8>)>,
+    angle_deg: f64,
+    /// Extra generations on top of the `generations` param (slow-growing curves)
+    extra_gens: u32,
+    /// Plants grow up from the ground and get leaves; curves are centered in the sky
+    rooted: bool,
+}
+
+impl Ruleset {
+    fn new(axiom: &str, rules: &[(u8, &str)], angle_deg: f64, extra_gens: u32, rooted: bool) -> Self {
+        Self {
+            axiom: axiom.as_bytes().to_vec(),
+            rules: rules.iter().map(|&(c, r)| (c, r.as_bytes().to_vec())).collect(),
+            angle_deg,
+            extra_gens,
+            rooted,
+        }
+    }
+
+    fn presets() -> Vec<Ruleset> {
+        vec![
+            // Fern
+            Ruleset::new("X", &[(b'X', "F+[[X]-X]-F[-FX]+X"), (b'F', "FF")], 25.0, 0, true),
+            // Bush
+            Ruleset::new("F", &[(b'F', "FF+[+F-F-F]-[-F+F+F]")], 22.5, 0, true),
+            // Tree
+            Ruleset::new("F", &[(b'F', "F[+F]F[-F]F")], 25.7, 0, true),
+            // Koch curve
+            Ruleset::new("F", &[(b'F', "F+F-F-F+F")], 90.0, 0, false),
+            // Dragon curve
+            Ruleset::new("FX", &[(b'X', "X+YF+"), (b'Y', "-FX-Y")], 90.0, 6, false),
+        ]
+    }
+
+    /// Parse rules like `F=FF+[+F-F]`, several separated by `;`.
+    fn parse_custom(spec: &str) -> Result<Ruleset, String> {
+        let mut rules = Vec::new();
+        for part in spec.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (lhs, rhs) = part
+                .split_once('=')
+                .ok_or_else(|| format!("rule {:?} is missing '='", part))?;
+            let lhs = lhs.trim().as_bytes();
+            if lhs.len() != 1 {
+                return Err(format!("rule {:?} must rewrite a single symbol", part));
+            }
+            rules.push((lhs[0], rhs.trim().as_bytes().to_vec()));
+        }
+        let axiom = match rules.first() {
+            Some(&(symbol, _)) => vec![symbol],
+            None => return Err("no rules given".to_string()),
+        };
+        let len = expanded_len(&axiom, &rules, MIN_GENERATIONS);
+        if len > MAX_SYMBOLS as u64 {
+            return Err(format!(
+                "rules grow to {} symbols in {} generations, over the limit of {}; \
+                 use shorter replacements",
+                len, MIN_GENERATIONS, MAX_SYMBOLS
+            ));
+        }
+        Ok(Ruleset {
+            axiom,
+            rules,
+            angle_deg: 22.5,
+            extra_gens: 0,
+            rooted: true,
+        })
+    }
+}
+
 pub struct LSystem {
     width: u32,
     height: u32,
     wind: f64,
     generations: f64,
+    ruleset: f64,
+    rulesets: Vec<Ruleset>,
+    // Generated string cached by (ruleset index, generations)
+    cache_key: Option<(usize, u32)>,
+    lstring: Vec<u8>,
     rng: StdRng,
     angle_seed: u32,
     length_seed: u32,
@@ -28,6 +136,10 @@ impl LSystem {
             height: 0,
             wind: 0.5,
             generations: 4.0,
+            ruleset: 1.0,
+            rulesets: Ruleset::presets(),
+            cache_key: None,
+            lstring: Vec::new(),
             rng: StdRng::seed_from_u64(0),
             angle_seed: 0,
             length_seed: 0,
@@ -35,27 +147,27 @@ impl LSystem {
         }
     }
 
-    /// Deterministic pseudo-random from seed, returns 0.0..1.0.
-    fn hash(seed: u32) -> f64 {
-        let mut h = seed;
-        h = h.wrapping_mul(747796405).wrapping_add(2891336453);
-        h = ((h >> ((h >> 28).wrapping_add(4))) ^ h).wrapping_mul(277803737);
-        h = h ^ (h >> 22);
-        (h & 0x00FFFFFF) as f64 / 0x01000000 as f64
+    /// Add a custom rule string (e.g. `F=FF+[+F-F]`) and select it.
+    pub fn with_rule(mut self, spec: &str) -> Result<Self, String> {
+        self.rulesets.push(Ruleset::parse_custom(spec)?);
+        self.ruleset = (self.rulesets.len() - 1) as f64;
+        Ok(self)
     }
 
-    /// Generate L-system string for given number of generations.
-    fn generate_string(gens: u32) -> Vec<u8> {
-        // Axiom: "F"
-        // Rule: F -> FF+[+F-F-F]-[-F+F+F]
-        let mut current: Vec<u8> = vec![b'F'];
+    /// Generate L-system string by rewriting `axiom` with `rules` for `gens`
+    /// generations, or fewer where the next would pass `MAX_SYMBOLS`.
+    fn generate_string(axiom: &[u8], rules: &[(u8, Vec<u8>)], gens: u32) -> Vec<u8> {
+        let mut current: Vec<u8> = axiom.to_vec();
         for _ in 0..gens {
-            let mut next = Vec::with_capacity(current.len() * 6);
+            let next_len = expanded_len(&current, rules, 1);
+            if next_len > MAX_SYMBOLS as u64 {
+                break;
+            }
+            let mut next = Vec::with_capacity(next_len as usize);
             for &ch in &current {
-                if ch == b'F' {
-                    next.extend_from_slice(b"FF+[+F-F-F]-[-F+F+F]");
-                } else {
-                    next.push(ch);
+                match rules.iter().find(|(symbol, _)| *symbol == ch) {
+                    Some((_, replacement)) => next.extend_from_slice(replacement),
+                    None => next.push(ch),
                 }
             }
             current = next;
@@ -63,6 +175,33 @@ impl LSystem {
         current
     }
 
+    /// Bounding box of the turtle path with unit step length, starting at the
+    /// origin pointing up: (min_x, min_y, max_x, max_y).
+    fn unit_bounds(lstring: &[u8], angle: f64, start_angle: f64) -> (f64, f64, f64, f64) {
+        let (mut x, mut y, mut a) = (0.0f64, 0.0f64, start_angle);
+        let mut stack = Vec::new();
+        let mut bounds = (0.0f64, 0.0f64, 0.0f64, 0.0f64);
+        for &ch in lstring {
+            match ch {
+                b'F' => {
+                    x += a.cos();
+                    y += a.sin();
+                    bounds = (bounds.0.min(x), bounds.1.min(y), bounds.2.max(x), bounds.3.max(y));
+                }
+                b'+' => a += angle,
+                b'-' => a -= angle,
+                b'[' => stack.push((x, y, a)),
+                b']' => {
+                    if let Some(saved) = stack.pop() {
+                        (x, y, a) = saved;
+                    }
+                }
+                _ => {}
+            }
+        }
+        bounds
+    }
+
     /// Draw a line into the pixel buffer with alpha blending.
     fn draw_line(
         pixels: &mut [(u8, u8, u8)],
@@ -147,6 +286,10 @@ impl Effect for LSystem {
         "LSystem"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -197,20 +340,42 @@ impl Effect for LSystem {
             }
         }
 
-        // Generate L-system string
-        let gens = (self.generations as u32).clamp(3, 6);
-        let lstring = Self::generate_string(gens);
+        // Generate L-system string (cached until ruleset or generations change)
+        let rs_idx = (self.ruleset.round() as usize).min(self.rulesets.len() - 1);
+        let ruleset = &self.rulesets[rs_idx];
+        let gens = (self.generations as u32).clamp(MIN_GENERATIONS, 6) + ruleset.extra_gens;
+        if self.cache_key != Some((rs_idx, gens)) {
+            self.lstring = Self::generate_string(&ruleset.axiom, &ruleset.rules, gens);
+            self.cache_key = Some((rs_idx, gens));
+        }
+        let lstring = &self.lstring;
 
-        // Interpret as turtle graphics
-        let base_angle = 22.5 * PI / 180.0;
-        let base_length = hf * 0.18 / (1.7_f64).powi(gens as i32);
-        let start_x = wf * 0.5;
-        let start_y = ground_line as f64;
+        // Interpret as turtle graphics, scaled so the whole figure fits on screen
+        let base_angle = ruleset.angle_deg * PI / 180.0;
+        let start_angle = if ruleset.rooted { -PI / 2.0 + self.lean } else { 0.0 };
+        let (min_x, min_y, max_x, max_y) = Self::unit_bounds(lstring, base_angle, start_angle);
+        let avail_w = wf * 0.9;
+        let avail_h = ground_line as f64 * 0.9;
+        let base_length = (avail_w / (max_x - min_x).max(1.0))
+            .min(avail_h / (max_y - min_y).max(1.0));
+        let (start_x, start_y) = if ruleset.rooted {
+            (
+                wf * 0.5 - (min_x + max_x) * 0.5 * base_length,
+                ground_line as f64,
+            )
+        } else {
+            (
+                wf * 0.5 - (min_x + max_x) * 0.5 * base_length,
+                ground_line as f64 * 0.5 - (min_y + max_y) * 0.5 * base_length,
+            )
+        };
+        let rooted = ruleset.rooted;
+        let total_segments = lstring.iter().filter(|&&c| c == b'F').count().max(1);
 
         let mut state = TurtleState {
             x: start_x,
             y: start_y,
-            angle: -PI / 2.0 + self.lean,
+            angle: start_angle,
             depth: 0,
         };
         let mut stack: Vec<TurtleState> = Vec::new();
@@ -219,7 +384,7 @@ impl Effect for LSystem {
         // First pass: find max depth to scale colors
         {
             let mut d: u32 = 0;
-            for &ch in &lstring {
+            for &ch in lstring {
                 match ch {
                     b'[' => d += 1,
                     b']' => d = d.saturating_sub(1),
@@ -236,16 +401,21 @@ impl Effect for LSystem {
 
         // Second pass: draw
         let mut segment_idx: u32 = 0;
-        for &ch in &lstring {
+        for &ch in lstring {
             match ch {
                 b'F' => {
-                    let depth_frac = state.depth as f64 / max_depth as f64;
+                    // Curves have no branches, so color them along their length instead
+                    let depth_frac = if rooted {
+                        state.depth as f64 / max_depth as f64
+                    } else {
+                        segment_idx as f64 / total_segments as f64
+                    };
 
                     // Per-branch random jitter (consistent across frames, varies between scenes)
                     let angle_jitter =
-                        (Self::hash(segment_idx ^ self.angle_seed) - 0.5) * 0.15;
+                        (rng::hash_u(segment_idx ^ self.angle_seed) - 0.5) * 0.15;
                     let length_jitter =
-                        0.85 + Self::hash(segment_idx ^ self.length_seed) * 0.3;
+                        0.85 + rng::hash_u(segment_idx ^ self.length_seed) * 0.3;
 
                     // Wind sway: angle offset depends on depth and time
                     let wind_offset =
@@ -290,7 +460,7 @@ impl Effect for LSystem {
                 b']' => {
                     // Draw leaf at tip before popping
                     let depth_frac = state.depth as f64 / max_depth as f64;
-                    if depth_frac > 0.6 {
+                    if rooted && depth_frac > 0.6 {
                         let leaf_size = 1.0 + depth_frac * 1.5;
                         let green_var = ((state.x * 7.3 + state.y * 3.1).sin() * 40.0) as i32;
                         let lr = (30 + green_var).clamp(10, 70) as u8;
@@ -322,6 +492,12 @@ impl Effect for LSystem {
                 max: 6.0,
                 value: self.generations,
             },
+            ParamDesc {
+                name: "ruleset".to_string(),
+                min: 0.0,
+                max: (self.rulesets.len() - 1) as f64,
+                value: self.ruleset,
+            },
         ]
     }
 
@@ -329,6 +505,7 @@ impl Effect for LSystem {
         match name {
             "wind" => self.wind = value,
             "generations" => self.generations = value,
+            "ruleset" => self.ruleset = value,
             _ => {}
         }
     }
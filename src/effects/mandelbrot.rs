@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 
 pub struct Mandelbrot {
@@ -5,6 +6,10 @@ pub struct Mandelbrot {
     height: u32,
     zoom_speed: f64,
     max_iter: u32,
+    /// 0 raw iteration count (banded), 1 smooth, 2 histogram-equalized
+    coloring: f64,
+    /// Per pixel: iterations before escape and the smooth escape value
+    escape: Vec<(u32, f64)>,
 }
 
 impl Mandelbrot {
@@ -14,8 +19,32 @@ impl Mandelbrot {
             height: 0,
             zoom_speed: 1.0,
             max_iter: 100,
+            coloring: 1.0,
+            escape: Vec::new(),
         }
     }
+
+    /// Palette position in 0..1 for every escaped iteration count, spread
+    /// by how many pixels escaped at it, so the common counts get most of
+    /// the colors instead of repeating bands.
+    fn equalize(&self) -> Vec<f64> {
+        let max_iter = self.max_iter as usize;
+        let mut counts = vec![0usize; max_iter + 1];
+        for &(iter, _) in &self.escape {
+            if (iter as usize) < max_iter {
+                counts[iter as usize] += 1;
+            }
+        }
+        let total = counts.iter().sum::<usize>().max(1) as f64;
+        let mut cumulative = 0;
+        counts
+            .iter()
+            .map(|&count| {
+                cumulative += count;
+                cumulative as f64 / total
+            })
+            .collect()
+    }
 }
 
 // Target point near the Mandelbrot boundary
@@ -27,6 +56,10 @@ impl Effect for Mandelbrot {
         "Mandelbrot"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -49,6 +82,9 @@ impl Effect for Mandelbrot {
         let cycle_t = t % cycle_period;
         let zoom = 3.0 * (-cycle_t * self.zoom_speed * 0.3).exp();
 
+        // First pass: escape counts, which histogram coloring needs in full
+        // before it can color anything
+        self.escape.resize((w * h) as usize, (0, 0.0));
         for y in 0..h {
             for x in 0..w {
                 let nx = (x as f64 / wf - 0.5) * 2.0 * aspect;
@@ -72,26 +108,39 @@ impl Effect for Mandelbrot {
                     iter += 1;
                 }
 
-                let idx = (y * w + x) as usize;
-
-                if iter == max_iter {
-                    pixels[idx] = (0, 0, 0);
+                let z_mag_sq = z_re * z_re + z_im * z_im;
+                let smooth = if z_mag_sq > 1.0 {
+                    iter as f64 + 1.0 - (z_mag_sq.ln() / 2.0_f64.ln()).ln() / 2.0_f64.ln()
                 } else {
-                    // Smooth coloring
-                    let z_mag_sq = z_re * z_re + z_im * z_im;
-                    let smooth = if z_mag_sq > 1.0 {
-                        iter as f64 + 1.0 - (z_mag_sq.ln() / 2.0_f64.ln()).ln() / 2.0_f64.ln()
-                    } else {
-                        iter as f64
-                    };
-
-                    let hue = (smooth * 0.02 + t * 0.05) % 1.0;
-                    let sat = 0.8;
-                    let val = 1.0;
-                    pixels[idx] = hsv_to_rgb(hue, sat, val);
-                }
+                    iter as f64
+                };
+                self.escape[(y * w + x) as usize] = (iter, smooth);
             }
         }
+
+        // Second pass: color
+        let coloring = self.coloring.round() as i64;
+        let ranks = if coloring == 2 { self.equalize() } else { Vec::new() };
+        for (px, &(iter, smooth)) in pixels.iter_mut().zip(&self.escape) {
+            if iter == max_iter {
+                *px = (0, 0, 0);
+                continue;
+            }
+            let hue = match coloring {
+                0 => iter as f64 * 0.02,
+                2 => {
+                    // Interpolate into the next count's share to stay smooth
+                    let below = if iter == 0 { 0.0 } else { ranks[iter as usize - 1] };
+                    let frac = (smooth - iter as f64).clamp(0.0, 1.0);
+                    below + (ranks[iter as usize] - below) * frac
+                }
+                _ => smooth * 0.02,
+            };
+            let hue = (hue + t * 0.05) % 1.0;
+            let sat = 0.8;
+            let val = 1.0;
+            *px = hsv_to_rgb(hue, sat, val);
+        }
     }
 
     fn params(&self) -> Vec<ParamDesc> {
@@ -108,6 +157,12 @@ impl Effect for Mandelbrot {
                 max: 300.0,
                 value: self.max_iter as f64,
             },
+            ParamDesc {
+                name: "coloring".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.coloring,
+            },
         ]
     }
 
@@ -115,24 +170,8 @@ impl Effect for Mandelbrot {
         match name {
             "zoom_speed" => self.zoom_speed = value,
             "max_iter" => self.max_iter = value as u32,
+            "coloring" => self.coloring = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
@@ -1,12 +1,46 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::font::{self, FONT_HEIGHT, FONT_WIDTH};
 use font8x8::UnicodeFonts;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+/// Glyph pool the rain draws from.
+#[derive(Clone, Copy)]
+pub enum Charset {
+    Katakana,
+    Ascii,
+    Binary,
+}
+
+impl Charset {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "katakana" => Some(Charset::Katakana),
+            "ascii" => Some(Charset::Ascii),
+            "binary" => Some(Charset::Binary),
+            _ => None,
+        }
+    }
+
+    fn glyphs(self) -> Vec<char> {
+        match self {
+            Charset::Katakana => font::KATAKANA.chars().chain('0'..='9').collect(),
+            Charset::Ascii => ('!'..='~').collect(),
+            Charset::Binary => vec!['0', '1'],
+        }
+    }
+}
+
+/// Head colors per `color` theme: green, amber, cyan
+const HEAD_COLORS: [(u8, u8, u8); 3] = [(200, 255, 200), (255, 240, 200), (210, 255, 255)];
+/// Full-brightness trail colors, scaled down along the trail
+const TAIL_COLORS: [(f64, f64, f64); 3] =
+    [(30.0, 200.0, 0.0), (220.0, 130.0, 0.0), (0.0, 180.0, 210.0)];
+
 struct Column {
     head_y: f64,
     speed: f64,
-    trail: Vec<u8>, // ASCII chars in trail
+    trail: Vec<char>,
     active: bool,
 }
 
@@ -15,6 +49,12 @@ pub struct Matrix {
     height: u32,
     speed: f64,
     density: f64,
+    /// Theme index into `HEAD_COLORS`/`TAIL_COLORS`
+    color: f64,
+    charset: Charset,
+    glyphs: Vec<char>,
+    head_color: (u8, u8, u8),
+    tail_color: (f64, f64, f64),
     columns: Vec<Column>,
     rng: StdRng,
 }
@@ -26,23 +66,43 @@ impl Matrix {
             height: 0,
             speed: 1.0,
             density: 0.6,
+            color: 0.0,
+            charset: Charset::Ascii,
+            glyphs: Charset::Ascii.glyphs(),
+            head_color: HEAD_COLORS[0],
+            tail_color: TAIL_COLORS[0],
             columns: Vec::new(),
             rng: StdRng::seed_from_u64(0),
         }
     }
 
+    pub fn with_charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self.glyphs = charset.glyphs();
+        self
+    }
+
+    fn random_glyph(&mut self) -> char {
+        self.glyphs[self.rng.gen_range(0..self.glyphs.len())]
+    }
+
+    fn apply_theme(&mut self) {
+        let theme = (self.color.round() as usize).min(HEAD_COLORS.len() - 1);
+        self.head_color = HEAD_COLORS[theme];
+        self.tail_color = TAIL_COLORS[theme];
+    }
+
     fn init_columns(&mut self) {
         let num_cols = (self.width / 8).max(1);
         self.columns.clear();
 
         for _ in 0..num_cols {
             let trail_len = self.rng.gen_range(8..25);
+            let trail = (0..trail_len).map(|_| self.random_glyph()).collect();
             self.columns.push(Column {
                 head_y: self.rng.gen_range(-(self.height as f64)..0.0),
                 speed: self.rng.gen_range(40.0..120.0),
-                trail: (0..trail_len)
-                    .map(|_| self.rng.gen_range(33..127))
-                    .collect(),
+                trail,
                 active: self.rng.gen::<f64>() < self.density,
             });
         }
@@ -54,10 +114,15 @@ impl Effect for Matrix {
         "Matrix"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
         self.columns.clear();
+        self.apply_theme();
     }
 
     fn randomize_init(&mut self, rng: &mut StdRng) {
@@ -78,8 +143,18 @@ impl Effect for Matrix {
         }
 
         let num_cols = self.columns.len();
+        let head_color = self.head_color;
+        let tail_color = self.tail_color;
 
         for col_idx in 0..num_cols {
+            // Occasional char mutation (2% per frame)
+            if self.columns[col_idx].active && self.rng.gen::<f64>() < 0.02 {
+                let glyph = self.random_glyph();
+                let trail = &mut self.columns[col_idx].trail;
+                let idx = self.rng.gen_range(0..trail.len());
+                trail[idx] = glyph;
+            }
+
             let col = &mut self.columns[col_idx];
 
             if !col.active {
@@ -94,12 +169,6 @@ impl Effect for Matrix {
 
             col.head_y += col.speed * self.speed * dt;
 
-            // Occasional char mutation (2% per frame)
-            if self.rng.gen::<f64>() < 0.02 {
-                let idx = self.rng.gen_range(0..col.trail.len());
-                col.trail[idx] = self.rng.gen_range(33..127);
-            }
-
             let pixel_x = col_idx as u32 * 8;
             if pixel_x >= w {
                 continue;
@@ -122,19 +191,19 @@ impl Effect for Matrix {
                 };
 
                 let (cr, cg, cb) = if ti == 0 {
-                    // Head: bright white-green
-                    (200, 255, 200)
+                    // Head: bright, nearly white
+                    head_color
                 } else {
-                    // Trail: fades to dark green
-                    let g = (200.0 * fade) as u8;
-                    let r = (30.0 * fade) as u8;
-                    (r, g, 0)
+                    // Trail: fades to dark in the theme color
+                    (
+                        (tail_color.0 * fade) as u8,
+                        (tail_color.1 * fade) as u8,
+                        (tail_color.2 * fade) as u8,
+                    )
                 };
 
                 // Render 8×8 glyph
-                let glyph = font8x8::BASIC_FONTS
-                    .get(ch as char)
-                    .unwrap_or([0; 8]);
+                let glyph = cell_glyph(self.charset, ch);
 
                 for gy in 0..8u32 {
                     let py = char_y + gy as i32;
@@ -184,6 +253,12 @@ impl Effect for Matrix {
                 max: 1.0,
                 value: self.density,
             },
+            ParamDesc {
+                name: "color".to_string(),
+                min: 0.0,
+                max: (HEAD_COLORS.len() - 1) as f64,
+                value: self.color,
+            },
         ]
     }
 
@@ -191,7 +266,31 @@ impl Effect for Matrix {
         match name {
             "speed" => self.speed = value,
             "density" => self.density = value,
+            "color" => {
+                self.color = value;
+                self.apply_theme();
+            }
             _ => {}
         }
     }
 }
+
+/// 8x8 cell bitmap (bit 0 = leftmost column). Every glyph of a charset
+/// comes from one font so they match in weight: katakana, digits included,
+/// from the shared 5x7 font centered in the cell, the rest from font8x8.
+fn cell_glyph(charset: Charset, ch: char) -> [u8; 8] {
+    if !matches!(charset, Charset::Katakana) {
+        return font8x8::BASIC_FONTS.get(ch).unwrap_or([0; 8]);
+    }
+    let bits = font::glyph(ch);
+    let left = (8 - font::glyph_width(ch).min(FONT_WIDTH)) / 2;
+    let mut cell = [0u8; 8];
+    for row in 0..FONT_HEIGHT {
+        for col in 0..FONT_WIDTH {
+            if font::pixel_set(&bits, col, row) && left + col < 8 {
+                cell[row as usize] |= 1 << (left + col);
+            }
+        }
+    }
+    cell
+}
@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 use rand::rngs::StdRng;
 use rand::Rng;
@@ -16,6 +17,10 @@ pub struct Metaballs {
     blobs: Vec<Blob>,
     speed: f64,
     threshold: f64,
+    /// Number of contour levels; 0 keeps the smooth fill
+    iso_lines: f64,
+    /// Field samples per pixel, kept for contour gradients
+    field: Vec<f64>,
 }
 
 impl Metaballs {
@@ -26,6 +31,65 @@ impl Metaballs {
             blobs: Vec::new(),
             speed: 1.0,
             threshold: 1.0,
+            iso_lines: 0.0,
+            field: Vec::new(),
+        }
+    }
+
+    /// Field values of the contour levels, spread around `threshold` so
+    /// the outer rings hug each blob and the inner ones nest inside it.
+    fn iso_levels(&self, count: usize) -> Vec<f64> {
+        if count == 1 {
+            return vec![self.threshold];
+        }
+        (0..count)
+            .map(|k| self.threshold * (0.3 + 1.7 * k as f64 / (count - 1) as f64))
+            .collect()
+    }
+
+    /// Draw anti-aliased contours of the sampled field.
+    ///
+    /// Instead of walking marching-squares cells, each pixel estimates its
+    /// distance to a level as |field - level| / |gradient|, which yields the
+    /// same iso-lines with smooth one-pixel edges.
+    fn draw_iso_lines(&self, t: f64, count: usize, pixels: &mut [(u8, u8, u8)]) {
+        let w = self.width as usize;
+        let h = self.height as usize;
+        let levels = self.iso_levels(count);
+        let field = &self.field;
+
+        for y in 0..h {
+            for x in 0..w {
+                let idx = y * w + x;
+                let f = field[idx];
+
+                // Central differences, one-sided at the borders
+                let (x0, x1) = (x.saturating_sub(1), (x + 1).min(w - 1));
+                let (y0, y1) = (y.saturating_sub(1), (y + 1).min(h - 1));
+                let gx = (field[y * w + x1] - field[y * w + x0]) / (x1 - x0).max(1) as f64;
+                let gy = (field[y1 * w + x] - field[y0 * w + x]) / (y1 - y0).max(1) as f64;
+                let grad = (gx * gx + gy * gy).sqrt().max(1e-9);
+
+                // Faint fill so the inside of each band still reads
+                let inside = levels.iter().filter(|&&level| f >= level).count();
+                let band = inside as f64 / count as f64;
+                let mut rgb = (band * 10.0, band * 20.0, 15.0 + band * 25.0);
+
+                for (k, &level) in levels.iter().enumerate() {
+                    let dist = (f - level).abs() / grad;
+                    let coverage = (1.5 - dist).clamp(0.0, 1.0);
+                    if coverage <= 0.0 {
+                        continue;
+                    }
+                    let hue = (t * 0.1 + k as f64 / count as f64 * 0.5) % 1.0;
+                    let (r, g, b) = hsv_to_rgb(hue, 0.7, 1.0);
+                    rgb.0 += (r as f64 - rgb.0) * coverage;
+                    rgb.1 += (g as f64 - rgb.1) * coverage;
+                    rgb.2 += (b as f64 - rgb.2) * coverage;
+                }
+
+                pixels[idx] = (rgb.0 as u8, rgb.1 as u8, rgb.2 as u8);
+            }
         }
     }
 }
@@ -39,6 +103,7 @@ impl Effect for Metaballs {
         self.width = width;
         self.height = height;
         self.blobs.clear();
+        self.field = vec![0.0; (width * height) as usize];
     }
 
     fn randomize_init(&mut self, rng: &mut StdRng) {
@@ -74,6 +139,8 @@ impl Effect for Metaballs {
             })
             .collect();
 
+        let iso_count = self.iso_lines.round() as usize;
+
         for y in 0..h {
             let ny = y as f64 / h as f64;
             for x in 0..w {
@@ -90,6 +157,11 @@ impl Effect for Metaballs {
 
                 let idx = (y * w + x) as usize;
 
+                if iso_count > 0 {
+                    self.field[idx] = field;
+                    continue;
+                }
+
                 // Three-zone coloring
                 if field < self.threshold * 0.3 {
                     // Dark background with subtle tint
@@ -120,6 +192,10 @@ impl Effect for Metaballs {
                 }
             }
         }
+
+        if iso_count > 0 {
+            self.draw_iso_lines(t, iso_count, pixels);
+        }
     }
 
     fn params(&self) -> Vec<ParamDesc> {
@@ -136,6 +212,12 @@ impl Effect for Metaballs {
                 max: 3.0,
                 value: self.threshold,
             },
+            ParamDesc {
+                name: "iso_lines".to_string(),
+                min: 0.0,
+                max: 8.0,
+                value: self.iso_lines,
+            },
         ]
     }
 
@@ -143,24 +225,8 @@ impl Effect for Metaballs {
         match name {
             "speed" => self.speed = value,
             "threshold" => self.threshold = value,
+            "iso_lines" => self.iso_lines = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
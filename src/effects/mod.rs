@@ -61,3 +61,100 @@ pub mod neon;
 pub mod rain;
 pub mod lavalamp;
 pub mod lsystem;
+pub mod scripted;
+pub mod compose;
+
+use crate::effect::Effect;
+
+type Constructor = fn() -> Box<dyn Effect>;
+
+/// Every effect by display name, with a default-configured constructor.
+const REGISTRY: &[(&str, Constructor)] = &[
+    ("Plasma", || Box::new(plasma::Plasma::new())),
+    ("Starfield", || Box::new(starfield::Starfield::new())),
+    ("Scroller", || {
+        Box::new(scroller::Scroller::new("TERMDEMO *** GREETS TO ALL DEMOSCENERS!   "))
+    }),
+    ("Fire", || Box::new(fire::Fire::new())),
+    ("Tunnel", || Box::new(tunnel::Tunnel::new())),
+    ("Rotozoom", || Box::new(rotozoom::Rotozoom::new())),
+    ("Metaballs", || Box::new(metaballs::Metaballs::new())),
+    ("Moire", || Box::new(moire::Moire::new())),
+    ("RasterBars", || Box::new(rasterbars::RasterBars::new())),
+    ("CopperBars", || Box::new(copperbars::CopperBars::new())),
+    ("Twister", || Box::new(twister::Twister::new())),
+    ("Lens", || Box::new(lens::Lens::new())),
+    ("Mandelbrot", || Box::new(mandelbrot::Mandelbrot::new())),
+    ("Julia", || Box::new(julia::Julia::new())),
+    ("Wireframe", || Box::new(wireframe::Wireframe::new())),
+    ("DotSphere", || Box::new(dotsphere::DotSphere::new())),
+    ("Water", || Box::new(water::Water::new())),
+    ("GameOfLife", || Box::new(gameoflife::GameOfLife::new())),
+    ("Fountain", || Box::new(fountain::Fountain::new())),
+    ("Matrix", || Box::new(matrix::Matrix::new())),
+    ("VoxelLandscape", || Box::new(voxel::VoxelLandscape::new())),
+    ("Shadebobs", || Box::new(shadebobs::Shadebobs::new())),
+    ("BumpMapping", || Box::new(bumpmapping::BumpMapping::new())),
+    ("Raymarcher", || Box::new(raymarcher::Raymarcher::new())),
+    ("Glenz", || Box::new(glenz::Glenz::new())),
+    ("Kaleidoscope", || Box::new(kaleidoscope::Kaleidoscope::new())),
+    ("Boids", || Box::new(boids::Boids::new())),
+    ("Voronoi", || Box::new(voronoi::Voronoi::new())),
+    ("Fireworks", || Box::new(fireworks::Fireworks::new())),
+    ("Lissajous3D", || Box::new(lissajous::Lissajous3D::new())),
+    ("Galaxy", || Box::new(galaxy::Galaxy::new())),
+    ("TorusKnot", || Box::new(torusknot::TorusKnot::new())),
+    ("BoingBall", || Box::new(boingball::BoingBall::new())),
+    ("SineScroller", || Box::new(sinescroller::SineScroller::new())),
+    ("CopperFlag", || Box::new(copperflag::CopperFlag::new())),
+    ("Wolfenstein", || Box::new(wolfenstein::Wolfenstein::new())),
+    ("FilledVector", || Box::new(filledvector::FilledVector::new())),
+    ("Morph", || Box::new(morph::Morph::new())),
+    ("Aurora Borealis", || Box::new(aurora::Aurora::new())),
+    ("Reaction-Diffusion", || Box::new(reaction::ReactionDiffusion::new())),
+    ("Fluid Simulation", || Box::new(fluidsim::FluidSim::new())),
+    ("Snowfall", || Box::new(snowfall::Snowfall::new())),
+    ("Lightning", || Box::new(lightning::Lightning::new())),
+    ("FractalZoom", || Box::new(fractalzoom::FractalZoom::new())),
+    ("Spirograph", || Box::new(spirograph::Spirograph::new())),
+    ("CellularAutomata", || Box::new(cellular::CellularAutomata::new())),
+    ("Truchet", || Box::new(truchet::Truchet::new())),
+    ("Oscilloscope", || Box::new(oscilloscope::Oscilloscope::new())),
+    ("Dot Tunnel", || Box::new(dottunnel::DotTunnel::new())),
+    ("Kefrens Bars", || Box::new(kefrensbars::KefrensBars::new())),
+    ("Parallax Landscape", || Box::new(parallax::Parallax::new())),
+    ("Cube Field", || Box::new(cubefield::CubeField::new())),
+    ("Cloth Simulation", || Box::new(clothsim::ClothSim::new())),
+    ("Flow Field", || Box::new(flowfield::FlowField::new())),
+    ("Pixel Sort", || Box::new(pixelsort::PixelSort::new())),
+    ("Pendulum Wave", || Box::new(pendulum::PendulumWave::new())),
+    ("Sierpinski", || Box::new(sierpinski::Sierpinski::new())),
+    ("Interference", || Box::new(interference::Interference::new())),
+    ("Terrain", || Box::new(terrain::Terrain::new())),
+    ("Neon", || Box::new(neon::Neon::new())),
+    ("Rain", || Box::new(rain::Rain::new())),
+    ("LavaLamp", || Box::new(lavalamp::LavaLamp::new())),
+    ("LSystem", || Box::new(lsystem::LSystem::new())),
+];
+
+/// Lowercase alphanumerics only, so "dot-tunnel" and "Dot Tunnel" match.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Display names of all registered effects.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    REGISTRY.iter().map(|&(name, _)| name)
+}
+
+/// Construct an effect by name, ignoring case, spaces and punctuation.
+pub fn create(name: &str) -> Option<Box<dyn Effect>> {
+    let key = normalize_name(name);
+    REGISTRY
+        .iter()
+        .find(|(entry, _)| normalize_name(entry) == key)
+        .map(|(_, make)| make())
+}
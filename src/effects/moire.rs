@@ -6,8 +6,24 @@ pub struct Moire {
     height: u32,
     speed: f64,
     frequency: f64,
+    /// Number of ring centers, 2 to 5
+    sources: f64,
+    /// How fast the rings travel outward; 0 keeps them still
+    phase_speed: f64,
+    /// Ring centers for the current frame, normalized 0–1
+    positions: Vec<(f64, f64)>,
 }
 
+/// Lissajous paths for the ring centers as (x rate, x phase, y rate,
+/// y phase). All rates are multiples of 0.1 so the loop period holds.
+const PATHS: [(f64, f64, f64, f64); 5] = [
+    (0.7, 0.0, 0.9, 0.0),
+    (1.1, 2.0, 0.8, 1.0),
+    (0.6, 4.0, 1.3, 3.0),
+    (0.9, 1.0, 0.5, 5.0),
+    (1.2, 3.0, 0.7, 2.0),
+];
+
 impl Moire {
     pub fn new() -> Self {
         Self {
@@ -15,6 +31,9 @@ impl Moire {
             height: 0,
             speed: 1.0,
             frequency: 1.0,
+            sources: 3.0,
+            phase_speed: 0.0,
+            positions: Vec::new(),
         }
     }
 }
@@ -24,6 +43,15 @@ impl Effect for Moire {
         "Moire"
     }
 
+    fn loop_period(&self) -> Option<f64> {
+        // Ring paths move at multiples of 0.1 and the hue makes 5 full cycles
+        if self.phase_speed == 0.0 {
+            Some(20.0 * PI / self.speed)
+        } else {
+            None
+        }
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -39,13 +67,17 @@ impl Effect for Moire {
         let t = t * self.speed;
         let freq = self.frequency * 40.0;
 
-        // Three ring centers on Lissajous paths (normalized 0–1)
-        let cx0 = 0.5 + 0.3 * (t * 0.7).sin();
-        let cy0 = 0.5 + 0.3 * (t * 0.9).cos();
-        let cx1 = 0.5 + 0.3 * (t * 1.1 + 2.0).sin();
-        let cy1 = 0.5 + 0.3 * (t * 0.8 + 1.0).cos();
-        let cx2 = 0.5 + 0.3 * (t * 0.6 + 4.0).sin();
-        let cy2 = 0.5 + 0.3 * (t * 1.3 + 3.0).cos();
+        let phase = t * 3.0 * self.phase_speed;
+
+        // Ring centers on Lissajous paths (normalized 0–1)
+        let count = (self.sources.round() as usize).clamp(2, PATHS.len());
+        self.positions.clear();
+        for &(rate_x, phase_x, rate_y, phase_y) in &PATHS[..count] {
+            self.positions.push((
+                0.5 + 0.3 * (t * rate_x + phase_x).sin(),
+                0.5 + 0.3 * (t * rate_y + phase_y).cos(),
+            ));
+        }
 
         let wf = w as f64;
         let hf = h as f64;
@@ -55,19 +87,18 @@ impl Effect for Moire {
             for x in 0..w {
                 let nx = x as f64 / wf;
 
-                let d0 = ((nx - cx0).powi(2) + (ny - cy0).powi(2)).sqrt();
-                let d1 = ((nx - cx1).powi(2) + (ny - cy1).powi(2)).sqrt();
-                let d2 = ((nx - cx2).powi(2) + (ny - cy2).powi(2)).sqrt();
-
-                let p0 = (d0 * freq).sin();
-                let p1 = (d1 * freq).sin();
-                let p2 = (d2 * freq).sin();
-
-                let v = p0 * p1 * p2;
+                let v: f64 = self
+                    .positions
+                    .iter()
+                    .map(|&(cx, cy)| {
+                        let d = ((nx - cx).powi(2) + (ny - cy).powi(2)).sqrt();
+                        (d * freq - phase).sin()
+                    })
+                    .product();
                 let v = v * 0.5 + 0.5; // normalize to 0–1
 
-                // Cosine palette with time hue cycling
-                let hue = t * 0.15;
+                // Cosine palette with time hue cycling (period 2, commensurate with the rings)
+                let hue = t / (2.0 * PI);
                 let r = (0.5 + 0.5 * (PI * (v * 2.0 + hue)).cos()).clamp(0.0, 1.0);
                 let g = (0.5 + 0.5 * (PI * (v * 2.0 + hue + 0.33)).cos()).clamp(0.0, 1.0);
                 let b = (0.5 + 0.5 * (PI * (v * 2.0 + hue + 0.67)).cos()).clamp(0.0, 1.0);
@@ -96,6 +127,18 @@ impl Effect for Moire {
                 max: 4.0,
                 value: self.frequency,
             },
+            ParamDesc {
+                name: "sources".to_string(),
+                min: 2.0,
+                max: PATHS.len() as f64,
+                value: self.sources,
+            },
+            ParamDesc {
+                name: "phase_speed".to_string(),
+                min: 0.0,
+                max: 3.0,
+                value: self.phase_speed,
+            },
         ]
     }
 
@@ -103,6 +146,8 @@ impl Effect for Moire {
         match name {
             "speed" => self.speed = value,
             "frequency" => self.frequency = value,
+            "sources" => self.sources = value,
+            "phase_speed" => self.phase_speed = value,
             _ => {}
         }
     }
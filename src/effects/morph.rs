@@ -1,8 +1,12 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use std::f64::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
 
 const NUM_POINTS: usize = 1014;
-const NUM_SHAPES: usize = 3;
 const TRANSITION_TIME: f64 = 3.0;
 const HOLD_TIME: f64 = 2.0;
 const CYCLE_TIME: f64 = TRANSITION_TIME + HOLD_TIME;
@@ -12,7 +16,13 @@ pub struct Morph {
     height: u32,
     speed: f64,
     point_size: f64,
+    /// Depth-of-field strength; 0 keeps every point sharp
+    dof: f64,
+    /// Depth of the focus plane, from the front (-1) to the back (1)
+    focus: f64,
     shapes: Vec<Vec<[f64; 3]>>,
+    /// User point cloud appended to the built-in shapes, already resampled
+    target: Option<Vec<[f64; 3]>>,
 }
 
 impl Morph {
@@ -22,26 +32,100 @@ impl Morph {
             height: 0,
             speed: 1.0,
             point_size: 1.0,
+            dof: 0.0,
+            focus: 0.0,
             shapes: Vec::new(),
+            target: None,
         }
     }
 
-    fn generate_shapes() -> Vec<Vec<[f64; 3]>> {
-        let mut shapes = Vec::with_capacity(NUM_SHAPES);
+    /// Add a loaded point cloud (see `load_xyz`) as an extra morph target.
+    pub fn with_target(mut self, points: Vec<[f64; 3]>) -> Self {
+        self.target = Some(points);
+        self
+    }
+
+    fn generate_shapes(&self) -> Vec<Vec<[f64; 3]>> {
+        let mut shapes = vec![
+            generate_sphere(NUM_POINTS),
+            generate_cube(NUM_POINTS),
+            generate_torus(NUM_POINTS),
+            generate_cone(NUM_POINTS),
+            generate_helix(NUM_POINTS),
+        ];
+        if let Some(target) = &self.target {
+            shapes.push(target.clone());
+        }
 
-        // Shape 0: Sphere
-        shapes.push(generate_sphere(NUM_POINTS));
+        shapes
+    }
+}
 
-        // Shape 1: Cube
-        shapes.push(generate_cube(NUM_POINTS));
+/// Load an `.xyz` point cloud (one `x y z` triple per line, `#` comments,
+/// extra columns ignored), centered, scaled to the unit sphere and
+/// resampled to the morph point count.
+pub fn load_xyz(path: &Path) -> io::Result<Vec<[f64; 3]>> {
+    let text = fs::read_to_string(path)?;
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let mut points = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let coords: Vec<f64> = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|f| !f.is_empty())
+            .take(3)
+            .map(|f| f.parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid(format!("line {}: bad coordinate", i + 1)))?;
+        if coords.len() < 3 || coords.iter().any(|c| !c.is_finite()) {
+            return Err(invalid(format!("line {}: expected x y z", i + 1)));
+        }
+        points.push([coords[0], coords[1], coords[2]]);
+    }
+    if points.is_empty() {
+        return Err(invalid("no points found".to_string()));
+    }
 
-        // Shape 2: Torus
-        shapes.push(generate_torus(NUM_POINTS));
+    normalize_cloud(&mut points);
+    Ok(resample(&points, NUM_POINTS))
+}
 
-        shapes
+/// Center on the centroid and scale the farthest point to radius 1.
+fn normalize_cloud(points: &mut [[f64; 3]]) {
+    let n = points.len() as f64;
+    let mut center = [0.0; 3];
+    for p in points.iter() {
+        for k in 0..3 {
+            center[k] += p[k] / n;
+        }
+    }
+    let radius = points
+        .iter()
+        .map(|p| {
+            let (dx, dy, dz) = (p[0] - center[0], p[1] - center[1], p[2] - center[2]);
+            (dx * dx + dy * dy + dz * dz).sqrt()
+        })
+        .fold(0.0, f64::max);
+    let scale = if radius > 0.0 { 1.0 / radius } else { 1.0 };
+    for p in points.iter_mut() {
+        for k in 0..3 {
+            p[k] = (p[k] - center[k]) * scale;
+        }
     }
 }
 
+/// Pick `count` points evenly across the cloud. Sparse clouds repeat each
+/// point over its nearest slots, so every morph index stays valid.
+fn resample(points: &[[f64; 3]], count: usize) -> Vec<[f64; 3]> {
+    (0..count)
+        .map(|i| points[i * points.len() / count])
+        .collect()
+}
+
 /// Fibonacci sphere distribution for even point placement
 fn generate_sphere(count: usize) -> Vec<[f64; 3]> {
     // Match cube grid size: side²×6
@@ -113,6 +197,54 @@ fn generate_torus(count: usize) -> Vec<[f64; 3]> {
         .collect()
 }
 
+/// Points on a cone's slanted surface plus its base disc, apex up
+fn generate_cone(count: usize) -> Vec<[f64; 3]> {
+    let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+    let radius = 0.9;
+    // Lateral area vs. base area for radius 0.9, height 2 decides the split
+    let slant = (radius * radius + 4.0_f64).sqrt();
+    let lateral = (count as f64 * slant / (slant + radius)) as usize;
+
+    (0..count)
+        .map(|i| {
+            let theta = i as f64 * golden_angle;
+            if i < lateral {
+                // sqrt keeps density even as the circumference grows
+                let v = ((i as f64 + 0.5) / lateral as f64).sqrt();
+                let r = radius * v;
+                [r * theta.cos(), -1.0 + 2.0 * v, r * theta.sin()]
+            } else {
+                let f = (i - lateral) as f64 + 0.5;
+                let r = radius * (f / (count - lateral) as f64).sqrt();
+                [r * theta.cos(), 1.0, r * theta.sin()]
+            }
+        })
+        .collect()
+}
+
+/// Points on a thin tube wound into a helix along the vertical axis
+fn generate_helix(count: usize) -> Vec<[f64; 3]> {
+    let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+    let turns = 3.0;
+    let coil_radius = 0.65;
+    let tube_radius = 0.18;
+
+    (0..count)
+        .map(|i| {
+            let f = (i as f64 + 0.5) / count as f64;
+            let a = f * turns * 2.0 * PI;
+            let around = i as f64 * golden_angle;
+            // Offset from the coil center: radially outward and vertically
+            let r = coil_radius + tube_radius * around.cos();
+            [
+                r * a.cos(),
+                -1.0 + 2.0 * f + tube_radius * around.sin(),
+                r * a.sin(),
+            ]
+        })
+        .collect()
+}
+
 fn lerp(a: f64, b: f64, t: f64) -> f64 {
     a + (b - a) * t
 }
@@ -128,10 +260,14 @@ impl Effect for Morph {
         "Morph"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
-        self.shapes = Self::generate_shapes();
+        self.shapes = self.generate_shapes();
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -159,13 +295,14 @@ impl Effect for Morph {
         }
 
         let ts = t * self.speed;
-        let total_cycle = CYCLE_TIME * NUM_SHAPES as f64;
+        let num_shapes = self.shapes.len();
+        let total_cycle = CYCLE_TIME * num_shapes as f64;
         let cycle_pos = ts % total_cycle;
 
         // Determine which shape we're on and the transition progress
         let shape_cycle = cycle_pos / CYCLE_TIME;
-        let current_shape = shape_cycle.floor() as usize % NUM_SHAPES;
-        let next_shape = (current_shape + 1) % NUM_SHAPES;
+        let current_shape = shape_cycle.floor() as usize % num_shapes;
+        let next_shape = (current_shape + 1) % num_shapes;
         let time_in_cycle = cycle_pos - current_shape as f64 * CYCLE_TIME;
 
         let morph_t = if time_in_cycle < HOLD_TIME {
@@ -220,28 +357,38 @@ impl Effect for Morph {
 
             // Draw point with size based on depth and point_size param
             let dot_size = (point_radius * persp * 1.2).max(0.5);
-            let half = dot_size.ceil() as i32;
-
+            if self.dof > 0.0 {
+                let blur = splat::defocus(z2, self.focus, self.dof, proj_scale);
+                splat::soft_dot(pixels, w, h, (sx, sy), dot_size, blur, (cr, cg, cb));
+                continue;
+            }
+            // Measure from the exact projected position rather than the
+            // pixel it falls in, so slow rotation glides instead of stepping
+            if dot_size < 1.0 {
+                splat::splat_point(pixels, w, h, (sx, sy), (cr, cg, cb));
+                continue;
+            }
+            let half = dot_size.ceil() as i32 + 1;
+            let (ix, iy) = (sx.floor() as i32, sy.floor() as i32);
             for dy in -half..=half {
                 for dx in -half..=half {
-                    let dist_sq = (dx * dx + dy * dy) as f64;
-                    if dist_sq <= dot_size * dot_size {
-                        let draw_x = sx as i32 + dx;
-                        let draw_y = sy as i32 + dy;
-                        if draw_x >= 0
-                            && draw_x < w as i32
-                            && draw_y >= 0
-                            && draw_y < h as i32
-                        {
-                            let idx = (draw_y as u32 * w + draw_x as u32) as usize;
-                            if idx < pixels.len() {
-                                let p = &mut pixels[idx];
-                                // Additive-like blending for glow
-                                p.0 = p.0.max(cr);
-                                p.1 = p.1.max(cg);
-                                p.2 = p.2.max(cb);
-                            }
-                        }
+                    let (draw_x, draw_y) = (ix + dx, iy + dy);
+                    if draw_x < 0 || draw_x >= w as i32 || draw_y < 0 || draw_y >= h as i32 {
+                        continue;
+                    }
+                    let ex = draw_x as f64 + 0.5 - sx;
+                    let ey = draw_y as f64 + 0.5 - sy;
+                    // Coverage fades over the last pixel of the rim
+                    let cover = (dot_size + 0.5 - (ex * ex + ey * ey).sqrt()).min(1.0);
+                    if cover <= 0.0 {
+                        continue;
+                    }
+                    let idx = (draw_y as u32 * w + draw_x as u32) as usize;
+                    if let Some(p) = pixels.get_mut(idx) {
+                        // Additive-like blending for glow
+                        p.0 = p.0.max((cr as f64 * cover) as u8);
+                        p.1 = p.1.max((cg as f64 * cover) as u8);
+                        p.2 = p.2.max((cb as f64 * cover) as u8);
                     }
                 }
             }
@@ -262,6 +409,18 @@ impl Effect for Morph {
                 max: 2.0,
                 value: self.point_size,
             },
+            ParamDesc {
+                name: "dof".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.dof,
+            },
+            ParamDesc {
+                name: "focus".to_string(),
+                min: -1.0,
+                max: 1.0,
+                value: self.focus,
+            },
         ]
     }
 
@@ -269,29 +428,9 @@ impl Effect for Morph {
         match name {
             "speed" => self.speed = value,
             "point_size" => self.point_size = value,
+            "dof" => self.dof = value,
+            "focus" => self.focus = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let h = ((h % 1.0) + 1.0) % 1.0;
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let tv = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, tv, p),
-        1 => (q, v, p),
-        2 => (p, v, tv),
-        3 => (p, q, v),
-        4 => (tv, p, v),
-        _ => (v, p, q),
-    };
-    (
-        (r * 255.0).clamp(0.0, 255.0) as u8,
-        (g * 255.0).clamp(0.0, 255.0) as u8,
-        (b * 255.0).clamp(0.0, 255.0) as u8,
-    )
-}
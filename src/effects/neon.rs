@@ -1,4 +1,6 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::font::{self, FONT_HEIGHT, FONT_WIDTH};
+use crate::rng;
 use std::f64::consts::PI;
 
 pub struct Neon {
@@ -13,28 +15,6 @@ pub struct Neon {
     brick_bg: Vec<(u8, u8, u8)>,
 }
 
-/// Simple 5x7 bitmap font for "DEMO".
-const FONT_WIDTH: usize = 5;
-const FONT_HEIGHT: usize = 7;
-
-fn char_bitmap(c: char) -> [u8; 7] {
-    match c {
-        'D' => [
-            0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110,
-        ],
-        'E' => [
-            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
-        ],
-        'M' => [
-            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
-        ],
-        'O' => [
-            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
-        ],
-        _ => [0; 7],
-    }
-}
-
 impl Neon {
     pub fn new() -> Self {
         Self {
@@ -49,15 +29,6 @@ impl Neon {
         }
     }
 
-    /// Deterministic pseudo-random from seed.
-    fn rng(seed: u32) -> f64 {
-        let mut h = seed;
-        h = h.wrapping_mul(747796405).wrapping_add(2891336453);
-        h = ((h >> ((h >> 28).wrapping_add(4))) ^ h).wrapping_mul(277803737);
-        h = h ^ (h >> 22);
-        (h & 0x00FFFFFF) as f64 / 0x01000000 as f64
-    }
-
     fn build_brick_bg(w: u32, h: u32) -> Vec<(u8, u8, u8)> {
         let mut bg = vec![(0u8, 0u8, 0u8); (w * h) as usize];
         let brick_w = 8;
@@ -75,7 +46,7 @@ impl Neon {
                     bg[(y * w + x) as usize] = mortar;
                 } else {
                     let seed = (row * 997 + (x as usize + offset) / brick_w) as u32;
-                    let variation = Self::rng(seed) * 30.0 - 15.0;
+                    let variation = rng::hash_u(seed) * 30.0 - 15.0;
                     let r = (70.0 + variation).clamp(30.0, 110.0) as u8;
                     let g = (40.0 + variation * 0.6).clamp(20.0, 70.0) as u8;
                     let b = (35.0 + variation * 0.4).clamp(15.0, 60.0) as u8;
@@ -146,11 +117,11 @@ impl Neon {
 
         let mut text_pts: Vec<(f64, f64)> = Vec::new();
         for (ci, ch) in text.chars().enumerate() {
-            let bmp = char_bitmap(ch);
+            let bmp = font::glyph(ch);
             let ox = text_start_x + ci as f64 * (FONT_WIDTH as f64 + 1.0) * text_scale;
             for row in 0..FONT_HEIGHT {
                 for col in 0..FONT_WIDTH {
-                    if (bmp[row] >> (FONT_WIDTH - 1 - col)) & 1 == 1 {
+                    if font::pixel_set(&bmp, col, row) {
                         let px = ox + col as f64 * text_scale + text_scale * 0.5;
                         let py = text_start_y + row as f64 * text_scale + text_scale * 0.5;
                         text_pts.push((px, py));
@@ -159,65 +130,22 @@ impl Neon {
             }
         }
 
-        // Compute glow for each pixel
+        // Glow per shape from its distance field; colors are pink, cyan, blue-white
         let glow_radius = 15.0_f64;
-        let glow_radius_sq = glow_radius * glow_radius;
-
-        for y in 0..h {
-            for x in 0..w {
-                let px = x as f64 + 0.5;
-                let py = y as f64 + 0.5;
-                let idx = (y * w + x) as usize;
-
-                // Circle glow (pink: R=1.0, G=0.2, B=0.6)
-                let mut min_d2 = f64::MAX;
-                for &(nx, ny) in &circle_pts {
-                    let dx = px - nx;
-                    let dy = py - ny;
-                    let d2 = dx * dx + dy * dy;
-                    if d2 < min_d2 {
-                        min_d2 = d2;
-                    }
-                }
-                if min_d2 < glow_radius_sq {
-                    let glow = 1.0 / (1.0 + min_d2 * 0.15);
-                    gr[idx] += glow * 1.0;
-                    gg[idx] += glow * 0.2;
-                    gb[idx] += glow * 0.6;
-                }
-
-                // Triangle glow (cyan: R=0.1, G=0.9, B=1.0)
-                min_d2 = f64::MAX;
-                for &(nx, ny) in &tri_pts {
-                    let dx = px - nx;
-                    let dy = py - ny;
-                    let d2 = dx * dx + dy * dy;
-                    if d2 < min_d2 {
-                        min_d2 = d2;
-                    }
-                }
-                if min_d2 < glow_radius_sq {
-                    let glow = 1.0 / (1.0 + min_d2 * 0.15);
-                    gr[idx] += glow * 0.1;
-                    gg[idx] += glow * 0.9;
-                    gb[idx] += glow * 1.0;
-                }
+        let shapes = [
+            (&circle_pts, (1.0, 0.2, 0.6)),
+            (&tri_pts, (0.1, 0.9, 1.0)),
+            (&text_pts, (0.4, 0.5, 1.0)),
+        ];
 
-                // Text glow (blue-white: R=0.4, G=0.5, B=1.0)
-                min_d2 = f64::MAX;
-                for &(nx, ny) in &text_pts {
-                    let dx = px - nx;
-                    let dy = py - ny;
-                    let d2 = dx * dx + dy * dy;
-                    if d2 < min_d2 {
-                        min_d2 = d2;
-                    }
-                }
-                if min_d2 < glow_radius_sq {
-                    let glow = 1.0 / (1.0 + min_d2 * 0.15);
-                    gr[idx] += glow * 0.4;
-                    gg[idx] += glow * 0.5;
-                    gb[idx] += glow * 1.0;
+        for (pts, (cr, cg, cb)) in shapes.iter() {
+            let dist_sq = nearest_dist_sq(w, h, pts, glow_radius);
+            for (idx, &d2) in dist_sq.iter().enumerate() {
+                if d2 < f64::MAX {
+                    let glow = 1.0 / (1.0 + d2 * 0.15);
+                    gr[idx] += glow * cr;
+                    gg[idx] += glow * cg;
+                    gb[idx] += glow * cb;
                 }
             }
         }
@@ -232,6 +160,10 @@ impl Effect for Neon {
     }
 
     fn init(&mut self, width: u32, height: u32) {
+        // The layers only depend on the size, so a scene restart reuses them
+        if width == self.width && height == self.height && !self.brick_bg.is_empty() {
+            return;
+        }
         self.width = width;
         self.height = height;
         self.brick_bg = Self::build_brick_bg(width, height);
@@ -343,3 +275,32 @@ impl Effect for Neon {
         }
     }
 }
+
+/// Squared distance from every pixel center to the nearest of `pts`, or
+/// `f64::MAX` beyond `radius`.
+///
+/// Glow vanishes past the radius, so each point only updates the pixels in
+/// its own window. The cost depends on the number of points rather than
+/// on the screen size, and the result matches a full nearest-point search.
+fn nearest_dist_sq(w: u32, h: u32, pts: &[(f64, f64)], radius: f64) -> Vec<f64> {
+    let mut dist_sq = vec![f64::MAX; (w * h) as usize];
+    let radius_sq = radius * radius;
+    for &(px, py) in pts {
+        let x0 = (px - radius).floor().max(0.0) as u32;
+        let y0 = (py - radius).floor().max(0.0) as u32;
+        let x1 = ((px + radius).ceil().max(0.0) as u32).min(w);
+        let y1 = ((py + radius).ceil().max(0.0) as u32).min(h);
+        for y in y0..y1 {
+            let dy = y as f64 + 0.5 - py;
+            for x in x0..x1 {
+                let dx = x as f64 + 0.5 - px;
+                let d2 = dx * dx + dy * dy;
+                let idx = (y * w + x) as usize;
+                if d2 < radius_sq && d2 < dist_sq[idx] {
+                    dist_sq[idx] = d2;
+                }
+            }
+        }
+    }
+    dist_sq
+}
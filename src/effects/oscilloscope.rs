@@ -1,5 +1,138 @@
 use crate::effect::{Effect, ParamDesc};
 use std::f64::consts::PI;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Sample rate assumed for raw f32 samples on stdin.
+const RAW_RATE: u32 = 44_100;
+/// Graticule divisions across and down the screen, as on a bench scope.
+const DIVS_X: u32 = 10;
+const DIVS_Y: u32 = 8;
+
+/// Decoded audio for the trace, one (left, right) pair per sample frame.
+/// Mono input carries the same value in both.
+pub struct Samples {
+    rate: u32,
+    stereo: bool,
+    frames: Vec<(f32, f32)>,
+}
+
+/// Load a PCM or float WAV file, or raw little-endian mono f32 at 44.1 kHz
+/// from stdin when `path` is `-`.
+pub fn load_samples(path: &Path) -> io::Result<Samples> {
+    if path == Path::new("-") {
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data)?;
+        let frames: Vec<(f32, f32)> = data
+            .chunks_exact(4)
+            .map(|b| finite(f32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            .map(|v| (v, v))
+            .collect();
+        if frames.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no samples on stdin",
+            ));
+        }
+        return Ok(Samples {
+            rate: RAW_RATE,
+            stereo: false,
+            frames,
+        });
+    }
+    parse_wav(&fs::read(path)?)
+}
+
+fn finite(v: f32) -> f32 {
+    if v.is_finite() {
+        v
+    } else {
+        0.0
+    }
+}
+
+fn parse_wav(data: &[u8]) -> io::Result<Samples> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(invalid("not a WAV file"));
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+    let u32_at = |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+
+    // (format tag, channels, rate, bits per sample)
+    let mut format = None;
+    let mut body = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let len = u32_at(pos + 4) as usize;
+        let start = pos + 8;
+        let end = start.saturating_add(len).min(data.len());
+        if id == b"fmt " && end - start >= 16 {
+            let mut tag = u16_at(start);
+            // WAVE_FORMAT_EXTENSIBLE keeps the real tag at the head of its subformat GUID
+            if tag == 0xFFFE && end - start >= 26 {
+                tag = u16_at(start + 24);
+            }
+            format = Some((
+                tag,
+                u16_at(start + 2),
+                u32_at(start + 4),
+                u16_at(start + 14),
+            ));
+        } else if id == b"data" {
+            body = Some(&data[start..end]);
+        }
+        // Chunks are padded to an even length
+        pos = start.saturating_add(len + (len & 1));
+    }
+
+    let (tag, channels, rate, bits) = format.ok_or_else(|| invalid("missing fmt chunk"))?;
+    let body = body.ok_or_else(|| invalid("missing data chunk"))?;
+    if channels == 0 || rate == 0 {
+        return Err(invalid("bad channel count or sample rate"));
+    }
+    let decode: fn(&[u8]) -> f32 = match (tag, bits) {
+        (1, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+        (1, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32_768.0,
+        (1, 24) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.0,
+        (1, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+        (3, 32) => |b| finite(f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        (3, 64) => {
+            |b| finite(f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32)
+        }
+        _ => {
+            return Err(invalid(
+                "unsupported sample format (expected 8/16/24/32-bit PCM or float)",
+            ))
+        }
+    };
+
+    let width = bits as usize / 8;
+    let frame_len = width * channels as usize;
+    let frames: Vec<(f32, f32)> = body
+        .chunks_exact(frame_len)
+        .map(|frame| {
+            let left = decode(&frame[..width]);
+            // Extra channels beyond the first two are ignored
+            let right = if channels > 1 {
+                decode(&frame[width..2 * width])
+            } else {
+                left
+            };
+            (left, right)
+        })
+        .collect();
+    if frames.is_empty() {
+        return Err(invalid("no samples in data chunk"));
+    }
+    Ok(Samples {
+        rate,
+        stereo: channels > 1,
+        frames,
+    })
+}
 
 pub struct Oscilloscope {
     width: u32,
@@ -8,6 +141,12 @@ pub struct Oscilloscope {
     decay: f64,
     phosphor: Vec<f64>,
     phase: f64,
+    /// Real signal to trace instead of the synthetic Lissajous figure.
+    samples: Option<Samples>,
+    /// Milliseconds of signal per horizontal division.
+    time_div: f64,
+    /// Playback position into `samples`, in seconds.
+    playhead: f64,
 }
 
 impl Oscilloscope {
@@ -19,41 +158,44 @@ impl Oscilloscope {
             decay: 0.05,
             phosphor: Vec::new(),
             phase: 0.0,
+            samples: None,
+            time_div: 1.0,
+            playhead: 0.0,
         }
     }
-}
-
-impl Effect for Oscilloscope {
-    fn name(&self) -> &str {
-        "Oscilloscope"
-    }
 
-    fn init(&mut self, width: u32, height: u32) {
-        self.width = width;
-        self.height = height;
-        self.phosphor = vec![0.0; (width * height) as usize];
-        self.phase = 0.0;
+    pub fn with_samples(mut self, samples: Samples) -> Self {
+        self.samples = Some(samples);
+        self
     }
 
-    fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]) {
-        let w = self.width;
-        let h = self.height;
-        if w == 0 || h == 0 {
-            return;
+    /// Light up a small gaussian spot around (px, py).
+    fn plot(&mut self, px: f64, py: f64) {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let ix = px as i32;
+        let iy = py as i32;
+        for dy in -2..=2_i32 {
+            for dx in -2..=2_i32 {
+                let sx = ix + dx;
+                let sy = iy + dy;
+                if sx >= 0 && sx < w && sy >= 0 && sy < h {
+                    let dist_sq = (dx * dx + dy * dy) as f64;
+                    let intensity = (-dist_sq * 0.5).exp(); // gaussian falloff
+                    let idx = (sy * w + sx) as usize;
+                    self.phosphor[idx] = (self.phosphor[idx] + intensity * 0.3).min(1.0);
+                }
+            }
         }
+    }
 
-        let wf = w as f64;
-        let hf = h as f64;
+    /// Drifting compound Lissajous figure, the default when no signal is loaded.
+    fn trace_lissajous(&mut self, t: f64, dt: f64) {
+        let wf = self.width as f64;
+        let hf = self.height as f64;
         let cx = wf / 2.0;
         let cy = hf / 2.0;
         let scale = cx.min(cy) * 0.8;
 
-        // Decay the phosphor buffer
-        let decay_factor = (1.0 - self.decay).max(0.0);
-        for p in self.phosphor.iter_mut() {
-            *p *= decay_factor;
-        }
-
         // Slowly drifting frequency ratios for organic Lissajous patterns
         let base_t = t * self.speed;
         let freq_x = 3.0 + (base_t * 0.037).sin() * 2.0;
@@ -78,29 +220,108 @@ impl Effect for Oscilloscope {
             let px = cx + x * scale;
             let py = cy + y * scale;
 
-            let ix = px as i32;
-            let iy = py as i32;
-
-            // Plot with a small glow (radius ~2px)
-            for dy in -2..=2_i32 {
-                for dx in -2..=2_i32 {
-                    let sx = ix + dx;
-                    let sy = iy + dy;
-                    if sx >= 0 && sx < w as i32 && sy >= 0 && sy < h as i32 {
-                        let dist_sq = (dx * dx + dy * dy) as f64;
-                        let intensity = (-dist_sq * 0.5).exp(); // gaussian falloff
-                        let idx = (sy as u32 * w + sx as u32) as usize;
-                        self.phosphor[idx] = (self.phosphor[idx] + intensity * 0.3).min(1.0);
-                    }
+            self.plot(px, py);
+        }
+    }
+
+    /// Sweep one screen of the loaded signal, triggered on a rising zero
+    /// crossing of the left channel so periodic waves stand still.
+    fn trace_samples(&mut self, dt: f64) {
+        let samples = match self.samples.take() {
+            Some(samples) => samples,
+            None => return,
+        };
+        let len = samples.frames.len();
+        let at = |i: usize| samples.frames[i % len];
+
+        let window =
+            ((self.time_div / 1000.0 * DIVS_X as f64 * samples.rate as f64) as usize).max(2);
+        let mut start = (self.playhead * samples.rate as f64) as usize % len;
+        if let Some(offset) =
+            (1..window.min(len)).find(|&i| at(start + i - 1).0 < 0.0 && at(start + i).0 >= 0.0)
+        {
+            start += offset;
+        }
+        self.playhead = (self.playhead + dt * self.speed) % (len as f64 / samples.rate as f64);
+
+        // Stereo gets one lane per channel; amplitude 1.0 fills a lane
+        let hf = self.height as f64;
+        let lanes: &[(f64, bool)] = if samples.stereo {
+            &[(0.25, false), (0.75, true)]
+        } else {
+            &[(0.5, false)]
+        };
+        let half = hf * 0.45 / lanes.len() as f64;
+        let w = self.width;
+        for &(center, right) in lanes {
+            let mut prev_y = None;
+            for x in 0..w {
+                let i = start + x as usize * window / w as usize;
+                let (l, r) = at(i);
+                let v = if right { r } else { l } as f64;
+                let y = (hf * center - v * half).clamp(0.0, hf - 1.0);
+                // Join to the previous column so steep edges stay continuous
+                let (lo, hi) = match prev_y {
+                    Some(p) if p < y => (p, y),
+                    Some(p) => (y, p),
+                    None => (y, y),
+                };
+                let mut yy = lo;
+                while yy <= hi {
+                    self.plot(x as f64 + 0.5, yy);
+                    yy += 1.0;
                 }
+                prev_y = Some(y);
             }
         }
+        self.samples = Some(samples);
+    }
+}
+
+/// Whether column or row `i` of `n` sits on one of `divs` graticule lines.
+fn on_division(i: u32, n: u32, divs: u32) -> bool {
+    i * divs / n != (i + 1) * divs / n
+}
+
+impl Effect for Oscilloscope {
+    fn name(&self) -> &str {
+        "Oscilloscope"
+    }
+
+    fn init(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.phosphor = vec![0.0; (width * height) as usize];
+        self.phase = 0.0;
+        self.playhead = 0.0;
+    }
+
+    fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]) {
+        let w = self.width;
+        let h = self.height;
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        // Decay the phosphor buffer
+        let decay_factor = (1.0 - self.decay).max(0.0);
+        for p in self.phosphor.iter_mut() {
+            *p *= decay_factor;
+        }
+
+        if self.samples.is_some() {
+            self.trace_samples(dt);
+        } else {
+            self.trace_lissajous(t, dt);
+        }
+        let graticule = self.samples.is_some();
 
         // Render phosphor buffer to pixels with green CRT coloring and scanlines
         for y in 0..h {
             // Scanline effect: every other row is slightly dimmer
             let scanline = if y % 2 == 0 { 1.0 } else { 0.82 };
             let row_offset = (y * w) as usize;
+            let grid_row = on_division(y, h, DIVS_Y);
 
             for x in 0..w {
                 let idx = row_offset + x as usize;
@@ -109,7 +330,11 @@ impl Effect for Oscilloscope {
                 }
 
                 let p = self.phosphor[idx];
-                let v = p * scanline;
+                let mut v = p * scanline;
+                // Dotted graticule so time/div has something to measure against
+                if graticule && (x + y) % 2 == 0 && (grid_row || on_division(x, w, DIVS_X)) {
+                    v = v.max(0.3);
+                }
 
                 // Green phosphor: bright is (0, 255, 50), dim is (0, 40, 10), off is black
                 // Use a curve that makes the glow feel warm and CRT-like
@@ -122,7 +347,6 @@ impl Effect for Oscilloscope {
             }
         }
     }
-
     fn params(&self) -> Vec<ParamDesc> {
         vec![
             ParamDesc {
@@ -137,6 +361,12 @@ impl Effect for Oscilloscope {
                 max: 0.15,
                 value: self.decay,
             },
+            ParamDesc {
+                name: "time_div".to_string(),
+                min: 0.2,
+                max: 5.0,
+                value: self.time_div,
+            },
         ]
     }
 
@@ -144,6 +374,7 @@ impl Effect for Oscilloscope {
         match name {
             "speed" => self.speed = value,
             "decay" => self.decay = value,
+            "time_div" => self.time_div = value,
             _ => {}
         }
     }
@@ -1,4 +1,5 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
 
 pub struct Parallax {
     width: u32,
@@ -18,15 +19,6 @@ impl Parallax {
     }
 }
 
-/// Deterministic pseudo-random hash for star placement
-fn hash_u32(mut x: u32) -> u32 {
-    x = x.wrapping_mul(0x9E3779B9);
-    x ^= x >> 16;
-    x = x.wrapping_mul(0x85EBCA6B);
-    x ^= x >> 13;
-    x
-}
-
 /// Generate mountain height at a given x position for a layer
 /// Uses several sine waves combined for a natural ridge shape
 fn mountain_height(x: f64, layer: usize) -> f64 {
@@ -44,6 +36,10 @@ impl Effect for Parallax {
         "Parallax Landscape"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -100,7 +96,7 @@ impl Effect for Parallax {
                 // Stars in upper portion of sky
                 if sky_t2 < 0.3 {
                     for x in 0..w {
-                        let star_hash = hash_u32(x.wrapping_mul(7919).wrapping_add(y.wrapping_mul(104729)));
+                        let star_hash = rng::hash_cell(x, y);
                         if star_hash % 800 == 0 {
                             let brightness = (120 + (star_hash % 136) as u8).min(255);
                             // Twinkle
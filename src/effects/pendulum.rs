@@ -1,4 +1,5 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use std::f64::consts::PI;
 
 pub struct PendulumWave {
@@ -63,34 +64,6 @@ impl PendulumWave {
         }
     }
 
-    fn draw_filled_circle(
-        pixels: &mut [(u8, u8, u8)],
-        w: u32,
-        h: u32,
-        cx: f64,
-        cy: f64,
-        radius: f64,
-        color: (u8, u8, u8),
-    ) {
-        let r = radius.ceil() as i32;
-        let icx = cx as i32;
-        let icy = cy as i32;
-        for dy in -r..=r {
-            for dx in -r..=r {
-                let dist = ((dx * dx + dy * dy) as f64).sqrt();
-                if dist <= radius {
-                    // Anti-alias the edge
-                    let alpha = if dist > radius - 1.0 {
-                        radius - dist
-                    } else {
-                        1.0
-                    };
-                    Self::plot_pixel(pixels, w, h, icx + dx, icy + dy, color, alpha.max(0.0));
-                }
-            }
-        }
-    }
-
     fn draw_line(
         pixels: &mut [(u8, u8, u8)],
         w: u32,
@@ -122,6 +95,10 @@ impl Effect for PendulumWave {
         "Pendulum Wave"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -220,15 +197,14 @@ impl Effect for PendulumWave {
 
             // Draw bob
             let bob_radius = 4.0_f64.min(spacing * 0.3);
-            Self::draw_filled_circle(pixels, w, h, bob_x, bob_y, bob_radius, color);
+            splat::filled_circle(pixels, w, h, (bob_x, bob_y), bob_radius, color);
 
             // Highlight on the bob
-            Self::draw_filled_circle(
+            splat::filled_circle(
                 pixels,
                 w,
                 h,
-                bob_x - bob_radius * 0.3,
-                bob_y - bob_radius * 0.3,
+                (bob_x - bob_radius * 0.3, bob_y - bob_radius * 0.3),
                 bob_radius * 0.4,
                 (
                     (color.0 as u16 / 2 + 128).min(255) as u8,
@@ -1,4 +1,6 @@
+use crate::camera::CameraPath;
 use crate::effect::{Effect, ParamDesc};
+use rand::rngs::StdRng;
 use std::f64::consts::PI;
 
 pub struct PixelSort {
@@ -6,6 +8,16 @@ pub struct PixelSort {
     height: u32,
     threshold: f64,
     chaos: f64,
+    /// 0 sorts along rows, 1 down columns
+    direction: f64,
+    /// 0 orders runs by brightness, 1 by hue
+    order: f64,
+    /// One row or column, copied out for sorting
+    line: Vec<(u8, u8, u8)>,
+    /// Effect whose frames get sorted instead of the built-in plasma
+    source: Option<Box<dyn Effect>>,
+    /// The source's own frame, kept apart so it never sees the sorting
+    scratch: Vec<(u8, u8, u8)>,
 }
 
 impl PixelSort {
@@ -15,6 +27,19 @@ impl PixelSort {
             height: 0,
             threshold: 0.4,
             chaos: 1.0,
+            direction: 0.0,
+            order: 0.0,
+            line: Vec::new(),
+            source: None,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Sort the live output of `source` rather than the built-in plasma.
+    pub fn wrapping(source: Box<dyn Effect>) -> Self {
+        Self {
+            source: Some(source),
+            ..Self::new()
         }
     }
 
@@ -35,6 +60,63 @@ impl PixelSort {
     fn brightness(c: &(u8, u8, u8)) -> f64 {
         (c.0 as f64 * 0.299 + c.1 as f64 * 0.587 + c.2 as f64 * 0.114) / 255.0
     }
+
+    /// Hue in 0..1, with grays at 0.
+    fn hue(c: &(u8, u8, u8)) -> f64 {
+        let (r, g, b) = (c.0 as f64, c.1 as f64, c.2 as f64);
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+        if delta == 0.0 {
+            return 0.0;
+        }
+        let sector = if max == r {
+            ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / delta + 2.0
+        } else {
+            (r - g) / delta + 4.0
+        };
+        sector / 6.0
+    }
+
+    /// Sort each run of pixels at or above `thresh` brightness in `line`.
+    fn sort_runs(
+        line: &mut [(u8, u8, u8)],
+        thresh: f64,
+        key: fn(&(u8, u8, u8)) -> f64,
+        reverse: bool,
+    ) {
+        let n = line.len();
+        let mut x = 0;
+        while x < n {
+            // Skip pixels below threshold
+            if Self::brightness(&line[x]) < thresh {
+                x += 1;
+                continue;
+            }
+
+            // Found start of a run
+            let run_start = x;
+            while x < n && Self::brightness(&line[x]) >= thresh {
+                x += 1;
+            }
+
+            let slice = &mut line[run_start..x];
+            if reverse {
+                slice.sort_by(|a, b| {
+                    key(b)
+                        .partial_cmp(&key(a))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+            } else {
+                slice.sort_by(|a, b| {
+                    key(a)
+                        .partial_cmp(&key(b))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+            }
+        }
+    }
 }
 
 impl Effect for PixelSort {
@@ -45,9 +127,19 @@ impl Effect for PixelSort {
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
+        if let Some(source) = &mut self.source {
+            self.scratch = vec![(0, 0, 0); (width * height) as usize];
+            source.init(width, height);
+        }
     }
 
-    fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        if let Some(source) = &mut self.source {
+            source.randomize_init(rng);
+        }
+    }
+
+    fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width as usize;
         let h = self.height as usize;
         if w == 0 || h == 0 {
@@ -57,52 +149,40 @@ impl Effect for PixelSort {
         // Oscillating threshold
         let thresh = self.threshold + (t * 0.8).sin() * 0.15;
 
-        // Generate base plasma image directly into pixels
-        for y in 0..h {
-            for x in 0..w {
-                let idx = y * w + x;
-                pixels[idx] = Self::plasma_color(x as f64, y as f64, t, self.chaos);
+        if let Some(source) = &mut self.source {
+            source.update(t, dt, &mut self.scratch);
+            pixels[..w * h].copy_from_slice(&self.scratch);
+        } else {
+            // Generate base plasma image directly into pixels
+            for y in 0..h {
+                for x in 0..w {
+                    let idx = y * w + x;
+                    pixels[idx] = Self::plasma_color(x as f64, y as f64, t, self.chaos);
+                }
             }
         }
 
-        // Pixel sort each row
-        for y in 0..h {
-            let row_start = y * w;
-            let reverse = y % 2 == 1;
-
-            // Find runs of pixels above threshold, then sort them
-            let mut x = 0;
-            while x < w {
-                // Skip pixels below threshold
-                let b = Self::brightness(&pixels[row_start + x]);
-                if b < thresh {
-                    x += 1;
-                    continue;
-                }
+        let key: fn(&(u8, u8, u8)) -> f64 = if self.order.round() >= 1.0 {
+            Self::hue
+        } else {
+            Self::brightness
+        };
 
-                // Found start of a run
-                let run_start = x;
-                while x < w && Self::brightness(&pixels[row_start + x]) >= thresh {
-                    x += 1;
-                }
-                let run_end = x;
-
-                // Sort the run by brightness
-                let slice = &mut pixels[row_start + run_start..row_start + run_end];
-                if reverse {
-                    slice.sort_by(|a, b| {
-                        Self::brightness(b)
-                            .partial_cmp(&Self::brightness(a))
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    });
-                } else {
-                    slice.sort_by(|a, b| {
-                        Self::brightness(a)
-                            .partial_cmp(&Self::brightness(b))
-                            .unwrap_or(std::cmp::Ordering::Equal)
-                    });
+        if self.direction.round() >= 1.0 {
+            // Columns all sort the same way, so bright runs drip downward
+            for x in 0..w {
+                self.line.clear();
+                self.line.extend((0..h).map(|y| pixels[y * w + x]));
+                Self::sort_runs(&mut self.line, thresh, key, false);
+                for (y, &px) in self.line.iter().enumerate() {
+                    pixels[y * w + x] = px;
                 }
             }
+        } else {
+            // Rows alternate direction for a woven look
+            for (y, row) in pixels[..w * h].chunks_mut(w).enumerate() {
+                Self::sort_runs(row, thresh, key, y % 2 == 1);
+            }
         }
     }
 
@@ -110,8 +190,8 @@ impl Effect for PixelSort {
         vec![
             ParamDesc {
                 name: "threshold".to_string(),
-                min: 0.2,
-                max: 0.8,
+                min: 0.05,
+                max: 0.95,
                 value: self.threshold,
             },
             ParamDesc {
@@ -120,6 +200,18 @@ impl Effect for PixelSort {
                 max: 2.0,
                 value: self.chaos,
             },
+            ParamDesc {
+                name: "direction".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.direction,
+            },
+            ParamDesc {
+                name: "order".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.order,
+            },
         ]
     }
 
@@ -127,7 +219,39 @@ impl Effect for PixelSort {
         match name {
             "threshold" => self.threshold = value,
             "chaos" => self.chaos = value,
+            "direction" => self.direction = value,
+            "order" => self.order = value,
             _ => {}
         }
     }
+
+    fn set_text(&mut self, text: &str) {
+        if let Some(source) = &mut self.source {
+            source.set_text(text);
+        }
+    }
+
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        if let Some(source) = &mut self.source {
+            source.set_camera(yaw, pitch);
+        }
+    }
+
+    fn set_camera_path(&mut self, path: CameraPath) {
+        if let Some(source) = &mut self.source {
+            source.set_camera_path(path);
+        }
+    }
+
+    fn trigger(&mut self) {
+        if let Some(source) = &mut self.source {
+            source.trigger();
+        }
+    }
+
+    fn poke(&mut self, x: f64, y: f64) {
+        if let Some(source) = &mut self.source {
+            source.poke(x, y);
+        }
+    }
 }
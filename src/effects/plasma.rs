@@ -1,10 +1,20 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::framebuffer::ColorDepth;
+use crate::noise::ValueNoise;
+
+/// Lattice cells across the screen at scale 1 for the noise backend
+const NOISE_CELLS: f64 = 3.0;
 
 pub struct Plasma {
     width: u32,
     height: u32,
     speed: f64,
     scale: f64,
+    /// 0 sums sine waves, 1 flows fractal value noise
+    backend: f64,
+    /// Noise layers; more adds finer wisps
+    octaves: f64,
+    noise: ValueNoise,
 }
 
 impl Plasma {
@@ -14,17 +24,33 @@ impl Plasma {
             height: 0,
             speed: 1.0,
             scale: 1.0,
+            backend: 0.0,
+            octaves: 4.0,
+            noise: ValueNoise::new(0x5EED),
         }
     }
 
     pub fn with_params(speed: f64, scale: f64) -> Self {
         Self {
-            width: 0,
-            height: 0,
             speed,
             scale,
+            ..Self::new()
         }
     }
+
+    fn uses_noise(&self) -> bool {
+        self.backend.round() >= 1.0
+    }
+
+    /// Summed sines; regular, and loops exactly.
+    fn sine_value(fx: f64, fy: f64, t: f64) -> f64 {
+        let v1 = (fx * 10.0 + t).sin();
+        let v2 = ((fy * 10.0 + t) * 0.7).sin();
+        let v3 = ((fx * 6.0 + fy * 6.0 + t * 0.8).sin() + (fx * fx + fy * fy).sqrt().sin()) * 0.5;
+        let v4 = ((fx * fx + fy * fy).sqrt() * 4.0 - t * 1.2).sin();
+
+        (v1 + v2 + v3 + v4) * 0.25
+    }
 }
 
 impl Effect for Plasma {
@@ -32,6 +58,19 @@ impl Effect for Plasma {
         "Plasma"
     }
 
+    fn loop_period(&self) -> Option<f64> {
+        // Wave rates 1.0, 0.7, 0.8 and 1.2 share a 0.1 fundamental; noise
+        // never repeats
+        if self.uses_noise() {
+            return None;
+        }
+        Some(20.0 * std::f64::consts::PI / self.speed)
+    }
+
+    fn min_color_depth(&self) -> ColorDepth {
+        ColorDepth::TrueColor
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -46,20 +85,23 @@ impl Effect for Plasma {
 
         let t = t * self.speed;
         let scale = self.scale;
+        let noise = self.uses_noise();
+        let octaves = self.octaves.round().max(1.0) as u32;
 
         for y in 0..self.height {
             for x in 0..self.width {
                 let fx = x as f64 / w * scale;
                 let fy = y as f64 / h * scale;
 
-                let v1 = (fx * 10.0 + t).sin();
-                let v2 = ((fy * 10.0 + t) * 0.7).sin();
-                let v3 = ((fx * 6.0 + fy * 6.0 + t * 0.8).sin()
-                    + (fx * fx + fy * fy).sqrt().sin())
-                    * 0.5;
-                let v4 = ((fx * fx + fy * fy).sqrt() * 4.0 - t * 1.2).sin();
-
-                let v = (v1 + v2 + v3 + v4) * 0.25;
+                let v = if noise {
+                    // Fractal noise rarely strays far from zero, so stretch
+                    // it to sweep the same palette as the sines, which also
+                    // drifts slowly around the color wheel
+                    let n = self.noise.fbm(fx * NOISE_CELLS, fy * NOISE_CELLS, t * 0.3, octaves);
+                    n * 1.8 + t * 0.05
+                } else {
+                    Self::sine_value(fx, fy, t)
+                };
 
                 let r = ((v * std::f64::consts::PI).cos() * 0.5 + 0.5) * 255.0;
                 let g = ((v * std::f64::consts::PI + 2.094).cos() * 0.5 + 0.5) * 255.0;
@@ -85,6 +127,18 @@ impl Effect for Plasma {
                 max: 4.0,
                 value: self.scale,
             },
+            ParamDesc {
+                name: "backend".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.backend,
+            },
+            ParamDesc {
+                name: "octaves".to_string(),
+                min: 1.0,
+                max: 8.0,
+                value: self.octaves,
+            },
         ]
     }
 
@@ -92,6 +146,8 @@ impl Effect for Plasma {
         match name {
             "speed" => self.speed = value,
             "scale" => self.scale = value,
+            "backend" => self.backend = value,
+            "octaves" => self.octaves = value,
             _ => {}
         }
     }
@@ -1,4 +1,8 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::noise;
+use crate::rng;
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::f64::consts::PI;
 
 pub struct Rain {
@@ -6,6 +10,10 @@ pub struct Rain {
     height: u32,
     intensity: f64,
     wind: f64,
+    /// Strength of the gusts on top of the steady wind
+    turbulence: f64,
+    /// Salted hash for the layout; `randomize_init` picks a new salt
+    hash: rng::Hash,
 }
 
 impl Rain {
@@ -15,17 +23,10 @@ impl Rain {
             height: 0,
             intensity: 1.0,
             wind: 0.2,
+            turbulence: 0.5,
+            hash: rng::Hash::default(),
         }
     }
-
-    /// Deterministic pseudo-random from seed, returns 0.0..1.0.
-    fn rng(seed: u32) -> f64 {
-        let mut h = seed;
-        h = h.wrapping_mul(747796405).wrapping_add(2891336453);
-        h = ((h >> ((h >> 28).wrapping_add(4))) ^ h).wrapping_mul(277803737);
-        h = h ^ (h >> 22);
-        (h & 0x00FFFFFF) as f64 / 0x01000000 as f64
-    }
 }
 
 impl Effect for Rain {
@@ -38,6 +39,10 @@ impl Effect for Rain {
         self.height = height;
     }
 
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        self.hash = rng::Hash::new(rng.gen());
+    }
+
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width as usize;
         let h = self.height as usize;
@@ -96,12 +101,12 @@ impl Effect for Rain {
         let num_buildings = (w / 6).max(5);
         for i in 0..num_buildings {
             let seed = i as u32 * 7 + 100;
-            let bx = (Self::rng(seed) * wf) as usize;
-            let bw = (Self::rng(seed + 1) * 8.0 + 3.0) as usize;
-            let bh = (Self::rng(seed + 2) * hf * 0.25 + hf * 0.05) as usize;
+            let bx = (self.hash.u(seed) * wf) as usize;
+            let bw = (self.hash.u(seed + 1) * 8.0 + 3.0) as usize;
+            let bh = (self.hash.u(seed + 2) * hf * 0.25 + hf * 0.05) as usize;
             let building_top = ground_y.saturating_sub(bh);
 
-            let shade = 12.0 + Self::rng(seed + 3) * 10.0 + lightning_brightness * 25.0;
+            let shade = 12.0 + self.hash.u(seed + 3) * 10.0 + lightning_brightness * 25.0;
             let br = shade.clamp(0.0, 255.0) as u8;
             let bg = (shade * 1.05).clamp(0.0, 255.0) as u8;
             let bb = (shade * 1.2).clamp(0.0, 255.0) as u8;
@@ -123,8 +128,8 @@ impl Effect for Rain {
                     let wx = bx + wx_offset;
                     if wx < w {
                         let win_seed = (i as u32) * 1000 + (wy as u32) * 100 + wx_offset as u32;
-                        if Self::rng(win_seed) > 0.5 {
-                            let warm = 140.0 + Self::rng(win_seed + 1) * 60.0;
+                        if self.hash.u(win_seed) > 0.5 {
+                            let warm = 140.0 + self.hash.u(win_seed + 1) * 60.0;
                             pixels[wy * w + wx] = (
                                 warm.clamp(0.0, 255.0) as u8,
                                 (warm * 0.85).clamp(0.0, 255.0) as u8,
@@ -167,8 +172,8 @@ impl Effect for Rain {
             for i in 0..count {
                 let seed_base = (layer_idx as u32) * 50000 + i;
 
-                let start_x = Self::rng(seed_base * 3 + 1) * (wf + 40.0) - 20.0;
-                let phase_offset = Self::rng(seed_base * 3 + 2);
+                let start_x = self.hash.u(seed_base * 3 + 1) * (wf + 40.0) - 20.0;
+                let phase_offset = self.hash.u(seed_base * 3 + 2);
 
                 // Continuous falling: y wraps around
                 let total_travel = hf + streak_len + 20.0;
@@ -178,8 +183,12 @@ impl Effect for Rain {
                 let wrap_w = wf + 40.0;
                 let head_x = ((raw_x + 20.0) % wrap_w + wrap_w) % wrap_w - 20.0;
 
+                // Local gust: pushes the drop sideways and tilts its streak
+                let gust = noise::sine_field(head_x, head_y, t) * self.turbulence;
+                let head_x = head_x + gust * 10.0;
+
                 // Draw streak as a line from (head_x, head_y) upward/back
-                let dx_per_step = -wind_angle;
+                let dx_per_step = -(wind_angle + gust * 0.3);
                 let dy_per_step = -1.0;
                 let steps = streak_len as i32;
                 let br = brightness as u8;
@@ -250,6 +259,12 @@ impl Effect for Rain {
                 max: 1.0,
                 value: self.wind,
             },
+            ParamDesc {
+                name: "turbulence".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.turbulence,
+            },
         ]
     }
 
@@ -257,6 +272,7 @@ impl Effect for Rain {
         match name {
             "intensity" => self.intensity = value,
             "wind" => self.wind = value,
+            "turbulence" => self.turbulence = value,
             _ => {}
         }
     }
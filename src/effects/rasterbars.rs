@@ -1,4 +1,6 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::framebuffer::{self, Rgba};
 use std::f64::consts::PI;
 
 pub struct RasterBars {
@@ -6,6 +8,8 @@ pub struct RasterBars {
     height: u32,
     bar_count: u32,
     amplitude: f64,
+    /// The bars on a transparent background, before they are laid on black
+    layer: Vec<Rgba>,
 }
 
 impl RasterBars {
@@ -15,31 +19,16 @@ impl RasterBars {
             height: 0,
             bar_count: 7,
             amplitude: 1.0,
+            layer: Vec::new(),
         }
     }
-}
-
-impl Effect for RasterBars {
-    fn name(&self) -> &str {
-        "RasterBars"
-    }
-
-    fn init(&mut self, width: u32, height: u32) {
-        self.width = width;
-        self.height = height;
-    }
 
-    fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
+    /// Draw the bars over a transparent background; each bar is opaque at
+    /// its bright center and fades out with its glow.
+    fn draw(&self, t: f64, pixels: &mut [Rgba]) {
         let w = self.width;
         let h = self.height;
-        if w == 0 || h == 0 {
-            return;
-        }
-
-        // Clear to black
-        for p in pixels.iter_mut() {
-            *p = (0, 0, 0);
-        }
+        pixels.iter_mut().for_each(|p| *p = (0, 0, 0, 0));
 
         let hf = h as f64;
         let bar_count = self.bar_count as usize;
@@ -76,10 +65,45 @@ impl Effect for RasterBars {
                     p.0 = p.0.saturating_add(cr);
                     p.1 = p.1.saturating_add(cg);
                     p.2 = p.2.saturating_add(cb);
+                    p.3 = p.3.max(bright);
                 }
             }
         }
     }
+}
+
+impl Effect for RasterBars {
+    fn name(&self) -> &str {
+        "RasterBars"
+    }
+
+    fn preferred_duration(&self) -> f64 {
+        10.0
+    }
+
+    fn init(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let mut layer = std::mem::take(&mut self.layer);
+        layer.resize(pixels.len(), (0, 0, 0, 0));
+        self.draw(t, &mut layer);
+        pixels.iter_mut().for_each(|p| *p = (0, 0, 0));
+        framebuffer::flatten(&layer, pixels);
+        self.layer = layer;
+    }
+
+    fn update_rgba(&mut self, t: f64, _dt: f64, pixels: &mut [Rgba]) -> bool {
+        if self.width > 0 && self.height > 0 {
+            self.draw(t, pixels);
+        }
+        true
+    }
 
     fn params(&self) -> Vec<ParamDesc> {
         vec![
@@ -106,20 +130,3 @@ impl Effect for RasterBars {
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
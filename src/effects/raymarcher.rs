@@ -1,3 +1,5 @@
+use crate::camera::CameraPath;
+use crate::color::hsv_to_rgb_f64;
 use crate::effect::{Effect, ParamDesc};
 
 pub struct Raymarcher {
@@ -5,6 +7,16 @@ pub struct Raymarcher {
     height: u32,
     speed: f64,
     complexity: f64,
+    /// Which built-in scene to march: 0 spheres and pillars, 1 mandelbulb,
+    /// 2 menger sponge, 3 torus field
+    scene: f64,
+    /// Extra lighting passes: 0 none, 1 soft shadows, 2 shadows and
+    /// ambient occlusion
+    quality: f64,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
+    /// Scripted flythrough replacing the built-in orbit
+    path: Option<CameraPath>,
 }
 
 impl Raymarcher {
@@ -14,6 +26,10 @@ impl Raymarcher {
             height: 0,
             speed: 1.0,
             complexity: 1.0,
+            scene: 0.0,
+            quality: 0.0,
+            look: (0.0, 0.0),
+            path: None,
         }
     }
 }
@@ -33,6 +49,69 @@ fn sd_plane(p: [f64; 3], h: f64) -> f64 {
     p[1] - h
 }
 
+fn sd_torus(p: [f64; 3], major: f64, minor: f64) -> f64 {
+    let ring = (p[0] * p[0] + p[2] * p[2]).sqrt() - major;
+    (ring * ring + p[1] * p[1]).sqrt() - minor
+}
+
+/// Distance estimate to a power-8 mandelbulb of radius about 1.1.
+fn sd_mandelbulb(p: [f64; 3], iterations: u32) -> f64 {
+    // Cheap bound so rays far from the bulb skip the iteration
+    let bound = length(p);
+    if bound > 1.5 {
+        return bound - 1.2;
+    }
+    let power = 8.0;
+    let mut z = p;
+    let mut dr = 1.0;
+    let mut r = bound;
+    for _ in 0..iterations {
+        r = length(z);
+        if r > 2.0 {
+            break;
+        }
+        let theta = (z[1] / r.max(1e-9)).clamp(-1.0, 1.0).acos() * power;
+        let phi = z[2].atan2(z[0]) * power;
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+        let zr = r.powf(power);
+        z = [
+            zr * theta.sin() * phi.cos() + p[0],
+            zr * theta.cos() + p[1],
+            zr * theta.sin() * phi.sin() + p[2],
+        ];
+    }
+    0.5 * r.max(1e-9).ln() * r / dr
+}
+
+/// Menger sponge filling the cube from -1 to 1, with `levels` of holes.
+fn sd_menger(p: [f64; 3], levels: u32) -> f64 {
+    let mut d = sd_box(p, [1.0, 1.0, 1.0]);
+    let mut s = 1.0;
+    for _ in 0..levels {
+        let a = [
+            (p[0] * s).rem_euclid(2.0) - 1.0,
+            (p[1] * s).rem_euclid(2.0) - 1.0,
+            (p[2] * s).rem_euclid(2.0) - 1.0,
+        ];
+        s *= 3.0;
+        let r = [
+            (1.0 - 3.0 * a[0].abs()).abs(),
+            (1.0 - 3.0 * a[1].abs()).abs(),
+            (1.0 - 3.0 * a[2].abs()).abs(),
+        ];
+        // Distance to the cross-shaped hole through this level's cell
+        let cross = r[0].max(r[1]).min(r[1].max(r[2])).min(r[2].max(r[0]));
+        d = d.max((cross - 1.0) / s);
+    }
+    d
+}
+
+/// `p` turned by `angle` radians about the vertical axis.
+fn rotate_y(p: [f64; 3], angle: f64) -> [f64; 3] {
+    let (sin, cos) = angle.sin_cos();
+    [p[0] * cos - p[2] * sin, p[1], p[0] * sin + p[2] * cos]
+}
+
 fn op_smooth_union(d1: f64, d2: f64, k: f64) -> f64 {
     let h = (0.5 + 0.5 * (d2 - d1) / k).clamp(0.0, 1.0);
     d2 * (1.0 - h) + d1 * h - k * h * (1.0 - h)
@@ -56,7 +135,19 @@ fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
 }
 
 impl Raymarcher {
+    /// Distance to the selected scene and the material hit: 0 floor,
+    /// 1 colorful, 2 metallic.
     fn scene_sdf(&self, p: [f64; 3], t: f64) -> (f64, u8) {
+        match self.scene.round() as u32 {
+            0 => self.spheres_sdf(p, t),
+            1 => self.mandelbulb_sdf(p, t),
+            2 => self.menger_sdf(p, t),
+            _ => self.torus_field_sdf(p, t),
+        }
+    }
+
+    /// Blobby spheres orbiting over a floor among box pillars.
+    fn spheres_sdf(&self, p: [f64; 3], t: f64) -> (f64, u8) {
         // Ground plane
         let d_plane = sd_plane(p, -1.0);
 
@@ -119,6 +210,60 @@ impl Raymarcher {
         (d, mat)
     }
 
+    /// A slowly turning mandelbulb hovering over the floor; complexity adds
+    /// iterations and so finer folds.
+    fn mandelbulb_sdf(&self, p: [f64; 3], t: f64) -> (f64, u8) {
+        let d_plane = sd_plane(p, -2.0);
+        let iterations = 3 + (self.complexity * 2.0).round() as u32;
+        let scale = 1.8;
+        let q = rotate_y(p, t * 0.2);
+        let q = [q[0] / scale, q[1] / scale, q[2] / scale];
+        let d_bulb = sd_mandelbulb(q, iterations) * scale;
+        if d_bulb < d_plane {
+            (d_bulb, 1)
+        } else {
+            (d_plane, 0)
+        }
+    }
+
+    /// A turning menger sponge resting on the floor; complexity adds levels
+    /// of holes.
+    fn menger_sdf(&self, p: [f64; 3], t: f64) -> (f64, u8) {
+        let d_plane = sd_plane(p, -1.0);
+        let levels = 1 + (self.complexity * 1.5).round() as u32;
+        // Scaled up and raised so its base sits on the floor
+        let scale = 1.5;
+        let q = rotate_y([p[0], p[1] - 0.5, p[2]], t * 0.25);
+        let q = [q[0] / scale, q[1] / scale, q[2] / scale];
+        let d_sponge = sd_menger(q, levels) * scale;
+        if d_sponge < d_plane {
+            (d_sponge, 2)
+        } else {
+            (d_plane, 0)
+        }
+    }
+
+    /// A grid of tumbling tori, each a little out of step with its
+    /// neighbors.
+    fn torus_field_sdf(&self, p: [f64; 3], t: f64) -> (f64, u8) {
+        let d_plane = sd_plane(p, -1.0);
+        let spacing = 3.0;
+        let (cell_x, cell_z) = (
+            (p[0] / spacing + 0.5).floor(),
+            (p[2] / spacing + 0.5).floor(),
+        );
+        let q = [op_rep(p[0], spacing), p[1], op_rep(p[2], spacing)];
+        // Tumble about the horizontal x axis
+        let (sin, cos) = (t * 0.8 + (cell_x + cell_z) * 0.7).sin_cos();
+        let q = [q[0], q[1] * cos - q[2] * sin, q[1] * sin + q[2] * cos];
+        let d_torus = sd_torus(q, 0.8, 0.25 * self.complexity.max(0.4));
+        if d_torus < d_plane {
+            (d_torus, 1)
+        } else {
+            (d_plane, 0)
+        }
+    }
+
     fn calc_normal(&self, p: [f64; 3], t: f64) -> [f64; 3] {
         let e = 0.001;
         let (dx, _) = self.scene_sdf([p[0] + e, p[1], p[2]], t);
@@ -129,6 +274,50 @@ impl Raymarcher {
         let (dzn, _) = self.scene_sdf([p[0], p[1], p[2] - e], t);
         normalize([dx - dxn, dy - dyn_, dz - dzn])
     }
+
+    /// How much of the light reaches `p` along `dir`, from 0 in full shadow
+    /// to 1 in full light. Near misses of occluders darken it too, which
+    /// gives the penumbra; larger `k` makes harder edges.
+    fn soft_shadow(&self, p: [f64; 3], dir: [f64; 3], max_dist: f64, t: f64, k: f64) -> f64 {
+        let mut lit: f64 = 1.0;
+        let mut dist = 0.02;
+        for _ in 0..32 {
+            let q = [
+                p[0] + dir[0] * dist,
+                p[1] + dir[1] * dist,
+                p[2] + dir[2] * dist,
+            ];
+            let (d, _) = self.scene_sdf(q, t);
+            if d < 0.0005 {
+                return 0.0;
+            }
+            lit = lit.min(k * d / dist);
+            dist += d.clamp(0.02, 0.5);
+            if dist > max_dist {
+                break;
+            }
+        }
+        lit.clamp(0.0, 1.0)
+    }
+
+    /// Ambient occlusion from five samples along the normal: where the
+    /// scene is closer than the sample distance, something blocks the sky.
+    fn ambient_occlusion(&self, p: [f64; 3], normal: [f64; 3], t: f64) -> f64 {
+        let mut occlusion = 0.0;
+        let mut weight = 1.0;
+        for i in 1..=5 {
+            let h = 0.02 + 0.12 * i as f64;
+            let q = [
+                p[0] + normal[0] * h,
+                p[1] + normal[1] * h,
+                p[2] + normal[2] * h,
+            ];
+            let (d, _) = self.scene_sdf(q, t);
+            occlusion += (h - d) * weight;
+            weight *= 0.85;
+        }
+        (1.0 - 1.5 * occlusion).clamp(0.0, 1.0)
+    }
 }
 
 impl Effect for Raymarcher {
@@ -136,6 +325,10 @@ impl Effect for Raymarcher {
         "Raymarcher"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -152,18 +345,33 @@ impl Effect for Raymarcher {
         let hf = h as f64;
         let aspect = wf / hf;
         let t = t * self.speed;
-
-        // Camera orbits the scene
-        let cam_angle = t * 0.3;
-        let cam_dist = 5.0;
-        let cam_pos = [
-            cam_angle.cos() * cam_dist,
-            1.5 + 0.5 * (t * 0.4).sin(),
-            cam_angle.sin() * cam_dist,
-        ];
-
-        // Look at origin
-        let target = [0.0, 0.0, 0.0];
+        let quality = self.quality.round() as u32;
+
+        let (cam_pos, target) = match &self.path {
+            Some(path) => {
+                // Mouse look swings the scripted camera around what it faces
+                let (pos, target) = path.sample(t);
+                let (sin, cos) = self.look.0.sin_cos();
+                let (dx, dz) = (pos[0] - target[0], pos[2] - target[2]);
+                let pos = [
+                    target[0] + dx * cos - dz * sin,
+                    pos[1] + self.look.1 * 2.5,
+                    target[2] + dx * sin + dz * cos,
+                ];
+                (pos, target)
+            }
+            None => {
+                // Camera orbits the scene, looking at the origin
+                let cam_angle = t * 0.3 + self.look.0;
+                let cam_dist = 5.0;
+                let cam_pos = [
+                    cam_angle.cos() * cam_dist,
+                    1.5 + 0.5 * (t * 0.4).sin() + self.look.1 * 2.5,
+                    cam_angle.sin() * cam_dist,
+                ];
+                (cam_pos, [0.0, 0.0, 0.0])
+            }
+        };
         let forward = normalize([
             target[0] - cam_pos[0],
             target[1] - cam_pos[1],
@@ -248,8 +456,25 @@ impl Effect for Raymarcher {
                     light_pos[2] - hit_pos[2],
                 ]);
 
-                let diffuse = dot(normal, light_dir).max(0.0);
-                let ambient = 0.15;
+                let mut diffuse = dot(normal, light_dir).max(0.0);
+                let mut ambient = 0.15;
+                if quality >= 1 && diffuse > 0.0 {
+                    let light_dist = length([
+                        light_pos[0] - hit_pos[0],
+                        light_pos[1] - hit_pos[1],
+                        light_pos[2] - hit_pos[2],
+                    ]);
+                    // Start just off the surface so it doesn't shadow itself
+                    let start = [
+                        hit_pos[0] + normal[0] * 0.01,
+                        hit_pos[1] + normal[1] * 0.01,
+                        hit_pos[2] + normal[2] * 0.01,
+                    ];
+                    diffuse *= self.soft_shadow(start, light_dir, light_dist, t, 8.0);
+                }
+                if quality >= 2 {
+                    ambient *= self.ambient_occlusion(hit_pos, normal, t);
+                }
 
                 // Specular (Blinn-Phong)
                 let half_dir = normalize([
@@ -274,7 +499,7 @@ impl Effect for Raymarcher {
                     1 => {
                         // Spheres: colorful
                         let hue = (t * 0.1 + hit_pos[1] * 0.2) % 1.0;
-                        let (r, g, b) = hsv_to_rgb_f(hue, 0.6, 0.9);
+                        let (r, g, b) = hsv_to_rgb_f64(hue, 0.6, 0.9);
                         (r, g, b)
                     }
                     _ => {
@@ -311,30 +536,36 @@ impl Effect for Raymarcher {
                 max: 2.0,
                 value: self.complexity,
             },
+            ParamDesc {
+                name: "scene".to_string(),
+                min: 0.0,
+                max: 3.0,
+                value: self.scene,
+            },
+            ParamDesc {
+                name: "quality".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.quality,
+            },
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
+    fn set_camera_path(&mut self, path: CameraPath) {
+        self.path = Some(path);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "speed" => self.speed = value,
             "complexity" => self.complexity = value,
+            "scene" => self.scene = value,
+            "quality" => self.quality = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb_f(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    }
-}
@@ -1,6 +1,16 @@
 use crate::effect::{Effect, ParamDesc};
 use rand::rngs::StdRng;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+
+/// Well-known Gray-Scott regimes as (name, feed, kill).
+const PRESETS: [(&str, f64, f64); 6] = [
+    ("classic", 0.035, 0.065),
+    ("coral", 0.0545, 0.062),
+    ("mitosis", 0.0367, 0.0649),
+    ("maze", 0.029, 0.057),
+    ("spots", 0.025, 0.06),
+    ("holes", 0.039, 0.058),
+];
 
 pub struct ReactionDiffusion {
     width: u32,
@@ -11,6 +21,12 @@ pub struct ReactionDiffusion {
     v_grid: Vec<f64>,
     feed_rate: f64,
     kill_rate: f64,
+    /// Index into `PRESETS`
+    preset: f64,
+    /// 0 simulates at half resolution, 1 at full framebuffer resolution
+    detail: f64,
+    /// Re-seeds the grids when a preset is picked mid-run
+    rng: StdRng,
 }
 
 impl ReactionDiffusion {
@@ -24,6 +40,61 @@ impl ReactionDiffusion {
             v_grid: Vec::new(),
             feed_rate: 0.035,
             kill_rate: 0.065,
+            preset: 0.0,
+            detail: 0.0,
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+
+    /// Size the grids for the framebuffer and current detail level.
+    fn resize_grids(&mut self) {
+        let div = if self.detail.round() >= 1.0 { 1 } else { 2 };
+        self.grid_w = (self.width / div).max(2) as usize;
+        self.grid_h = (self.height / div).max(2) as usize;
+        let n = self.grid_w * self.grid_h;
+        self.u_grid = vec![1.0; n];
+        self.v_grid = vec![0.0; n];
+    }
+
+    fn reseed(&mut self) {
+        let mut rng = self.rng.clone();
+        self.init_grids(&mut rng);
+        self.rng = rng;
+    }
+
+    /// Bilinear sample of both chemicals at fractional grid coordinates,
+    /// wrapping around the edges like the simulation does.
+    fn sample(&self, gx: f64, gy: f64) -> (f64, f64) {
+        let gw = self.grid_w;
+        let gh = self.grid_h;
+        let x0 = gx.floor();
+        let y0 = gy.floor();
+        let fx = gx - x0;
+        let fy = gy - y0;
+        let x0 = (x0 as i64).rem_euclid(gw as i64) as usize;
+        let y0 = (y0 as i64).rem_euclid(gh as i64) as usize;
+        let x1 = (x0 + 1) % gw;
+        let y1 = (y0 + 1) % gh;
+        let lerp = |grid: &[f64]| {
+            let top = grid[y0 * gw + x0] * (1.0 - fx) + grid[y0 * gw + x1] * fx;
+            let bottom = grid[y1 * gw + x0] * (1.0 - fx) + grid[y1 * gw + x1] * fx;
+            top * (1.0 - fy) + bottom * fy
+        };
+        (lerp(&self.v_grid), lerp(&self.u_grid))
+    }
+
+    fn preset_index(value: f64) -> usize {
+        (value.round().max(0.0) as usize).min(PRESETS.len() - 1)
+    }
+
+    /// Snap the rates to a preset and start over from fresh seed spots, since
+    /// a grown pattern from another regime would take ages to convert.
+    fn apply_preset(&mut self) {
+        let (_, feed, kill) = PRESETS[Self::preset_index(self.preset)];
+        self.feed_rate = feed;
+        self.kill_rate = kill;
+        if self.grid_w > 0 && self.grid_h > 0 {
+            self.reseed();
         }
     }
 
@@ -126,18 +197,19 @@ impl Effect for ReactionDiffusion {
         "Reaction-Diffusion"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        20.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
-        self.grid_w = (width / 2).max(2) as usize;
-        self.grid_h = (height / 2).max(2) as usize;
-        let n = self.grid_w * self.grid_h;
-        self.u_grid = vec![1.0; n];
-        self.v_grid = vec![0.0; n];
+        self.resize_grids();
     }
 
     fn randomize_init(&mut self, rng: &mut StdRng) {
         self.init_grids(rng);
+        self.rng = StdRng::seed_from_u64(rng.gen());
     }
 
     fn update(&mut self, _t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -155,15 +227,15 @@ impl Effect for ReactionDiffusion {
         let gw = self.grid_w;
         let gh = self.grid_h;
 
-        // Render: map V concentration to color
+        // Render: map V concentration to color, sampling between cells so
+        // a coarser grid still gives smooth edges
+        let scale_x = gw as f64 / w as f64;
+        let scale_y = gh as f64 / h as f64;
         for y in 0..h {
-            let gy = (y as usize * gh) / h as usize;
-            let gy = gy.min(gh - 1);
+            let gy = (y as f64 + 0.5) * scale_y - 0.5;
             for x in 0..w {
-                let gx = (x as usize * gw) / w as usize;
-                let gx = gx.min(gw - 1);
-                let v = self.v_grid[gy * gw + gx];
-                let u_val = self.u_grid[gy * gw + gx];
+                let gx = (x as f64 + 0.5) * scale_x - 0.5;
+                let (v, u_val) = self.sample(gx, gy);
 
                 // Color mapping: dark blue -> teal -> white based on V
                 let (r, g, b) = if v < 0.15 {
@@ -213,6 +285,18 @@ impl Effect for ReactionDiffusion {
                 max: 0.075,
                 value: self.kill_rate,
             },
+            ParamDesc {
+                name: "preset".to_string(),
+                min: 0.0,
+                max: (PRESETS.len() - 1) as f64,
+                value: self.preset,
+            },
+            ParamDesc {
+                name: "detail".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.detail,
+            },
         ]
     }
 
@@ -220,6 +304,21 @@ impl Effect for ReactionDiffusion {
         match name {
             "feed_rate" => self.feed_rate = value,
             "kill_rate" => self.kill_rate = value,
+            "preset" => {
+                let changed = Self::preset_index(value) != Self::preset_index(self.preset);
+                self.preset = value;
+                if changed {
+                    self.apply_preset();
+                }
+            }
+            "detail" => {
+                let changed = value.round() != self.detail.round();
+                self.detail = value;
+                if changed && self.width > 0 && self.height > 0 {
+                    self.resize_grids();
+                    self.reseed();
+                }
+            }
             _ => {}
         }
     }
@@ -1,10 +1,23 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::media::SourceImage;
+use crate::texture::Edge;
+use std::f64::consts::PI;
+
+/// Hue scroll rate: 13 whole cycles per 20π seconds, close to 0.2/s
+const HUE_RATE: f64 = 13.0 / (20.0 * PI);
 
 pub struct Rotozoom {
     width: u32,
     height: u32,
     rotation_speed: f64,
     zoom_speed: f64,
+    /// What lies past the texture's edge: 0 wrap, 1 mirror, 2 clamp
+    edge: f64,
+    /// How far the corners darken, 0 for none
+    vignette: f64,
+    /// Picture spun and scaled instead of the XOR pattern
+    image: Option<SourceImage>,
 }
 
 impl Rotozoom {
@@ -14,8 +27,16 @@ impl Rotozoom {
             height: 0,
             rotation_speed: 1.0,
             zoom_speed: 1.0,
+            edge: 0.0,
+            vignette: 0.0,
+            image: None,
         }
     }
+
+    pub fn with_image(mut self, image: SourceImage) -> Self {
+        self.image = Some(image);
+        self
+    }
 }
 
 impl Effect for Rotozoom {
@@ -23,9 +44,21 @@ impl Effect for Rotozoom {
         "Rotozoom"
     }
 
+    fn loop_period(&self) -> Option<f64> {
+        // At default speeds rotation (0.8), zoom (0.7) and hue scroll all repeat after 20π
+        if self.rotation_speed == 1.0 && self.zoom_speed == 1.0 {
+            Some(20.0 * PI)
+        } else {
+            None
+        }
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
+        if let Some(image) = &mut self.image {
+            image.fit(width, height);
+        }
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -43,6 +76,11 @@ impl Effect for Rotozoom {
 
         let cos_a = angle.cos() / zoom;
         let sin_a = angle.sin() / zoom;
+        let edge = match self.edge.round() as u32 {
+            1 => Edge::Mirror,
+            2 => Edge::Clamp,
+            _ => Edge::Wrap,
+        };
 
         for y in 0..h {
             for x in 0..w {
@@ -52,19 +90,35 @@ impl Effect for Rotozoom {
                 // Inverse-rotate to get texture coordinates
                 let u = dx * cos_a + dy * sin_a;
                 let v = -dx * sin_a + dy * cos_a;
-
-                // XOR texture
-                let pattern = ((u.abs() as u32) & 255) ^ ((v.abs() as u32) & 255);
-                let normalized = pattern as f64 / 255.0;
-
-                let (r, g, b) = hsv_to_rgb(
-                    (normalized + t * 0.2) % 1.0,
-                    0.8,
-                    normalized * 0.7 + 0.3,
-                );
-
                 let idx = (y * w + x) as usize;
-                pixels[idx] = (r, g, b);
+
+                let color = match &self.image {
+                    Some(image) => image.sample_edge(u, v, edge),
+                    None => {
+                        // XOR texture, 256 texels across
+                        let tu = edge.fold(u.abs() as i64, 256);
+                        let tv = edge.fold(v.abs() as i64, 256);
+                        let normalized = (tu ^ tv) as f64 / 255.0;
+                        hsv_to_rgb(
+                            (normalized + t * HUE_RATE) % 1.0,
+                            0.8,
+                            normalized * 0.7 + 0.3,
+                        )
+                    }
+                };
+
+                pixels[idx] = if self.vignette > 0.0 {
+                    // Squared distance from the center, 1 in the corners
+                    let d2 = ((dx / cx).powi(2) + (dy / cy).powi(2)) * 0.5;
+                    let k = (1.0 - self.vignette * d2).max(0.0);
+                    (
+                        (color.0 as f64 * k) as u8,
+                        (color.1 as f64 * k) as u8,
+                        (color.2 as f64 * k) as u8,
+                    )
+                } else {
+                    color
+                };
             }
         }
     }
@@ -83,6 +137,18 @@ impl Effect for Rotozoom {
                 max: 5.0,
                 value: self.zoom_speed,
             },
+            ParamDesc {
+                name: "edge".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.edge,
+            },
+            ParamDesc {
+                name: "vignette".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.vignette,
+            },
         ]
     }
 
@@ -90,24 +156,9 @@ impl Effect for Rotozoom {
         match name {
             "rot_speed" => self.rotation_speed = value,
             "zoom_speed" => self.zoom_speed = value,
+            "edge" => self.edge = value,
+            "vignette" => self.vignette = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
@@ -0,0 +1,624 @@
+//! An effect written as per-pixel formulas, loaded with `--script`.
+//!
+//! ```text
+//! # A plasma in one line
+//! rgb = 0.5 + 0.5 * sin(x * 12 + t), 0.5 + 0.5 * sin(y * 9 - t), 0.5 + 0.5 * sin((x + y) * 7)
+//! ```
+//!
+//! Each line assigns a name, and later lines can use earlier ones. `x` and
+//! `y` run 0..1 across and down the frame and `t` is seconds; `pi` and `tau`
+//! are the usual constants. Setting `r`, `g` and `b` (0..1 each, or all
+//! three at once with `rgb = r, g, b`) colors the pixel. Scripts can only
+//! do arithmetic, so loading one can't touch files or the terminal.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::effect::{Effect, ParamDesc};
+
+/// Slots every script can read without assigning them
+const BUILTINS: [&str; 3] = ["x", "y", "t"];
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Abs,
+    Sqrt,
+    Exp,
+    Floor,
+    Fract,
+    Min,
+    Max,
+    Pow,
+    Atan2,
+    Clamp,
+    Mix,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sin" => Func::Sin,
+            "cos" => Func::Cos,
+            "tan" => Func::Tan,
+            "abs" => Func::Abs,
+            "sqrt" => Func::Sqrt,
+            "exp" => Func::Exp,
+            "floor" => Func::Floor,
+            "fract" => Func::Fract,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            "pow" => Func::Pow,
+            "atan2" => Func::Atan2,
+            "clamp" => Func::Clamp,
+            "mix" => Func::Mix,
+            _ => return None,
+        })
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Func::Min | Func::Max | Func::Pow | Func::Atan2 => 2,
+            Func::Clamp | Func::Mix => 3,
+            _ => 1,
+        }
+    }
+
+    fn apply(self, a: &[f64]) -> f64 {
+        match self {
+            Func::Sin => a[0].sin(),
+            Func::Cos => a[0].cos(),
+            Func::Tan => a[0].tan(),
+            Func::Abs => a[0].abs(),
+            Func::Sqrt => a[0].max(0.0).sqrt(),
+            Func::Exp => a[0].exp(),
+            Func::Floor => a[0].floor(),
+            Func::Fract => a[0] - a[0].floor(),
+            Func::Min => a[0].min(a[1]),
+            Func::Max => a[0].max(a[1]),
+            Func::Pow => a[0].powf(a[1]),
+            Func::Atan2 => a[0].atan2(a[1]),
+            Func::Clamp => a[0].max(a[1]).min(a[2]),
+            Func::Mix => a[0] + (a[1] - a[0]) * a[2],
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Expr {
+    Num(f64),
+    /// A builtin or an earlier assignment, by slot
+    Var(usize),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, slots: &[f64]) -> f64 {
+        match self {
+            Expr::Num(v) => *v,
+            Expr::Var(slot) => slots[*slot],
+            Expr::Neg(e) => -e.eval(slots),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.eval(slots), b.eval(slots));
+                match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                    Op::Rem => a.rem_euclid(b),
+                }
+            }
+            Expr::Call(func, args) => {
+                let mut values = [0.0; 3];
+                for (value, arg) in values.iter_mut().zip(args) {
+                    *value = arg.eval(slots);
+                }
+                func.apply(&values)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Symbol(char),
+}
+
+/// A parse failure at a 1-based column of its line.
+type ParseError = (usize, String);
+
+/// Split a line into tokens, each with the column it starts at.
+fn tokenize(line: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().enumerate().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        let column = i + 1;
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut text = String::new();
+            while let Some(&(_, c)) = chars
+                .peek()
+                .filter(|(_, c)| c.is_ascii_digit() || *c == '.')
+            {
+                text.push(c);
+                chars.next();
+            }
+            let value = text
+                .parse()
+                .map_err(|_| (column, format!("invalid number {:?}", text)))?;
+            tokens.push((Token::Num(value), column));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut text = String::new();
+            while let Some(&(_, c)) = chars
+                .peek()
+                .filter(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+            {
+                text.push(c);
+                chars.next();
+            }
+            tokens.push((Token::Ident(text), column));
+        } else if "+-*/%(),=".contains(c) {
+            tokens.push((Token::Symbol(c), column));
+            chars.next();
+        } else {
+            return Err((column, format!("unexpected character {:?}", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Named constants, which scripts can read but not assign
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" => Some(std::f64::consts::PI),
+        "tau" => Some(std::f64::consts::TAU),
+        _ => None,
+    }
+}
+
+/// Deepest an expression may nest, in parentheses and unary minus while
+/// parsing and in operators and calls once built, so hostile input can't
+/// exhaust the stack while parsing or evaluating.
+const MAX_DEPTH: usize = 64;
+
+/// Recursive-descent parser for one line's right-hand side. Each rule
+/// returns its expression along with the expression's depth.
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    pos: usize,
+    names: &'a [String],
+    /// Column just past the end of the line, for errors there
+    end: usize,
+    /// Current recursion depth
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    /// Column of the next token, or the end of the line.
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |&(_, column)| column)
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ParseError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err((self.column(), format!("expected '{}'", symbol)))
+        }
+    }
+
+    /// `depth`, unless it is past `MAX_DEPTH`.
+    fn check_depth(&self, depth: usize) -> Result<usize, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err((self.column(), "expression nested too deeply".to_string()));
+        }
+        Ok(depth)
+    }
+
+    /// Sums and differences
+    fn expr(&mut self) -> Result<(Expr, usize), ParseError> {
+        let (mut lhs, mut depth) = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                Op::Add
+            } else if self.eat('-') {
+                Op::Sub
+            } else {
+                return Ok((lhs, depth));
+            };
+            let (rhs, rhs_depth) = self.term()?;
+            depth = self.check_depth(depth.max(rhs_depth) + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    /// Products, quotients and remainders
+    fn term(&mut self) -> Result<(Expr, usize), ParseError> {
+        let (mut lhs, mut depth) = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                Op::Mul
+            } else if self.eat('/') {
+                Op::Div
+            } else if self.eat('%') {
+                Op::Rem
+            } else {
+                return Ok((lhs, depth));
+            };
+            let (rhs, rhs_depth) = self.unary()?;
+            depth = self.check_depth(depth.max(rhs_depth) + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn unary(&mut self) -> Result<(Expr, usize), ParseError> {
+        // Every level of parentheses passes through here too
+        self.depth = self.check_depth(self.depth + 1)?;
+        let parsed = if self.eat('-') {
+            let (inner, depth) = self.unary()?;
+            (Expr::Neg(Box::new(inner)), self.check_depth(depth + 1)?)
+        } else {
+            self.atom()?
+        };
+        self.depth -= 1;
+        Ok(parsed)
+    }
+
+    fn atom(&mut self) -> Result<(Expr, usize), ParseError> {
+        let column = self.column();
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or((column, "unexpected end of line".to_string()))?;
+        self.pos += 1;
+        match token {
+            Token::Num(value) => Ok((Expr::Num(value), 1)),
+            Token::Symbol('(') => {
+                let inner = self.expr()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.eat('(') => {
+                let func = Func::from_name(&name)
+                    .ok_or_else(|| (column, format!("unknown function {:?}", name)))?;
+                let (first, mut depth) = self.expr()?;
+                let mut args = vec![first];
+                while self.eat(',') {
+                    let (arg, arg_depth) = self.expr()?;
+                    args.push(arg);
+                    depth = depth.max(arg_depth);
+                }
+                self.expect(')')?;
+                if args.len() != func.arity() {
+                    return Err((
+                        column,
+                        format!("{} takes {} argument(s)", name, func.arity()),
+                    ));
+                }
+                Ok((Expr::Call(func, args), self.check_depth(depth + 1)?))
+            }
+            Token::Ident(name) => match constant(&name) {
+                Some(value) => Ok((Expr::Num(value), 1)),
+                None => self
+                    .names
+                    .iter()
+                    .position(|n| *n == name)
+                    .map(|slot| (Expr::Var(slot), 1))
+                    .ok_or_else(|| (column, format!("unknown name {:?}", name))),
+            },
+            Token::Symbol(c) => Err((column, format!("unexpected '{}'", c))),
+        }
+    }
+}
+
+pub struct Scripted {
+    width: u32,
+    height: u32,
+    speed: f64,
+    /// Assignments in order, each storing into a slot
+    program: Vec<(usize, Expr)>,
+    /// Slots of `r`, `g` and `b`, where the script assigned them
+    channels: [Option<usize>; 3],
+    /// Builtins followed by the script's names, reused every pixel
+    slots: Vec<f64>,
+}
+
+impl Scripted {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut names: Vec<String> = BUILTINS.iter().map(|s| s.to_string()).collect();
+        let mut program = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            parse_line(line, &mut names, &mut program)
+                .map_err(|(column, msg)| format!("line {}, column {}: {}", i + 1, column, msg))?;
+        }
+
+        let slot = |name: &str| names.iter().position(|n| n == name);
+        let channels = [slot("r"), slot("g"), slot("b")];
+        if channels.iter().all(Option::is_none) {
+            return Err("the script never sets r, g or b".to_string());
+        }
+        Ok(Self {
+            width: 0,
+            height: 0,
+            speed: 1.0,
+            program,
+            channels,
+            slots: vec![0.0; names.len()],
+        })
+    }
+}
+
+/// Parse `name = expr` or `rgb = expr, expr, expr` onto the program,
+/// declaring new names as it goes. Columns count from the start of `line`.
+fn parse_line(
+    line: &str,
+    names: &mut Vec<String>,
+    program: &mut Vec<(usize, Expr)>,
+) -> Result<(), ParseError> {
+    let tokens = tokenize(line)?;
+    let target = match tokens.as_slice() {
+        [(Token::Ident(name), _), (Token::Symbol('='), _), ..] => name.clone(),
+        _ => return Err((1, "expected `name = expression`".to_string())),
+    };
+    if BUILTINS.contains(&target.as_str())
+        || constant(&target).is_some()
+        || Func::from_name(&target).is_some()
+    {
+        return Err((1, format!("cannot assign to {:?}", target)));
+    }
+
+    let mut parser = Parser {
+        tokens: &tokens[2..],
+        pos: 0,
+        names,
+        end: line.chars().count() + 1,
+        depth: 0,
+    };
+    let mut values = vec![parser.expr()?.0];
+    while parser.eat(',') {
+        values.push(parser.expr()?.0);
+    }
+    if parser.pos < parser.tokens.len() {
+        return Err((
+            parser.column(),
+            "unexpected text after the expression".to_string(),
+        ));
+    }
+
+    let targets: Vec<String> = match (target.as_str(), values.len()) {
+        ("rgb", 3) => vec!["r".into(), "g".into(), "b".into()],
+        ("rgb", _) => return Err((1, "rgb takes three values".to_string())),
+        (_, 1) => vec![target],
+        _ => return Err((1, format!("{} takes one value", target))),
+    };
+    for (name, value) in targets.into_iter().zip(values) {
+        let slot = match names.iter().position(|n| *n == name) {
+            Some(slot) => slot,
+            None => {
+                names.push(name);
+                names.len() - 1
+            }
+        };
+        program.push((slot, value));
+    }
+    Ok(())
+}
+
+impl Effect for Scripted {
+    fn name(&self) -> &str {
+        "Scripted"
+    }
+
+    fn init(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
+        let (w, h) = (self.width, self.height);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let t = t * self.speed;
+        let to_byte = |slots: &[f64], slot: Option<usize>| match slot {
+            // NaN and infinities from a stray division land on black or white
+            Some(slot) => (slots[slot].clamp(0.0, 1.0) * 255.0) as u8,
+            None => 0,
+        };
+        for y in 0..h {
+            for x in 0..w {
+                self.slots[0] = x as f64 / w as f64;
+                self.slots[1] = y as f64 / h as f64;
+                self.slots[2] = t;
+                for (slot, expr) in &self.program {
+                    self.slots[*slot] = expr.eval(&self.slots);
+                }
+                pixels[(y * w + x) as usize] = (
+                    to_byte(&self.slots, self.channels[0]),
+                    to_byte(&self.slots, self.channels[1]),
+                    to_byte(&self.slots, self.channels[2]),
+                );
+            }
+        }
+    }
+
+    fn params(&self) -> Vec<ParamDesc> {
+        vec![ParamDesc {
+            name: "speed".to_string(),
+            min: 0.1,
+            max: 3.0,
+            value: self.speed,
+        }]
+    }
+
+    fn set_param(&mut self, name: &str, value: f64) {
+        if name == "speed" {
+            self.speed = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Value of `expr` at x = 0.25, y = 0.5, t = 2.
+    fn eval(expr: &str) -> Result<f64, ParseError> {
+        let mut names = BUILTINS.iter().map(|s| s.to_string()).collect();
+        let mut program = Vec::new();
+        parse_line(&format!("v = {}", expr), &mut names, &mut program)?;
+        Ok(program[0].1.eval(&[0.25, 0.5, 2.0, 0.0]))
+    }
+
+    fn error(script: &str) -> String {
+        Scripted::parse(script)
+            .err()
+            .expect("script should not parse")
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(eval("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(eval("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(eval("8 - 3 - 2").unwrap(), 3.0);
+        assert_eq!(eval("16 / 4 / 2").unwrap(), 2.0);
+        assert_eq!(eval("7 % 4 * 2").unwrap(), 6.0);
+        assert_eq!(eval("-7 % 4").unwrap(), 1.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(eval("-3").unwrap(), -3.0);
+        assert_eq!(eval("--3").unwrap(), 3.0);
+        assert_eq!(eval("2 * -x").unwrap(), -0.5);
+        assert_eq!(eval("-(1 + 2) * 2").unwrap(), -6.0);
+    }
+
+    #[test]
+    fn builtins_constants_and_functions() {
+        assert_eq!(eval("x + y * t").unwrap(), 1.25);
+        assert_eq!(eval("tau / pi").unwrap(), 2.0);
+        assert_eq!(eval("max(x, y)").unwrap(), 0.5);
+        assert_eq!(eval("clamp(t, 0, 1)").unwrap(), 1.0);
+        assert_eq!(eval("mix(2, 4, x)").unwrap(), 2.5);
+        assert_eq!(eval("pow(t, 3)").unwrap(), 8.0);
+        assert_eq!(eval("fract(-0.25)").unwrap(), 0.75);
+        assert_eq!(eval("sqrt(-4)").unwrap(), 0.0);
+        assert!((eval("sin(pi / 2)").unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn later_lines_read_earlier_ones() {
+        let mut script = Scripted::parse("a = x * 2\nrgb = a, a + y, 1\n").unwrap();
+        script.init(4, 2);
+        let mut pixels = vec![(0, 0, 0); 8];
+        script.update(0.0, 0.0, &mut pixels);
+        // x = 0.25, y = 0.5 at column 1 of row 1
+        assert_eq!(pixels[5], (127, 255, 255));
+    }
+
+    #[test]
+    fn errors_carry_line_and_column() {
+        assert_eq!(
+            error("r = 1\ng = 2 $ 3"),
+            "line 2, column 7: unexpected character '$'"
+        );
+        assert_eq!(
+            error("r = foo + 1"),
+            "line 1, column 5: unknown name \"foo\""
+        );
+        assert_eq!(
+            error("r = bar(1)"),
+            "line 1, column 5: unknown function \"bar\""
+        );
+        assert_eq!(
+            error("r = min(1)"),
+            "line 1, column 5: min takes 2 argument(s)"
+        );
+        assert_eq!(error("r = (1 + 2"), "line 1, column 11: expected ')'");
+        assert_eq!(
+            error("r = 1 2"),
+            "line 1, column 7: unexpected text after the expression"
+        );
+        assert_eq!(error("r = 1 +"), "line 1, column 8: unexpected end of line");
+        assert_eq!(error("r ="), "line 1, column 4: unexpected end of line");
+        assert_eq!(
+            error("rgb = 1, 2"),
+            "line 1, column 1: rgb takes three values"
+        );
+        assert_eq!(error("a = 1"), "the script never sets r, g or b");
+    }
+
+    #[test]
+    fn reserved_names_cannot_be_assigned() {
+        for name in ["x", "t", "pi", "tau", "sin"] {
+            assert_eq!(
+                error(&format!("{} = 1\nr = 1", name)),
+                format!("line 1, column 1: cannot assign to {:?}", name)
+            );
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error_not_a_crash() {
+        let parens = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(eval(&parens).unwrap_err().1.contains("nested too deeply"));
+        let negations = format!("{}1", "-".repeat(100_000));
+        assert!(eval(&negations)
+            .unwrap_err()
+            .1
+            .contains("nested too deeply"));
+        let chain = vec!["1"; 100_000].join(" + ");
+        assert!(eval(&chain).unwrap_err().1.contains("nested too deeply"));
+        let calls = format!("{}1{}", "abs(".repeat(100_000), ")".repeat(100_000));
+        assert!(eval(&calls).unwrap_err().1.contains("nested too deeply"));
+
+        let (ok_parens, ok_chain) = (
+            "(".repeat(40) + "1" + &")".repeat(40),
+            vec!["1"; 40].join("+"),
+        );
+        assert_eq!(eval(&ok_parens).unwrap(), 1.0);
+        assert_eq!(eval(&ok_chain).unwrap(), 40.0);
+    }
+}
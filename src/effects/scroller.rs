@@ -1,9 +1,10 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
-use font8x8::UnicodeFonts;
+use crate::font::{self, FONT_HEIGHT, FONT_WIDTH};
 
 const GLYPH_SCALE: u32 = 2;
-const GLYPH_W: u32 = 8 * GLYPH_SCALE;
-const GLYPH_H: u32 = 8 * GLYPH_SCALE;
+const GLYPH_W: u32 = FONT_WIDTH * GLYPH_SCALE;
+const GLYPH_H: u32 = FONT_HEIGHT * GLYPH_SCALE;
 
 pub struct Scroller {
     text: String,
@@ -23,12 +24,6 @@ impl Scroller {
             wave_amp: 1.0,
         }
     }
-
-    fn get_glyph(ch: char) -> [u8; 8] {
-        font8x8::BASIC_FONTS
-            .get(ch)
-            .unwrap_or(font8x8::BASIC_FONTS.get(' ').unwrap_or([0; 8]))
-    }
 }
 
 impl Effect for Scroller {
@@ -36,11 +31,19 @@ impl Effect for Scroller {
         "Scroller"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        16.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
     }
 
+    fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width;
         let h = self.height;
@@ -63,12 +66,16 @@ impl Effect for Scroller {
         }
 
         let text_bytes: Vec<char> = self.text.chars().collect();
-        let total_text_width = text_bytes.len() as f64 * GLYPH_W as f64;
+        // Proportional advance: glyph width plus one blank column
+        let advance = |ch: char| ((font::glyph_width(ch) + 1) * GLYPH_SCALE) as f64;
+        let total_text_width: f64 = text_bytes.iter().map(|&ch| advance(ch)).sum();
         let scroll_offset = (t * self.speed * 120.0) % (total_text_width + w as f64);
         let center_y = h as f64 / 2.0 - GLYPH_H as f64 / 2.0;
 
+        let mut pen_x = 0.0;
         for (ci, &ch) in text_bytes.iter().enumerate() {
-            let char_x = ci as f64 * GLYPH_W as f64 - scroll_offset + w as f64;
+            let char_x = pen_x - scroll_offset + w as f64;
+            pen_x += advance(ch);
 
             // Skip characters fully off-screen
             if char_x + GLYPH_W as f64 <= 0.0 || char_x >= w as f64 {
@@ -84,11 +91,10 @@ impl Effect for Scroller {
             let hue = (ci as f64 * 0.12 + t * 0.8) % 1.0;
             let (cr, cg, cb) = hsv_to_rgb(hue, 1.0, 1.0);
 
-            let glyph = Self::get_glyph(ch);
-            for gy in 0..8u32 {
-                let row_bits = glyph[gy as usize];
-                for gx in 0..8u32 {
-                    if row_bits & (1 << gx) != 0 {
+            let glyph = font::glyph(ch);
+            for gy in 0..FONT_HEIGHT {
+                for gx in 0..FONT_WIDTH {
+                    if font::pixel_set(&glyph, gx, gy) {
                         // Draw scaled pixel
                         for sy in 0..GLYPH_SCALE {
                             for sx in 0..GLYPH_SCALE {
@@ -137,20 +143,3 @@ impl Effect for Scroller {
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
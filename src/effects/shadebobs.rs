@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb_f64;
 use crate::effect::{Effect, ParamDesc};
 
 struct Bob {
@@ -148,19 +149,3 @@ impl Effect for Shadebobs {
         }
     }
 }
-
-fn hsv_to_rgb_f64(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    }
-}
@@ -41,6 +41,10 @@ impl Effect for Sierpinski {
         "Sierpinski"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
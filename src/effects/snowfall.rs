@@ -1,4 +1,8 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::noise;
+use crate::rng;
+use rand::rngs::StdRng;
+use rand::Rng;
 use std::f64::consts::PI;
 
 pub struct Snowfall {
@@ -6,6 +10,10 @@ pub struct Snowfall {
     height: u32,
     wind: f64,
     density: f64,
+    /// Strength of the swirling gusts on top of the steady wind
+    turbulence: f64,
+    /// Salted hash for the layout; `randomize_init` picks a new salt
+    hash: rng::Hash,
 }
 
 impl Snowfall {
@@ -15,16 +23,19 @@ impl Snowfall {
             height: 0,
             wind: 0.3,
             density: 1.0,
+            turbulence: 0.7,
+            hash: rng::Hash::default(),
         }
     }
 
-    /// Deterministic pseudo-random from seed, returns 0.0..1.0.
-    fn rng(seed: u32) -> f64 {
-        let mut h = seed;
-        h = h.wrapping_mul(747796405).wrapping_add(2891336453);
-        h = ((h >> ((h >> 28).wrapping_add(4))) ^ h).wrapping_mul(277803737);
-        h = h ^ (h >> 22);
-        (h & 0x00FFFFFF) as f64 / 0x01000000 as f64
+    /// Swirl at (x, y): the curl of a slow, squashed `sine_field`, so flakes
+    /// circle around eddies instead of bunching up or thinning out.
+    fn swirl(x: f64, y: f64, t: f64) -> (f64, f64) {
+        let field = |x: f64, y: f64| noise::sine_field(x * 1.2, y * 1.6, t * 0.4);
+        let e = 1.0;
+        let dx = (field(x + e, y) - field(x - e, y)) / (2.0 * e);
+        let dy = (field(x, y + e) - field(x, y - e)) / (2.0 * e);
+        (dy, -dx)
     }
 }
 
@@ -38,6 +49,10 @@ impl Effect for Snowfall {
         self.height = height;
     }
 
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        self.hash = rng::Hash::new(rng.gen());
+    }
+
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width;
         let h = self.height;
@@ -91,9 +106,9 @@ impl Effect for Snowfall {
             for i in 0..count {
                 let seed_base = (layer_idx as u32) * 10000 + i;
 
-                let start_x = Self::rng(seed_base * 3 + 1) * wf;
-                let start_y = Self::rng(seed_base * 3 + 2) * hf;
-                let offset = Self::rng(seed_base * 3 + 3) * PI * 2.0;
+                let start_x = self.hash.u(seed_base * 3 + 1) * wf;
+                let start_y = self.hash.u(seed_base * 3 + 2) * hf;
+                let offset = self.hash.u(seed_base * 3 + 3) * PI * 2.0;
 
                 // Y position wraps around screen
                 let fall_y = (start_y + t * speed) % hf;
@@ -103,6 +118,12 @@ impl Effect for Snowfall {
                     + self.wind * t * speed * 0.15;
                 let fall_x = ((start_x + drift) % wf + wf) % wf;
 
+                // Gusts push flakes along the swirl field; nearer layers move more
+                let (sx, sy) = Self::swirl(fall_x, fall_y, t);
+                let gust = self.turbulence * speed * 8.0;
+                let fall_x = ((fall_x + sx * gust) % wf + wf) % wf;
+                let fall_y = fall_y + sy * gust;
+
                 // Don't draw below ground
                 if fall_y >= ground_base as f64 - 1.0 {
                     continue;
@@ -154,6 +175,12 @@ impl Effect for Snowfall {
                 max: 3.0,
                 value: self.density,
             },
+            ParamDesc {
+                name: "turbulence".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.turbulence,
+            },
         ]
     }
 
@@ -161,6 +188,7 @@ impl Effect for Snowfall {
         match name {
             "wind" => self.wind = value,
             "density" => self.density = value,
+            "turbulence" => self.turbulence = value,
             _ => {}
         }
     }
@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb_f64;
 use crate::effect::{Effect, ParamDesc};
 use std::f64::consts::TAU;
 
@@ -5,7 +6,8 @@ pub struct Spirograph {
     width: u32,
     height: u32,
     speed: f64,
-    complexity: f64,
+    /// Number of simultaneous pens, each on its own wheel
+    pens: f64,
     canvas: Vec<(f64, f64, f64)>,
     angle: f64,
 }
@@ -16,7 +18,7 @@ impl Spirograph {
             width: 0,
             height: 0,
             speed: 1.0,
-            complexity: 4.0,
+            pens: 4.0,
             canvas: Vec::new(),
             angle: 0.0,
         }
@@ -30,11 +32,34 @@ struct CurveParams {
     hue: f64,
 }
 
+impl CurveParams {
+    /// Wheel size, pen offset and color for pen `i` of `pens`. Each pen's
+    /// ratio drifts slowly out of phase with the others, and the hues are
+    /// spread evenly around the color wheel.
+    fn for_pen(i: usize, pens: usize, t: f64) -> Self {
+        let fi = i as f64;
+        let base_phase = fi * TAU / pens as f64;
+        // Slowly evolve the ratios over time for variety
+        let evolve = (t * 0.03 + base_phase).sin() * 0.3;
+        let small_r = (0.2 + fi * 0.15 + evolve * 0.1).fract() * 0.6 + 0.15;
+        Self {
+            big_r: 1.0,
+            small_r,
+            d: small_r * (0.7 + evolve * 0.3),
+            hue: (fi / pens as f64 + t * 0.02) % 1.0,
+        }
+    }
+}
+
 impl Effect for Spirograph {
     fn name(&self) -> &str {
         "Spirograph"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -62,22 +87,10 @@ impl Effect for Spirograph {
             c.2 *= 0.965;
         }
 
-        // Number of curves depends on complexity
-        let num_curves = self.complexity as usize;
-
-        // Define curves with slowly evolving parameters
-        let curves: Vec<CurveParams> = (0..num_curves)
-            .map(|i| {
-                let fi = i as f64;
-                let base_phase = fi * TAU / num_curves as f64;
-                // Slowly evolve the ratios over time for variety
-                let evolve = (t * 0.03 + base_phase).sin() * 0.3;
-                let big_r = 1.0;
-                let small_r = (0.2 + fi * 0.15 + evolve * 0.1).fract() * 0.6 + 0.15;
-                let d = small_r * (0.7 + evolve * 0.3);
-                let hue = (fi / num_curves as f64 + t * 0.02) % 1.0;
-                CurveParams { big_r, small_r, d, hue }
-            })
+        // Every pen traces its own hypotrochoid; their light adds up
+        let pens = self.pens.round() as usize;
+        let curves: Vec<CurveParams> = (0..pens)
+            .map(|i| CurveParams::for_pen(i, pens, t))
             .collect();
 
         // Advance angle and plot new points
@@ -151,10 +164,10 @@ impl Effect for Spirograph {
                 value: self.speed,
             },
             ParamDesc {
-                name: "complexity".to_string(),
-                min: 2.0,
+                name: "pens".to_string(),
+                min: 1.0,
                 max: 8.0,
-                value: self.complexity,
+                value: self.pens,
             },
         ]
     }
@@ -162,24 +175,8 @@ impl Effect for Spirograph {
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "speed" => self.speed = value,
-            "complexity" => self.complexity = value,
+            "pens" => self.pens = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb_f64(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    }
-}
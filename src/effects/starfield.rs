@@ -1,8 +1,11 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::splat;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
 const NUM_STARS: usize = 400;
+/// How quickly a warp burst eases back to cruising speed (per second)
+const WARP_DECAY: f64 = 3.0;
 
 struct Star {
     x: f64,
@@ -17,6 +20,10 @@ pub struct Starfield {
     height: u32,
     stars: Vec<Star>,
     speed: f64,
+    /// Velocity multiplier at the peak of a warp burst
+    max_warp: f64,
+    /// Current burst strength, 1 right after a trigger and decaying to 0
+    warp: f64,
     rng: StdRng,
 }
 
@@ -27,6 +34,8 @@ impl Starfield {
             height: 0,
             stars: Vec::new(),
             speed: 1.0,
+            max_warp: 6.0,
+            warp: 0.0,
             rng: StdRng::seed_from_u64(0),
         }
     }
@@ -51,6 +60,7 @@ impl Effect for Starfield {
         self.width = width;
         self.height = height;
         self.stars.clear();
+        self.warp = 0.0;
     }
 
     fn randomize_init(&mut self, rng: &mut StdRng) {
@@ -78,8 +88,13 @@ impl Effect for Starfield {
         let cx = w as f64 / 2.0;
         let cy = h as f64 / 2.0;
 
+        self.warp *= (-dt * WARP_DECAY).exp();
+        let velocity = 1.0 + self.warp * (self.max_warp - 1.0);
+        // Streaks stretch back toward the center while warping
+        let stretch = 1.0 + self.warp * 2.0;
+
         for star in &mut self.stars {
-            star.z -= dt * self.speed * 0.5;
+            star.z -= dt * self.speed * 0.5 * velocity;
 
             if star.z <= 0.01 {
                 *star = Self::spawn_star(&mut self.rng);
@@ -97,28 +112,18 @@ impl Effect for Starfield {
             // Brightness based on depth (closer = brighter)
             let brightness = ((1.0 - star.z) * 255.0).clamp(40.0, 255.0) as u8;
 
-            // Draw a short trail from prev to current position
-            let steps = 4;
+            // Draw a trail from prev to current position, longer during warp
+            let tail_x = sx - (sx - star.prev_sx) * stretch;
+            let tail_y = sy - (sy - star.prev_sy) * stretch;
+            let len = (sx - tail_x).abs().max((sy - tail_y).abs());
+            let steps = (len.ceil() as usize).clamp(4, 256);
             for i in 0..=steps {
                 let t = i as f64 / steps as f64;
-                let px = star.prev_sx + (sx - star.prev_sx) * t;
-                let py = star.prev_sy + (sy - star.prev_sy) * t;
-                let ix = px as i32;
-                let iy = py as i32;
-
-                if ix >= 0 && ix < w as i32 && iy >= 0 && iy < h as i32 {
-                    let idx = (iy as u32 * w + ix as u32) as usize;
-                    if idx < pixels.len() {
-                        let trail_bright =
-                            (brightness as f64 * (0.3 + 0.7 * t)) as u8;
-                        let existing = pixels[idx];
-                        pixels[idx] = (
-                            existing.0.max(trail_bright),
-                            existing.1.max(trail_bright),
-                            existing.2.max(trail_bright),
-                        );
-                    }
-                }
+                let px = tail_x + (sx - tail_x) * t;
+                let py = tail_y + (sy - tail_y) * t;
+                let trail_bright = (brightness as f64 * (0.3 + 0.7 * t)) as u8;
+                let color = (trail_bright, trail_bright, trail_bright);
+                splat::splat_point(pixels, w, h, (px, py), color);
             }
 
             star.prev_sx = sx;
@@ -126,18 +131,32 @@ impl Effect for Starfield {
         }
     }
 
+    fn trigger(&mut self) {
+        self.warp = 1.0;
+    }
+
     fn params(&self) -> Vec<ParamDesc> {
-        vec![ParamDesc {
-            name: "speed".to_string(),
-            min: 0.2,
-            max: 5.0,
-            value: self.speed,
-        }]
+        vec![
+            ParamDesc {
+                name: "speed".to_string(),
+                min: 0.2,
+                max: 5.0,
+                value: self.speed,
+            },
+            ParamDesc {
+                name: "max_warp".to_string(),
+                min: 2.0,
+                max: 10.0,
+                value: self.max_warp,
+            },
+        ]
     }
 
     fn set_param(&mut self, name: &str, value: f64) {
-        if name == "speed" {
-            self.speed = value;
+        match name {
+            "speed" => self.speed = value,
+            "max_warp" => self.max_warp = value,
+            _ => {}
         }
     }
 }
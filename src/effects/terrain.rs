@@ -1,4 +1,7 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::noise::{ValueNoise, TERRAIN_SEED};
+use crate::sunlight;
+use std::f64::consts::PI;
 
 
 pub struct Terrain {
@@ -6,8 +9,27 @@ pub struct Terrain {
     height: u32,
     speed: f64,
     roughness: f64,
+    /// Altitude of the shoreline; everything below is water
+    water_level: f64,
+    /// Altitude where rock gives way to snow
+    snow_line: f64,
+    /// Sun direction around the horizon, in radians
+    sun_angle: f64,
+    noise: ValueNoise,
 }
 
+/// Depth over which shallow water darkens into deep water
+const SHALLOWS: f64 = 0.4;
+/// Height of the beach band above the shoreline
+const BEACH: f64 = 0.2;
+/// Slope exaggeration for lighting; the hills are gentle at world scale
+const RELIEF: f64 = 10.0;
+/// World units per noise cell at roughness 1, about the width of one hill
+const HILL_SIZE: f64 = 40.0;
+/// Vertical scale of the hills, in the same units as the camera height
+const HILL_HEIGHT: f64 = 3.0;
+const OCTAVES: u32 = 5;
+
 impl Terrain {
     pub fn new() -> Self {
         Self {
@@ -15,45 +37,51 @@ impl Terrain {
             height: 0,
             speed: 1.0,
             roughness: 1.0,
+            water_level: -0.2,
+            snow_line: 1.0,
+            sun_angle: 2.4,
+            noise: ValueNoise::new(TERRAIN_SEED),
         }
     }
 
-    /// Compute terrain height at world (x, z) using layered sine waves.
+    /// Compute terrain height at world (x, z) from fractal noise.
     fn terrain_height(&self, x: f64, z: f64) -> f64 {
-        let r = self.roughness;
-        let mut h = 0.0;
-        h += (x * 0.031 * r + z * 0.047 * r).sin() * 1.0;
-        h += (x * 0.067 * r - z * 0.073 * r + 1.3).sin() * 0.5;
-        h += (x * 0.113 * r + z * 0.097 * r + 2.7).sin() * 0.25;
-        h += (x * 0.191 * r - z * 0.157 * r + 4.1).sin() * 0.125;
-        h += ((x * 0.051 * r).sin() * (z * 0.043 * r).cos()) * 0.6;
-        h
+        let scale = self.roughness / HILL_SIZE;
+        self.noise.fbm2(x * scale, z * scale, OCTAVES) * HILL_HEIGHT
     }
 
     /// Color by elevation: water -> grass -> hills -> snow.
-    fn terrain_color(h: f64) -> (f64, f64, f64) {
-        if h < -0.6 {
+    ///
+    /// Grass and rock split the land between the beach and the snow line
+    /// evenly; the snow line is kept a little above the beach so every band
+    /// stays ordered.
+    fn terrain_color(&self, h: f64) -> (f64, f64, f64) {
+        let water = self.water_level;
+        let land = water + BEACH;
+        let snow = self.snow_line.max(land + 0.1);
+        let rock = (land + snow) * 0.5;
+        if h < water - SHALLOWS {
             // Deep water
             (0.1, 0.2, 0.6)
-        } else if h < -0.2 {
+        } else if h < water {
             // Shallow water
-            let t = (h + 0.6) / 0.4;
+            let t = (h - water + SHALLOWS) / SHALLOWS;
             (0.1 + t * 0.05, 0.2 + t * 0.15, 0.6 + t * 0.1)
-        } else if h < 0.0 {
+        } else if h < land {
             // Beach / low grass
-            let t = (h + 0.2) / 0.2;
+            let t = (h - water) / BEACH;
             (0.15 + t * 0.05, 0.35 + t * 0.2, 0.1 + t * 0.05)
-        } else if h < 0.5 {
+        } else if h < rock {
             // Grass
-            let t = h / 0.5;
+            let t = (h - land) / (rock - land);
             (0.2 + t * 0.15, 0.55 - t * 0.1, 0.15 - t * 0.05)
-        } else if h < 1.0 {
+        } else if h < snow {
             // Brown hills / rock
-            let t = (h - 0.5) / 0.5;
+            let t = (h - rock) / (snow - rock);
             (0.35 + t * 0.2, 0.3 + t * 0.05, 0.1 + t * 0.15)
         } else {
             // Snow peaks
-            let t = ((h - 1.0) / 0.5).min(1.0);
+            let t = ((h - snow) / 0.5).min(1.0);
             (0.55 + t * 0.4, 0.35 + t * 0.6, 0.25 + t * 0.7)
         }
     }
@@ -64,6 +92,10 @@ impl Effect for Terrain {
         "Terrain"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -120,7 +152,19 @@ impl Effect for Terrain {
                 let screen_y = (horizon + projected) as usize;
 
                 if screen_y < max_drawn_sy && screen_y < h {
-                    let (cr, cg, cb) = Self::terrain_color(terrain_h);
+                    // Water stays flat; land is lit by its slope
+                    let light = if terrain_h < self.water_level {
+                        1.0
+                    } else {
+                        let step = 0.5;
+                        let slope_x =
+                            (self.terrain_height(world_x + step, world_z) - terrain_h) / step;
+                        let slope_z =
+                            (self.terrain_height(world_x, world_z + step) - terrain_h) / step;
+                        sunlight::shade(slope_x * RELIEF, slope_z * RELIEF, self.sun_angle)
+                    };
+                    let (cr, cg, cb) = self.terrain_color(terrain_h);
+                    let (cr, cg, cb) = (cr * light, cg * light, cb * light);
 
                     // Distance fog
                     let fog_t = (dist / max_dist).powi(2).clamp(0.0, 1.0);
@@ -159,6 +203,24 @@ impl Effect for Terrain {
                 max: 2.0,
                 value: self.roughness,
             },
+            ParamDesc {
+                name: "water_level".to_string(),
+                min: -1.5,
+                max: 1.5,
+                value: self.water_level,
+            },
+            ParamDesc {
+                name: "snow_line".to_string(),
+                min: -1.0,
+                max: 2.0,
+                value: self.snow_line,
+            },
+            ParamDesc {
+                name: "sun_angle".to_string(),
+                min: 0.0,
+                max: 2.0 * PI,
+                value: self.sun_angle,
+            },
         ]
     }
 
@@ -166,6 +228,9 @@ impl Effect for Terrain {
         match name {
             "speed" => self.speed = value,
             "roughness" => self.roughness = value,
+            "water_level" => self.water_level = value,
+            "snow_line" => self.snow_line = value,
+            "sun_angle" => self.sun_angle = value,
             _ => {}
         }
     }
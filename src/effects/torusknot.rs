@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 use std::f64::consts::TAU;
 
@@ -8,6 +9,8 @@ pub struct TorusKnot {
     height: u32,
     rot_speed: f64,
     glow: f64,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
 }
 
 impl TorusKnot {
@@ -17,6 +20,7 @@ impl TorusKnot {
             height: 0,
             rot_speed: 1.0,
             glow: 1.0,
+            look: (0.0, 0.0),
         }
     }
 }
@@ -26,6 +30,10 @@ impl Effect for TorusKnot {
         "TorusKnot"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
@@ -55,8 +63,8 @@ impl Effect for TorusKnot {
         let big_r = 1.0;
         let small_r = 0.45;
 
-        let rot_y = t * 0.35;
-        let rot_x = t * 0.22;
+        let rot_y = t * 0.35 + self.look.0;
+        let rot_x = t * 0.22 + self.look.1;
         let rot_z = t * 0.13;
         let (cos_ry, sin_ry) = (rot_y.cos(), rot_y.sin());
         let (cos_rx, sin_rx) = (rot_x.cos(), rot_x.sin());
@@ -153,6 +161,10 @@ impl Effect for TorusKnot {
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "rot_speed" => self.rot_speed = value,
@@ -161,20 +173,3 @@ impl Effect for TorusKnot {
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
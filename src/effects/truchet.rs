@@ -1,10 +1,17 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use crate::rng;
 
 pub struct Truchet {
     width: u32,
     height: u32,
     tile_size: f64,
     morph_speed: f64,
+    /// Tile drawing: 0 quarter-circle arcs, 1 diagonal lines, 2 filled
+    /// triangles
+    style: f64,
+    /// How often each tile flips on its own, in flips per second
+    flip_rate: f64,
 }
 
 impl Truchet {
@@ -14,6 +21,8 @@ impl Truchet {
             height: 0,
             tile_size: 20.0,
             morph_speed: 0.5,
+            style: 0.0,
+            flip_rate: 0.0,
         }
     }
 }
@@ -39,6 +48,7 @@ impl Effect for Truchet {
         let hf = h as f64;
         let tile = self.tile_size;
         let line_thickness = tile * 0.15; // ~3px at default tile size
+        let style = self.style.round() as u32;
 
         for y in 0..h {
             for x in 0..w {
@@ -64,11 +74,28 @@ impl Effect for Truchet {
                 // Two orientations:
                 // Type A: arcs from top-left and bottom-right corners
                 // Type B: arcs from top-right and bottom-left corners
-                let is_type_a = noise_val > 0.0;
+                let mut is_type_a = noise_val > 0.0;
+                if self.flip_rate > 0.0 {
+                    // Each tile flips on its own beat, offset by a random phase
+                    let phase = rng::hash_u(rng::hash_cell(tx as u32, ty as u32));
+                    let flips = (t * self.flip_rate + phase).floor() as i64;
+                    if flips & 1 == 1 {
+                        is_type_a = !is_type_a;
+                    }
+                }
 
-                // Compute distance to the nearest arc
+                // Compute distance to the nearest arc, line or triangle
                 let half = tile / 2.0;
-                let dist = if is_type_a {
+                let dist = if style == 1 {
+                    // One diagonal: top-left to bottom-right for type A
+                    let along = if is_type_a { lx - ly } else { lx + ly - tile };
+                    along.abs() * std::f64::consts::FRAC_1_SQRT_2
+                } else if style == 2 {
+                    // Half the tile filled, split along the diagonal; zero
+                    // inside so the fill is solid and only the edge fades
+                    let outside = if is_type_a { ly - lx } else { lx + ly - tile };
+                    (outside * std::f64::consts::FRAC_1_SQRT_2).max(0.0)
+                } else if is_type_a {
                     // Arcs centered at (0,0) and (tile,tile)
                     let d1 = ((lx * lx + ly * ly).sqrt() - half).abs();
                     let d2 = (((lx - tile) * (lx - tile) + (ly - tile) * (ly - tile)).sqrt() - half).abs();
@@ -83,7 +110,7 @@ impl Effect for Truchet {
                 let idx = (y * w + x) as usize;
 
                 if dist < line_thickness {
-                    // On the arc — color with smooth rainbow gradient
+                    // On the tile's shape — color with smooth rainbow gradient
                     let intensity = 1.0 - (dist / line_thickness);
                     let intensity = intensity * intensity; // sharper edges
 
@@ -115,6 +142,18 @@ impl Effect for Truchet {
                 max: 2.0,
                 value: self.morph_speed,
             },
+            ParamDesc {
+                name: "style".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.style,
+            },
+            ParamDesc {
+                name: "flip_rate".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.flip_rate,
+            },
         ]
     }
 
@@ -122,6 +161,8 @@ impl Effect for Truchet {
         match name {
             "tile_size" => self.tile_size = value,
             "morph_speed" => self.morph_speed = value,
+            "style" => self.style = value,
+            "flip_rate" => self.flip_rate = value,
             _ => {}
         }
     }
@@ -134,20 +175,3 @@ fn smooth_noise(x: f64, y: f64, z: f64) -> f64 {
         + (x * 0.5 + y * 3.1 - z * 0.7).cos() * 0.25;
     v
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
@@ -1,4 +1,6 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::texture::Texture;
+use std::f64::consts::{PI, TAU};
 
 pub struct Tunnel {
     width: u32,
@@ -7,6 +9,8 @@ pub struct Tunnel {
     distance_lut: Vec<f64>,
     speed: f64,
     texture_scale: f64,
+    /// Image wrapped around the walls instead of the procedural pattern
+    texture: Option<Texture>,
 }
 
 impl Tunnel {
@@ -18,8 +22,14 @@ impl Tunnel {
             distance_lut: Vec::new(),
             speed: 1.0,
             texture_scale: 1.0,
+            texture: None,
         }
     }
+
+    pub fn with_texture(mut self, texture: Texture) -> Self {
+        self.texture = Some(texture);
+        self
+    }
 }
 
 impl Effect for Tunnel {
@@ -62,6 +72,8 @@ impl Effect for Tunnel {
         let cy = h as f64 / 2.0;
         let max_dist = (cx * cx + cy * cy).sqrt();
         let tex = self.texture_scale;
+        let around = TAU * (tex * 8.0 / TAU).round().max(1.0);
+        let stripes = PI * (tex * 16.0 / PI).round().max(1.0);
 
         for y in 0..h {
             for x in 0..w {
@@ -69,21 +81,38 @@ impl Effect for Tunnel {
                 let angle = self.angle_lut[idx];
                 let distance = self.distance_lut[idx];
 
-                // Animate: rotation + forward motion
+                // Animate: rotation + forward motion; a negative speed runs
+                // backwards, which the wrapping texture lookups handle as is
                 let u = angle + t * self.speed * 0.1;
                 let v = distance - t * self.speed * 2.0;
 
-                // Texture: dual sine pattern
-                let tex_val = (u * 8.0 * tex).sin() * (v * 8.0 * tex).sin();
-                let stripe = ((u * 16.0 * tex).sin() * 0.3).abs();
-                let pattern = (tex_val * 0.5 + 0.5) * 0.7 + stripe * 0.3;
-
                 // Shade by distance from center (edge = brighter)
                 let dx = x as f64 - cx;
                 let dy = y as f64 - cy;
                 let edge_dist = (dx * dx + dy * dy).sqrt() / max_dist;
                 let shade = (edge_dist * 1.5).clamp(0.1, 1.0);
 
+                if let Some(image) = &self.texture {
+                    // Whole repeats around the circumference keep the seam invisible;
+                    // the image repeats once per unit of depth
+                    let repeats = tex.round().max(1.0);
+                    let tx = (u * repeats * image.width as f64).floor() as i64;
+                    let ty = (v * tex * image.height as f64).floor() as i64;
+                    let (r, g, b) = image.texel(tx, ty);
+                    pixels[idx] = (
+                        (r as f64 * shade) as u8,
+                        (g as f64 * shade) as u8,
+                        (b as f64 * shade) as u8,
+                    );
+                    continue;
+                }
+
+                // Texture: dual sine pattern, with whole periods around the
+                // circumference so there's no seam where the angle wraps
+                let tex_val = (u * around).sin() * (v * 8.0 * tex).sin();
+                let stripe = ((u * stripes).sin() * 0.3).abs();
+                let pattern = (tex_val * 0.5 + 0.5) * 0.7 + stripe * 0.3;
+
                 // Cosine palette with slow hue cycling
                 let hue_offset = t * 0.15;
                 let r = (0.5
@@ -117,7 +146,7 @@ impl Effect for Tunnel {
         vec![
             ParamDesc {
                 name: "speed".to_string(),
-                min: 0.2,
+                min: -5.0,
                 max: 5.0,
                 value: self.speed,
             },
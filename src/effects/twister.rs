@@ -6,6 +6,9 @@ pub struct Twister {
     height: u32,
     twist_speed: f64,
     segments: f64,
+    /// Dark bands per screen height scrolling along the bar; 0 leaves the
+    /// faces plain
+    stripes: f64,
 }
 
 impl Twister {
@@ -15,6 +18,7 @@ impl Twister {
             height: 0,
             twist_speed: 1.5,
             segments: 8.0,
+            stripes: 0.0,
         }
     }
 }
@@ -73,6 +77,17 @@ impl Effect for Twister {
 
             let row = (y * w) as usize;
 
+            // Bands scroll with the twist, so a negative speed reverses both
+            let band = if self.stripes > 0.0 {
+                if (fy * self.stripes - t * 0.5).rem_euclid(1.0) < 0.5 {
+                    0.7
+                } else {
+                    1.0
+                }
+            } else {
+                1.0
+            };
+
             // Draw each face between consecutive edges.
             // A face is visible (front-facing) when edge[i+1] > edge[i].
             for i in 0..4 {
@@ -86,7 +101,7 @@ impl Effect for Twister {
 
                 // Brightness from projected width: wider = facing camera more = brighter
                 let brightness = (face_width / (2.0 * radius)).clamp(0.0, 1.0);
-                let shade = 0.15 + 0.85 * brightness;
+                let shade = (0.15 + 0.85 * brightness) * band;
 
                 let color = FACE_COLORS[i];
                 let x0 = x_left.max(0.0) as i32;
@@ -116,7 +131,7 @@ impl Effect for Twister {
         vec![
             ParamDesc {
                 name: "twist_speed".to_string(),
-                min: 0.3,
+                min: -4.0,
                 max: 4.0,
                 value: self.twist_speed,
             },
@@ -126,6 +141,12 @@ impl Effect for Twister {
                 max: 20.0,
                 value: self.segments,
             },
+            ParamDesc {
+                name: "stripes".to_string(),
+                min: 0.0,
+                max: 16.0,
+                value: self.stripes,
+            },
         ]
     }
 
@@ -133,6 +154,7 @@ impl Effect for Twister {
         match name {
             "twist_speed" => self.twist_speed = value,
             "segments" => self.segments = value,
+            "stripes" => self.stripes = value,
             _ => {}
         }
     }
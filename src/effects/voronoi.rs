@@ -1,12 +1,76 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f64::consts::TAU;
 
 const NUM_SEEDS: usize = 24;
+/// Cap on seeds added by `trigger`, which keeps the per-pixel search cheap
+const MAX_SEEDS: usize = 64;
 
+/// Half-width in pixels of the outlines drawn by the `borders` toggle.
+const BORDER_WIDTH: f64 = 0.75;
+
+#[derive(Clone, Copy)]
+enum Metric {
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    fn from_param(value: f64) -> Self {
+        match value.round() as i64 {
+            1 => Metric::Manhattan,
+            2 => Metric::Chebyshev,
+            _ => Metric::Euclidean,
+        }
+    }
+
+    fn distance(self, dx: f64, dy: f64) -> f64 {
+        match self {
+            Metric::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Metric::Manhattan => dx.abs() + dy.abs(),
+            Metric::Chebyshev => dx.abs().max(dy.abs()),
+        }
+    }
+}
+
+/// A cell center drifting across the screen, in fractions of its size.
 struct Seed {
-    freq_x: f64,
-    freq_y: f64,
-    phase_x: f64,
-    phase_y: f64,
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    hue: f64,
+}
+
+impl Seed {
+    fn random(rng: &mut StdRng, hue: f64) -> Self {
+        let angle = rng.gen_range(0.0..TAU);
+        let speed = rng.gen_range(0.04..0.16);
+        Self {
+            x: rng.gen_range(0.05..0.95),
+            y: rng.gen_range(0.05..0.95),
+            vx: angle.cos() * speed,
+            vy: angle.sin() * speed,
+            hue,
+        }
+    }
+
+    /// Move by `step` seconds of velocity, bouncing off the screen edges.
+    fn advance(&mut self, step: f64) {
+        self.x += self.vx * step;
+        self.y += self.vy * step;
+        if self.x < 0.0 || self.x > 1.0 {
+            self.vx = -self.vx;
+            self.x = self.x.clamp(0.0, 1.0);
+        }
+        if self.y < 0.0 || self.y > 1.0 {
+            self.vy = -self.vy;
+            self.y = self.y.clamp(0.0, 1.0);
+        }
+    }
 }
 
 pub struct Voronoi {
@@ -14,7 +78,13 @@ pub struct Voronoi {
     height: u32,
     speed: f64,
     edge_glow: f64,
+    /// 0 Euclidean (round cells), 1 Manhattan (diamonds), 2 Chebyshev (squares)
+    metric: f64,
+    /// Above 0.5, outline every cell with a crisp line
+    borders: f64,
     seeds: Vec<Seed>,
+    /// Places the seeds added by `trigger`
+    rng: StdRng,
 }
 
 impl Voronoi {
@@ -24,7 +94,10 @@ impl Voronoi {
             height: 0,
             speed: 1.0,
             edge_glow: 1.0,
+            metric: 0.0,
+            borders: 0.0,
             seeds: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
         }
     }
 }
@@ -38,20 +111,30 @@ impl Effect for Voronoi {
         self.width = width;
         self.height = height;
 
-        // Deterministic seeds with varied Lissajous frequencies
+        // Deterministic seeds spread around the screen with varied headings
         self.seeds.clear();
         for i in 0..NUM_SEEDS {
             let fi = i as f64;
+            let heading = fi * 2.4;
+            let speed = 0.05 + (fi * 0.37) % 0.1;
             self.seeds.push(Seed {
-                freq_x: 0.3 + (fi * 0.17) % 0.8,
-                freq_y: 0.4 + (fi * 0.23) % 0.7,
-                phase_x: fi * 1.3,
-                phase_y: fi * 1.7,
+                x: 0.5 + 0.45 * (fi * 1.3).sin(),
+                y: 0.5 + 0.45 * (fi * 1.7).cos(),
+                vx: heading.cos() * speed,
+                vy: heading.sin() * speed,
+                hue: fi / NUM_SEEDS as f64,
             });
         }
     }
 
-    fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
+    fn randomize_init(&mut self, rng: &mut StdRng) {
+        for (i, seed) in self.seeds.iter_mut().enumerate() {
+            *seed = Seed::random(rng, i as f64 / NUM_SEEDS as f64);
+        }
+        self.rng = StdRng::seed_from_u64(rng.gen());
+    }
+
+    fn update(&mut self, t: f64, dt: f64, pixels: &mut [(u8, u8, u8)]) {
         let w = self.width;
         let h = self.height;
         if w == 0 || h == 0 {
@@ -61,17 +144,13 @@ impl Effect for Voronoi {
         let wf = w as f64;
         let hf = h as f64;
         let t = t * self.speed;
+        let metric = Metric::from_param(self.metric);
+        let borders = self.borders >= 0.5;
 
-        // Compute seed positions in pixel space
-        let positions: Vec<(f64, f64)> = self
-            .seeds
-            .iter()
-            .map(|s| {
-                let x = (0.5 + 0.45 * (t * s.freq_x + s.phase_x).sin()) * wf;
-                let y = (0.5 + 0.45 * (t * s.freq_y + s.phase_y).cos()) * hf;
-                (x, y)
-            })
-            .collect();
+        for seed in &mut self.seeds {
+            seed.advance(dt * self.speed);
+        }
+        let positions: Vec<(f64, f64)> = self.seeds.iter().map(|s| (s.x * wf, s.y * hf)).collect();
 
         for y in 0..h {
             let py = y as f64;
@@ -86,9 +165,7 @@ impl Effect for Voronoi {
                 let mut closest = 0usize;
 
                 for (i, &(sx, sy)) in positions.iter().enumerate() {
-                    let dx = px - sx;
-                    let dy = py - sy;
-                    let d = dx * dx + dy * dy;
+                    let d = metric.distance(px - sx, py - sy);
 
                     if d < d1 {
                         d2 = d1;
@@ -99,14 +176,20 @@ impl Effect for Voronoi {
                     }
                 }
 
-                let d1 = d1.sqrt();
-                let d2 = d2.sqrt();
+                let idx = row + x as usize;
+
+                // Where the two nearest seeds are about equally far, this
+                // pixel sits on a cell boundary
+                if borders && d2 - d1 < 2.0 * BORDER_WIDTH {
+                    pixels[idx] = (8, 8, 16);
+                    continue;
+                }
 
                 // Edge detection: how close to the boundary between cells
                 let edge = (d2 - d1) / (d2 + d1 + 0.001);
 
                 // Cell color from seed index + time
-                let hue = (closest as f64 / NUM_SEEDS as f64 + t * 0.03) % 1.0;
+                let hue = (self.seeds[closest].hue + t * 0.03) % 1.0;
 
                 // Interior brightness: slight gradient from center
                 let interior = (1.0 - d1 * 0.003).clamp(0.5, 1.0);
@@ -117,7 +200,6 @@ impl Effect for Voronoi {
 
                 let (cr, cg, cb) = hsv_to_rgb(hue, 0.75, interior * 0.7);
 
-                let idx = row + x as usize;
                 pixels[idx] = (
                     (cr as f64 + edge_bright * 180.0).clamp(0.0, 255.0) as u8,
                     (cg as f64 + edge_bright * 220.0).clamp(0.0, 255.0) as u8,
@@ -141,31 +223,37 @@ impl Effect for Voronoi {
                 max: 3.0,
                 value: self.edge_glow,
             },
+            ParamDesc {
+                name: "metric".to_string(),
+                min: 0.0,
+                max: 2.0,
+                value: self.metric,
+            },
+            ParamDesc {
+                name: "borders".to_string(),
+                min: 0.0,
+                max: 1.0,
+                value: self.borders,
+            },
         ]
     }
 
+    /// Drop in a new seed, splitting whichever cell it lands in.
+    fn trigger(&mut self) {
+        if self.seeds.len() < MAX_SEEDS {
+            let hue = self.rng.gen_range(0.0..1.0);
+            let seed = Seed::random(&mut self.rng, hue);
+            self.seeds.push(seed);
+        }
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "speed" => self.speed = value,
             "edge_glow" => self.edge_glow = value,
+            "metric" => self.metric = value,
+            "borders" => self.borders = value,
             _ => {}
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
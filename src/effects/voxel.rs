@@ -1,7 +1,19 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::noise::{ValueNoise, TERRAIN_SEED};
+use crate::sunlight;
+use rand::rngs::StdRng;
 use std::f64::consts::PI;
 
 const MAP_SIZE: usize = 1024;
+/// World height of the tallest peak, in map cells
+const HEIGHT_SCALE: f64 = 120.0;
+/// Heightmap values below this are water
+const WATER_LEVEL: f64 = 0.3;
+/// Slope exaggeration for lighting, so gentle hills still show relief
+const RELIEF: f64 = 4.0;
+/// Noise cells across the map; the noise repeats with the map so it wraps
+const HILLS_ACROSS: u32 = 6;
+const OCTAVES: u32 = 5;
 
 pub struct VoxelLandscape {
     width: u32,
@@ -10,6 +22,10 @@ pub struct VoxelLandscape {
     cam_height: f64,
     heightmap: Vec<f64>,
     colormap: Vec<(u8, u8, u8)>,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
+    /// Sun direction around the horizon, in radians
+    sun_angle: f64,
 }
 
 impl VoxelLandscape {
@@ -21,6 +37,8 @@ impl VoxelLandscape {
             cam_height: 1.5,
             heightmap: Vec::new(),
             colormap: Vec::new(),
+            look: (0.0, 0.0),
+            sun_angle: 2.4,
         }
     }
 
@@ -29,30 +47,26 @@ impl VoxelLandscape {
         self.heightmap = vec![0.0; size * size];
         self.colormap = vec![(0, 0, 0); size * size];
 
-        // Multi-octave sine-based heightmap
+        // Fractal noise heightmap from Terrain's seed, tiled so the map wraps;
+        // the tiling lattice makes these hills its own, not Terrain's
+        let noise = ValueNoise::tiling(TERRAIN_SEED, HILLS_ACROSS);
+        let cell = HILLS_ACROSS as f64 / size as f64;
         for y in 0..size {
             for x in 0..size {
-                let fx = x as f64 / size as f64;
-                let fy = y as f64 / size as f64;
-
-                let mut h = 0.0;
-                h += (fx * 3.0 * PI).sin() * (fy * 2.0 * PI).cos() * 0.4;
-                h += (fx * 7.0 * PI + 1.0).sin() * (fy * 5.0 * PI + 2.0).cos() * 0.2;
-                h += (fx * 13.0 * PI + 3.0).sin() * (fy * 11.0 * PI + 5.0).cos() * 0.1;
-                h += (fx * 23.0 * PI).cos() * (fy * 19.0 * PI).sin() * 0.05;
-                h = h * 0.5 + 0.5; // normalize to 0–1
+                let n = noise.fbm2(x as f64 * cell, y as f64 * cell, OCTAVES);
+                let h = (n * 1.2 + 0.5).clamp(0.0, 1.0); // center on 0.5
 
                 let idx = y * size + x;
                 self.heightmap[idx] = h;
 
                 // Altitude coloring
-                self.colormap[idx] = if h < 0.3 {
+                self.colormap[idx] = if h < WATER_LEVEL {
                     // Water
-                    let d = h / 0.3;
+                    let d = h / WATER_LEVEL;
                     ((20.0 * d) as u8, (40.0 + 40.0 * d) as u8, (120.0 + 80.0 * d) as u8)
                 } else if h < 0.5 {
                     // Grass
-                    let d = (h - 0.3) / 0.2;
+                    let d = (h - WATER_LEVEL) / 0.2;
                     ((40.0 + 30.0 * d) as u8, (120.0 + 40.0 * d) as u8, (30.0 + 20.0 * d) as u8)
                 } else if h < 0.75 {
                     // Rock
@@ -68,6 +82,21 @@ impl VoxelLandscape {
             }
         }
     }
+
+    /// Heightmap sampled with bilinear filtering and wrap-around.
+    fn height_at(&self, x: f64, y: f64) -> f64 {
+        let size = MAP_SIZE as isize;
+        let (fx, fy) = (x.floor(), y.floor());
+        let (tx, ty) = (x - fx, y - fy);
+        let x0 = (fx as isize).rem_euclid(size) as usize;
+        let y0 = (fy as isize).rem_euclid(size) as usize;
+        let x1 = (x0 + 1) % MAP_SIZE;
+        let y1 = (y0 + 1) % MAP_SIZE;
+        let hm = &self.heightmap;
+        let top = hm[y0 * MAP_SIZE + x0] * (1.0 - tx) + hm[y0 * MAP_SIZE + x1] * tx;
+        let bottom = hm[y1 * MAP_SIZE + x0] * (1.0 - tx) + hm[y1 * MAP_SIZE + x1] * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
 }
 
 impl Effect for VoxelLandscape {
@@ -75,10 +104,22 @@ impl Effect for VoxelLandscape {
         "VoxelLandscape"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
-        self.generate_terrain();
+        // The terrain doesn't depend on the screen, so generate it once
+        if self.heightmap.is_empty() {
+            self.generate_terrain();
+        }
+    }
+
+    fn on_resize(&mut self, width: u32, height: u32, _rng: &mut StdRng) {
+        self.width = width;
+        self.height = height;
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -100,7 +141,9 @@ impl Effect for VoxelLandscape {
         let cam_x = (t_scaled * 60.0) % (MAP_SIZE as f64);
         let cam_y = (t_scaled * 30.0 + MAP_SIZE as f64 * 0.3) % (MAP_SIZE as f64);
         let cam_z = self.cam_height * 120.0 + (t_scaled * 0.5).sin() * 15.0;
-        let cam_angle = t_scaled * 0.1;
+        let cam_angle = t_scaled * 0.1 + self.look.0;
+        // Pitch moves the horizon, as in the classic voxel engines
+        let horizon = h as f64 * (0.5 + self.look.1 * 0.5);
 
         let cos_a = cam_angle.cos();
         let sin_a = cam_angle.sin();
@@ -114,6 +157,9 @@ impl Effect for VoxelLandscape {
             let dir_y = rx * sin_a + cos_a;
 
             let mut max_screen_y = h; // front-to-back occlusion
+            // Color of the previous sample, so each newly revealed span
+            // blends into the one in front of it instead of banding
+            let mut prev_color: Option<(f64, f64, f64)> = None;
 
             // March forward with increasing step size
             let mut dist = 1.0;
@@ -123,39 +169,56 @@ impl Effect for VoxelLandscape {
                 let world_x = cam_x + dir_x * dist;
                 let world_y = cam_y + dir_y * dist;
 
-                // Sample heightmap with wrapping
+                // Bilinear height so the surface doesn't jump between map cells
+                let sample = self.height_at(world_x, world_y);
+                let terrain_h = sample * HEIGHT_SCALE;
                 let mx = ((world_x as isize).rem_euclid(MAP_SIZE as isize)) as usize;
                 let my = ((world_y as isize).rem_euclid(MAP_SIZE as isize)) as usize;
                 let map_idx = my * MAP_SIZE + mx;
 
-                let terrain_h = self.heightmap[map_idx] * 120.0;
-
                 // Project to screen: higher terrain or closer = higher on screen
                 let height_on_screen = (cam_z - terrain_h) / dist * (h as f64) * 0.5;
-                let screen_y = (h as f64 * 0.5 + height_on_screen) as usize;
+                let screen_y = (horizon + height_on_screen).max(0.0) as usize;
+
+                // Sun shading from the slope one map cell over; water stays flat
+                let light = if sample < WATER_LEVEL {
+                    1.0
+                } else {
+                    let slope_x = (self.height_at(world_x + 1.0, world_y) - sample) * HEIGHT_SCALE;
+                    let slope_y = (self.height_at(world_x, world_y + 1.0) - sample) * HEIGHT_SCALE;
+                    sunlight::shade(slope_x * RELIEF, slope_y * RELIEF, self.sun_angle)
+                };
+
+                // Distance fog
+                let base_color = self.colormap[map_idx];
+                let fog = (dist / max_dist).clamp(0.0, 1.0);
+                let lit = 1.0 - fog;
+                let color = (
+                    (base_color.0 as f64 * light).min(255.0) * lit + sky.0 as f64 * fog,
+                    (base_color.1 as f64 * light).min(255.0) * lit + sky.1 as f64 * fog,
+                    (base_color.2 as f64 * light).min(255.0) * lit + sky.2 as f64 * fog,
+                );
 
                 if screen_y < max_screen_y {
-                    // Get terrain color
-                    let base_color = self.colormap[map_idx];
-
-                    // Distance fog
-                    let fog = (dist / max_dist).clamp(0.0, 1.0);
-                    let r = (base_color.0 as f64 * (1.0 - fog) + sky.0 as f64 * fog) as u8;
-                    let g = (base_color.1 as f64 * (1.0 - fog) + sky.1 as f64 * fog) as u8;
-                    let b = (base_color.2 as f64 * (1.0 - fog) + sky.2 as f64 * fog) as u8;
-
-                    // Draw vertical column from screen_y to max_screen_y
-                    for sy in screen_y..max_screen_y {
-                        if sy < h {
-                            let idx = sy * w + sx;
-                            if idx < pixels.len() {
-                                pixels[idx] = (r, g, b);
-                            }
-                        }
+                    // Fill the whole newly visible span, shading from the
+                    // previous sample at its bottom to this one at its top
+                    let bottom = prev_color.unwrap_or(color);
+                    let span = (max_screen_y - screen_y) as f64;
+                    for sy in screen_y..max_screen_y.min(h) {
+                        let k = (sy - screen_y) as f64 / span;
+                        pixels[sy * w + sx] = (
+                            (color.0 + (bottom.0 - color.0) * k) as u8,
+                            (color.1 + (bottom.1 - color.1) * k) as u8,
+                            (color.2 + (bottom.2 - color.2) * k) as u8,
+                        );
                     }
 
                     max_screen_y = screen_y;
+                    if screen_y == 0 {
+                        break;
+                    }
                 }
+                prev_color = Some(color);
 
                 // Increasing step size for performance
                 dist += 0.5 + dist * 0.01;
@@ -177,13 +240,24 @@ impl Effect for VoxelLandscape {
                 max: 3.0,
                 value: self.cam_height,
             },
+            ParamDesc {
+                name: "sun_angle".to_string(),
+                min: 0.0,
+                max: 2.0 * PI,
+                value: self.sun_angle,
+            },
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "speed" => self.speed = value,
             "cam_height" => self.cam_height = value,
+            "sun_angle" => self.sun_angle = value,
             _ => {}
         }
     }
@@ -2,10 +2,16 @@ use crate::effect::{Effect, ParamDesc};
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+/// Strength of a drop the viewer makes, as big as the largest raindrop
+const SPLASH: f64 = 500.0;
+
 pub struct Water {
     width: u32,
     height: u32,
+    /// Fraction of the wave height kept each step; values near 1 ring on
     damping: f64,
+    /// How far ripples travel per step, relative to the fastest stable speed
+    wave_speed: f64,
     drop_freq: f64,
     buf_current: Vec<f64>,
     buf_previous: Vec<f64>,
@@ -19,6 +25,7 @@ impl Water {
             width: 0,
             height: 0,
             damping: 0.97,
+            wave_speed: 1.0,
             drop_freq: 3.0,
             buf_current: Vec::new(),
             buf_previous: Vec::new(),
@@ -26,6 +33,19 @@ impl Water {
             rng: StdRng::seed_from_u64(0),
         }
     }
+
+    /// Drop a disturbance of `strength` into the height field at pixel
+    /// (x, y); it spreads out as concentric ripples. Points off the surface
+    /// land on its nearest inner cell, since the edges stay still.
+    pub fn perturb(&mut self, x: f64, y: f64, strength: f64) {
+        let (w, h) = (self.width as usize, self.height as usize);
+        if w < 3 || h < 3 {
+            return;
+        }
+        let x = (x.round().max(0.0) as usize).clamp(1, w - 2);
+        let y = (y.round().max(0.0) as usize).clamp(1, h - 2);
+        self.buf_current[y * w + x] = strength;
+    }
 }
 
 impl Effect for Water {
@@ -60,22 +80,25 @@ impl Effect for Water {
             let dx = self.rng.gen_range(2..w - 2);
             let dy = self.rng.gen_range(2..h - 2);
             let strength = self.rng.gen_range(200.0..500.0);
-            self.buf_current[dy * w + dx] = strength;
+            self.perturb(dx as f64, dy as f64, strength);
         }
 
-        // Wave equation: new = avg_4_neighbors(current)*2 - previous, then * damping
-        // Skip edge pixels
+        // Wave equation: new = 2*current - previous + c^2 * laplacian(current),
+        // then damped. Past c^2 = 1/2 the grid blows up, so speed is capped
+        // there; at full speed it reduces to the classic neighbor average.
+        // Edge pixels stay still, so ripples reflect off them
+        let c2 = 0.5 * self.wave_speed.clamp(0.0, 1.0).powi(2);
         let mut new_buf = vec![0.0; w * h];
         for y in 1..h - 1 {
             for x in 1..w - 1 {
                 let idx = y * w + x;
-                let avg = (self.buf_current[idx - 1]
+                let neighbors = self.buf_current[idx - 1]
                     + self.buf_current[idx + 1]
                     + self.buf_current[idx - w]
-                    + self.buf_current[idx + w])
-                    / 2.0
+                    + self.buf_current[idx + w];
+                let wave = self.buf_current[idx] * (2.0 - 4.0 * c2) + neighbors * c2
                     - self.buf_previous[idx];
-                new_buf[idx] = avg * self.damping;
+                new_buf[idx] = wave * self.damping;
             }
         }
 
@@ -111,6 +134,19 @@ impl Effect for Water {
         }
     }
 
+    fn trigger(&mut self) {
+        if self.width < 5 || self.height < 5 {
+            return;
+        }
+        let x = self.rng.gen_range(2..self.width - 2);
+        let y = self.rng.gen_range(2..self.height - 2);
+        self.perturb(x as f64, y as f64, SPLASH);
+    }
+
+    fn poke(&mut self, x: f64, y: f64) {
+        self.perturb(x, y, SPLASH);
+    }
+
     fn params(&self) -> Vec<ParamDesc> {
         vec![
             ParamDesc {
@@ -119,6 +155,12 @@ impl Effect for Water {
                 max: 0.999,
                 value: self.damping,
             },
+            ParamDesc {
+                name: "wave_speed".to_string(),
+                min: 0.2,
+                max: 1.0,
+                value: self.wave_speed,
+            },
             ParamDesc {
                 name: "drop_freq".to_string(),
                 min: 0.5,
@@ -131,6 +173,7 @@ impl Effect for Water {
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "damping" => self.damping = value,
+            "wave_speed" => self.wave_speed = value,
             "drop_freq" => self.drop_freq = value,
             _ => {}
         }
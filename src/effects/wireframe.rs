@@ -1,3 +1,4 @@
+use crate::color::hsv_to_rgb;
 use crate::effect::{Effect, ParamDesc};
 
 pub struct Wireframe {
@@ -5,6 +6,8 @@ pub struct Wireframe {
     height: u32,
     rot_speed: f64,
     zoom: f64,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
 }
 
 impl Wireframe {
@@ -14,6 +17,7 @@ impl Wireframe {
             height: 0,
             rot_speed: 1.0,
             zoom: 1.0,
+            look: (0.0, 0.0),
         }
     }
 }
@@ -60,8 +64,8 @@ impl Effect for Wireframe {
         }
 
         let t_scaled = t * self.rot_speed;
-        let angle_y = t_scaled * 0.7;
-        let angle_x = t_scaled * 0.5;
+        let angle_y = t_scaled * 0.7 + self.look.0;
+        let angle_x = t_scaled * 0.5 + self.look.1;
 
         let cos_y = angle_y.cos();
         let sin_y = angle_y.sin();
@@ -134,6 +138,10 @@ impl Effect for Wireframe {
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "rot_speed" => self.rot_speed = value,
@@ -191,20 +199,3 @@ fn draw_line(
         }
     }
 }
-
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let i = (h * 6.0).floor() as i32;
-    let f = h * 6.0 - i as f64;
-    let p = v * (1.0 - s);
-    let q = v * (1.0 - f * s);
-    let t = v * (1.0 - (1.0 - f) * s);
-    let (r, g, b) = match i % 6 {
-        0 => (v, t, p),
-        1 => (q, v, p),
-        2 => (p, v, t),
-        3 => (p, q, v),
-        4 => (t, p, v),
-        _ => (v, p, q),
-    };
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
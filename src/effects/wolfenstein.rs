@@ -1,7 +1,11 @@
 use crate::effect::{Effect, ParamDesc};
+use crate::texture::{Texture, TRANSPARENT};
 use std::f64::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
 
-/// 16x16 map: 1 = wall, 0 = empty
+/// Built-in 16x16 map: 1 = wall, 0 = empty
 const MAP_SIZE: usize = 16;
 #[rustfmt::skip]
 const MAP: [u8; MAP_SIZE * MAP_SIZE] = [
@@ -23,11 +27,121 @@ const MAP: [u8; MAP_SIZE * MAP_SIZE] = [
     1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,
 ];
 
+/// Rectangular grid of cells: 1 = wall, 0 = empty.
+pub struct Map {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<u8>,
+    /// Sprites standing on floor cells, at cell centers
+    sprites: Vec<(f64, f64, TextureId)>,
+}
+
+impl Map {
+    fn builtin() -> Self {
+        Self {
+            width: MAP_SIZE,
+            height: MAP_SIZE,
+            cells: MAP.to_vec(),
+            sprites: vec![
+                (4.5, 4.5, TextureId::Pillar),
+                (12.5, 12.5, TextureId::Pillar),
+                (12.5, 3.5, TextureId::Pillar),
+                (8.5, 2.5, TextureId::Orb),
+                (2.5, 8.5, TextureId::Orb),
+                (13.5, 8.5, TextureId::Ghost),
+                (8.5, 13.5, TextureId::Ghost),
+            ],
+        }
+    }
+
+    /// Load a text map where `#` is a wall and `.` is floor; `p`, `o` and `g`
+    /// are floor with a pillar, orb or ghost standing on it.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let rows: Vec<&str> = text
+            .lines()
+            .map(|l| l.trim_end())
+            .filter(|l| !l.is_empty())
+            .collect();
+        let width = rows.first().map(|r| r.chars().count()).unwrap_or(0);
+        if width == 0 {
+            return Err("map is empty".to_string());
+        }
+
+        let mut cells = Vec::with_capacity(width * rows.len());
+        let mut sprites = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!(
+                    "line {} has {} cells, expected {}",
+                    y + 1,
+                    row.chars().count(),
+                    width
+                ));
+            }
+            for (x, ch) in row.chars().enumerate() {
+                let sprite = match ch {
+                    '#' | '.' => None,
+                    'p' => Some(TextureId::Pillar),
+                    'o' => Some(TextureId::Orb),
+                    'g' => Some(TextureId::Ghost),
+                    _ => return Err(format!("line {}: unexpected character {:?}", y + 1, ch)),
+                };
+                if let Some(id) = sprite {
+                    sprites.push((x as f64 + 0.5, y as f64 + 0.5, id));
+                }
+                cells.push(if ch == '#' { 1 } else { 0 });
+            }
+        }
+
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells,
+            sprites,
+        })
+    }
+
+    /// Cell at `(mx, my)`; anything outside the grid counts as wall.
+    fn at(&self, mx: i32, my: i32) -> u8 {
+        if mx < 0 || mx >= self.width as i32 || my < 0 || my >= self.height as i32 {
+            return 1;
+        }
+        self.cells[my as usize * self.width + mx as usize]
+    }
+}
+
+/// Sprite kinds, indexing into the per-kind animation frames.
+#[derive(Clone, Copy)]
+pub enum TextureId {
+    Pillar,
+    Orb,
+    Ghost,
+}
+
+const SPRITE_SIZE: u32 = 32;
+
 pub struct Wolfenstein {
     width: u32,
     height: u32,
     move_speed: f64,
     fov: f64,
+    map: Map,
+    texture: Texture,
+    floor_texture: Texture,
+    ceiling_texture: Texture,
+    // Per-column ray directions and half-open wall spans, reused by the floor pass
+    ray_dirs: Vec<(f64, f64)>,
+    wall_spans: Vec<(u32, u32)>,
+    // Per-column wall distance for sprite occlusion
+    depth: Vec<f64>,
+    sprite_frames: Vec<Vec<Texture>>,
+    /// Mouse-look yaw and pitch offsets in radians, from `set_camera`
+    look: (f64, f64),
 }
 
 impl Wolfenstein {
@@ -37,15 +151,171 @@ impl Wolfenstein {
             height: 0,
             move_speed: 1.0,
             fov: 60.0,
+            map: Map::builtin(),
+            texture: Texture::brick(),
+            floor_texture: Texture::tiles((110, 100, 80)),
+            ceiling_texture: Texture::tiles((60, 60, 90)),
+            ray_dirs: Vec::new(),
+            wall_spans: Vec::new(),
+            depth: Vec::new(),
+            sprite_frames: vec![
+                vec![pillar_sprite()],
+                (0..4).map(orb_sprite).collect(),
+                (0..2).map(ghost_sprite).collect(),
+            ],
+            look: (0.0, 0.0),
         }
     }
+
+    pub fn with_texture(mut self, texture: Texture) -> Self {
+        self.texture = texture;
+        self
+    }
+
+    /// Replace the built-in map, along with the sprites placed in it.
+    pub fn with_map(mut self, map: Map) -> Self {
+        self.map = map;
+        self
+    }
+
+    /// Billboard sprites drawn back-to-front, clipped per column against the wall depth.
+    fn draw_sprites(
+        &self,
+        t: f64,
+        cam_x: f64,
+        cam_y: f64,
+        look_angle: f64,
+        half_fov: f64,
+        pixels: &mut [(u8, u8, u8)],
+    ) {
+        let w = self.width;
+        let h = self.height;
+        let wf = w as f64;
+        let hf = h as f64;
+
+        let mut order: Vec<(f64, usize)> = self
+            .map
+            .sprites
+            .iter()
+            .enumerate()
+            .map(|(i, &(sx, sy, _))| ((sx - cam_x).hypot(sy - cam_y), i))
+            .collect();
+        order.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        for (dist, i) in order {
+            if dist < 0.2 {
+                continue;
+            }
+            let (sx, sy, id) = self.map.sprites[i];
+
+            // Angle from the view direction, wrapped to [-PI, PI]
+            let rel = (sy - cam_y).atan2(sx - cam_x) - look_angle;
+            let rel = (rel + PI).rem_euclid(2.0 * PI) - PI;
+            if rel.abs() > half_fov + 0.5 {
+                continue;
+            }
+
+            let screen_x = (rel / half_fov + 1.0) * 0.5 * wf;
+            let size = (hf / dist).min(hf * 4.0);
+            let left = screen_x - size / 2.0;
+            let top = hf / 2.0 - size / 2.0;
+
+            let frames = &self.sprite_frames[id as usize];
+            let tex = &frames[(t * 6.0) as usize % frames.len()];
+            let shade = (1.0 / (1.0 + dist * 0.15)).clamp(0.15, 1.0);
+
+            let x0 = left.max(0.0) as u32;
+            let x1 = (left + size).min(wf) as u32;
+            let y0 = top.max(0.0) as u32;
+            let y1 = (top + size).min(hf) as u32;
+
+            for x in x0..x1 {
+                if self.depth[x as usize] <= dist {
+                    continue;
+                }
+                let tex_x = ((x as f64 - left) / size * tex.width as f64) as i64;
+                for y in y0..y1 {
+                    let tex_y = ((y as f64 - top) / size * tex.height as f64) as i64;
+                    let texel = tex.texel(tex_x, tex_y);
+                    if texel == TRANSPARENT {
+                        continue;
+                    }
+                    pixels[(y * w + x) as usize] = (
+                        (texel.0 as f64 * shade) as u8,
+                        (texel.1 as f64 * shade) as u8,
+                        (texel.2 as f64 * shade) as u8,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Stone column with a lighter capital and base.
+fn pillar_sprite() -> Texture {
+    let mut tex = Texture::transparent(SPRITE_SIZE, SPRITE_SIZE);
+    for y in 0..SPRITE_SIZE {
+        let cap = !(4..SPRITE_SIZE - 4).contains(&y);
+        let half = if cap { 9 } else { 6 };
+        for x in (16 - half)..(16 + half) {
+            // Cylindrical shading across the column
+            let u = (x as f64 - 15.5) / half as f64;
+            let light = (1.0 - u * u).sqrt() * 0.8 + 0.2;
+            let base = if cap { 200.0 } else { 170.0 };
+            tex.pixels[(y * SPRITE_SIZE + x) as usize] = (
+                (base * light) as u8,
+                (base * 0.95 * light) as u8,
+                (base * 0.85 * light) as u8,
+            );
+        }
+    }
+    tex
+}
+
+/// Floating glowing orb; frames pulse the glow radius.
+fn orb_sprite(frame: u32) -> Texture {
+    let mut tex = Texture::transparent(SPRITE_SIZE, SPRITE_SIZE);
+    let pulse = 1.0 + (frame as f64 * PI / 2.0).sin() * 0.15;
+    let radius = 6.0 * pulse;
+    let (cx, cy) = (15.5, 11.5);
+    for y in 0..SPRITE_SIZE {
+        for x in 0..SPRITE_SIZE {
+            let d = ((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt();
+            if d < radius {
+                let core = 1.0 - d / radius;
+                tex.pixels[(y * SPRITE_SIZE + x) as usize] = (
+                    (120.0 + core * 135.0) as u8,
+                    (200.0 + core * 55.0) as u8,
+                    254,
+                );
+            }
+        }
+    }
+    tex
 }
 
-fn map_at(mx: i32, my: i32) -> u8 {
-    if mx < 0 || mx >= MAP_SIZE as i32 || my < 0 || my >= MAP_SIZE as i32 {
-        return 1;
+/// Hovering ghost whose skirt ripples between frames.
+fn ghost_sprite(frame: u32) -> Texture {
+    let mut tex = Texture::transparent(SPRITE_SIZE, SPRITE_SIZE);
+    let phase = frame as f64 * PI;
+    for y in 4..SPRITE_SIZE {
+        for x in 6..26 {
+            let dx = x as f64 - 15.5;
+            // Round head over a straight body with a wavy hem
+            let inside_head = y >= 14 || dx * dx + (y as f64 - 14.0).powi(2) < 100.0;
+            let hem = 28.0 + (x as f64 * 0.9 + phase).sin() * 2.0;
+            if !inside_head || y as f64 > hem {
+                continue;
+            }
+            let eye = (y == 12 || y == 13) && (dx.abs() - 4.0).abs() < 1.5;
+            tex.pixels[(y * SPRITE_SIZE + x) as usize] = if eye {
+                (20, 20, 60)
+            } else {
+                (230, 80 + (y * 3) as u8, 90)
+            };
+        }
     }
-    MAP[my as usize * MAP_SIZE + mx as usize]
+    tex
 }
 
 impl Effect for Wolfenstein {
@@ -53,9 +323,16 @@ impl Effect for Wolfenstein {
         "Wolfenstein"
     }
 
+    fn preferred_duration(&self) -> f64 {
+        14.0
+    }
+
     fn init(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
+        self.ray_dirs = vec![(1.0, 0.0); width as usize];
+        self.wall_spans = vec![(height / 2, height / 2); width as usize];
+        self.depth = vec![f64::MAX; width as usize];
     }
 
     fn update(&mut self, t: f64, _dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -70,9 +347,9 @@ impl Effect for Wolfenstein {
         let t_move = t * self.move_speed;
 
         // Camera position: orbit around the center of the map
-        let center_x = MAP_SIZE as f64 / 2.0;
-        let center_y = MAP_SIZE as f64 / 2.0;
-        let orbit_radius = 3.5;
+        let center_x = self.map.width as f64 / 2.0;
+        let center_y = self.map.height as f64 / 2.0;
+        let orbit_radius = self.map.width.min(self.map.height) as f64 * 0.22;
         let orbit_speed = 0.3;
 
         // Camera orbits and looks inward with some variation
@@ -81,34 +358,20 @@ impl Effect for Wolfenstein {
         let cam_y = center_y + cam_angle.sin() * orbit_radius;
 
         // Look direction: mostly toward center with some rotation
-        let look_angle = cam_angle + PI + (t_move * 0.7).sin() * 0.4;
+        let look_angle = cam_angle + PI + (t_move * 0.7).sin() * 0.4 + self.look.0;
 
         let fov_rad = self.fov * PI / 180.0;
         let half_fov = fov_rad / 2.0;
 
-        // Draw ceiling and floor
-        for y in 0..h {
-            let fy = y as f64 / hf;
-            for x in 0..w {
-                let idx = (y * w + x) as usize;
-                if fy < 0.5 {
-                    // Ceiling: dark gray-blue
-                    let shade = (30.0 + (0.5 - fy) * 40.0) as u8;
-                    pixels[idx] = (shade / 3, shade / 3, shade);
-                } else {
-                    // Floor: dark green-brown
-                    let shade = (20.0 + (fy - 0.5) * 50.0) as u8;
-                    pixels[idx] = (shade / 2, shade, shade / 3);
-                }
-            }
-        }
-
         // Raycast for each column
         for x in 0..w {
             let camera_x = 2.0 * x as f64 / wf - 1.0; // -1 to +1
             let ray_angle = look_angle + camera_x * half_fov;
             let ray_dir_x = ray_angle.cos();
             let ray_dir_y = ray_angle.sin();
+            self.ray_dirs[x as usize] = (ray_dir_x, ray_dir_y);
+            self.wall_spans[x as usize] = (h / 2, h / 2);
+            self.depth[x as usize] = f64::MAX;
 
             // DDA raycasting
             let mut map_x = cam_x.floor() as i32;
@@ -148,7 +411,7 @@ impl Effect for Wolfenstein {
             // Perform DDA
             let mut hit = false;
             let mut side = 0; // 0 = x-side, 1 = y-side
-            let max_steps = 64;
+            let max_steps = (self.map.width + self.map.height).max(64);
 
             for _ in 0..max_steps {
                 if side_dist_x < side_dist_y {
@@ -160,7 +423,7 @@ impl Effect for Wolfenstein {
                     map_y += step_y;
                     side = 1;
                 }
-                if map_at(map_x, map_y) != 0 {
+                if self.map.at(map_x, map_y) != 0 {
                     hit = true;
                     break;
                 }
@@ -178,25 +441,23 @@ impl Effect for Wolfenstein {
             };
 
             let perp_dist = perp_dist.abs().max(0.01);
+            self.depth[x as usize] = perp_dist;
 
-            // Wall strip height
+            // Wall strip height (unclamped start is kept for texture mapping)
             let line_height = (hf / perp_dist).min(hf * 4.0);
-            let draw_start = ((hf / 2.0 - line_height / 2.0).max(0.0)) as u32;
+            let line_top = hf / 2.0 - line_height / 2.0;
+            let draw_start = line_top.max(0.0) as u32;
             let draw_end = ((hf / 2.0 + line_height / 2.0).min(hf - 1.0)) as u32;
+            self.wall_spans[x as usize] = (draw_start, draw_end + 1);
 
-            // Wall color: different for N/S vs E/W sides
-            let base_color: (f64, f64, f64) = if side == 0 {
-                // E/W walls: reddish brick
-                (180.0, 70.0, 50.0)
-            } else {
-                // N/S walls: darker shade
-                (120.0, 50.0, 35.0)
-            };
+            // N/S walls are shaded darker than E/W walls
+            let side_shade = if side == 0 { 1.0 } else { 0.7 };
 
             // Distance-based darkening
             let dist_factor = (1.0 / (1.0 + perp_dist * 0.15)).clamp(0.15, 1.0);
+            let shade = side_shade * dist_factor;
 
-            // Calculate where on the wall the ray hit (for texture variation)
+            // Calculate where on the wall the ray hit
             let wall_x = if side == 0 {
                 cam_y + perp_dist * ray_dir_y
             } else {
@@ -204,26 +465,82 @@ impl Effect for Wolfenstein {
             };
             let wall_x = wall_x - wall_x.floor();
 
-            // Simple vertical stripe pattern on walls
-            let stripe = if (wall_x * 8.0) as i32 % 2 == 0 {
-                1.0
-            } else {
-                0.85
-            };
-
-            let r = (base_color.0 * dist_factor * stripe).min(255.0) as u8;
-            let g = (base_color.1 * dist_factor * stripe).min(255.0) as u8;
-            let b = (base_color.2 * dist_factor * stripe).min(255.0) as u8;
+            // Texture column, mirrored so textures read the same way on every face
+            let tex_w = self.texture.width as f64;
+            let tex_h = self.texture.height as f64;
+            let mut tex_x = (wall_x * tex_w) as i64;
+            if (side == 0 && ray_dir_x > 0.0) || (side == 1 && ray_dir_y < 0.0) {
+                tex_x = self.texture.width as i64 - tex_x - 1;
+            }
 
             for y in draw_start..=draw_end {
                 if y < h {
                     let idx = (y * w + x) as usize;
                     if idx < pixels.len() {
-                        pixels[idx] = (r, g, b);
+                        let tex_y = ((y as f64 - line_top) / line_height * tex_h) as i64;
+                        let (tr, tg, tb) = self.texture.texel(tex_x, tex_y);
+                        pixels[idx] = (
+                            (tr as f64 * shade) as u8,
+                            (tg as f64 * shade) as u8,
+                            (tb as f64 * shade) as u8,
+                        );
                     }
                 }
             }
         }
+
+        // Floor and ceiling casting: each row below the horizon maps to a fixed
+        // distance along every column's ray; the ceiling mirrors it above.
+        let half_h = hf / 2.0;
+        let floor_w = self.floor_texture.width as f64;
+        let floor_h = self.floor_texture.height as f64;
+        let ceil_w = self.ceiling_texture.width as f64;
+        let ceil_h = self.ceiling_texture.height as f64;
+        for y in (h / 2)..h {
+            // With an odd height the first row sits on the horizon itself;
+            // treat it as half a row below so its distance stays finite
+            let row_dist = half_h / (y as f64 + 0.5 - half_h).max(0.5);
+            let shade = (1.0 / (1.0 + row_dist * 0.15)).clamp(0.15, 1.0);
+            let ceil_y = h - 1 - y;
+
+            for x in 0..w {
+                let (wall_top, wall_end) = self.wall_spans[x as usize];
+                let below = y >= wall_end;
+                let above = ceil_y < wall_top;
+                if !below && !above {
+                    continue;
+                }
+
+                let (dir_x, dir_y) = self.ray_dirs[x as usize];
+                let world_x = cam_x + dir_x * row_dist;
+                let world_y = cam_y + dir_y * row_dist;
+                let frac_x = world_x - world_x.floor();
+                let frac_y = world_y - world_y.floor();
+
+                if below {
+                    let (r, g, b) = self
+                        .floor_texture
+                        .texel((frac_x * floor_w) as i64, (frac_y * floor_h) as i64);
+                    pixels[(y * w + x) as usize] = (
+                        (r as f64 * shade) as u8,
+                        (g as f64 * shade) as u8,
+                        (b as f64 * shade) as u8,
+                    );
+                }
+                if above {
+                    let (r, g, b) = self
+                        .ceiling_texture
+                        .texel((frac_x * ceil_w) as i64, (frac_y * ceil_h) as i64);
+                    pixels[(ceil_y * w + x) as usize] = (
+                        (r as f64 * shade) as u8,
+                        (g as f64 * shade) as u8,
+                        (b as f64 * shade) as u8,
+                    );
+                }
+            }
+        }
+
+        self.draw_sprites(t, cam_x, cam_y, look_angle, half_fov, pixels);
     }
 
     fn params(&self) -> Vec<ParamDesc> {
@@ -243,6 +560,10 @@ impl Effect for Wolfenstein {
         ]
     }
 
+    fn set_camera(&mut self, yaw: f64, pitch: f64) {
+        self.look = (yaw, pitch);
+    }
+
     fn set_param(&mut self, name: &str, value: f64) {
         match name {
             "move_speed" => self.move_speed = value,
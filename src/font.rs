@@ -0,0 +1,188 @@
+//! Shared 5x7 bitmap font for text-rendering effects.
+//!
+//! Each glyph is 7 rows of 5 bits; bit 4 is the leftmost column. Glyphs are
+//! proportional: `glyph` returns bits left-aligned and `glyph_width` gives
+//! the number of columns actually used.
+
+pub const FONT_WIDTH: u32 = 5;
+pub const FONT_HEIGHT: u32 = 7;
+
+/// Hollow box drawn for characters the font doesn't cover
+const PLACEHOLDER: [u8; 7] = [0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111];
+
+/// Advance for a blank glyph such as space
+const SPACE_WIDTH: u32 = 3;
+
+/// Katakana covered by the font, e.g. for Matrix rain.
+pub const KATAKANA: &str = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+fn raw_glyph(ch: char) -> Option<[u8; 7]> {
+    Some(match ch {
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b11110, 0b10001, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01110],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01110, 0b10001, 0b10000, 0b01110, 0b00001, 0b10001, 0b01110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b11011, 0b10001],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        '*' => [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '?' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+        '\'' => [0b00100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000],
+        ' ' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        'a' => [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111],
+        'b' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110],
+        'c' => [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110],
+        'd' => [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111],
+        'e' => [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110],
+        'f' => [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000],
+        'g' => [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'h' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        'i' => [0b00100, 0b00000, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'j' => [0b00010, 0b00000, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100],
+        'k' => [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010],
+        'l' => [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'm' => [0b00000, 0b00000, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001],
+        'n' => [0b00000, 0b00000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        'o' => [0b00000, 0b00000, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110],
+        'p' => [0b00000, 0b00000, 0b11110, 0b10001, 0b11110, 0b10000, 0b10000],
+        'q' => [0b00000, 0b00000, 0b01101, 0b10011, 0b01111, 0b00001, 0b00001],
+        'r' => [0b00000, 0b00000, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000],
+        's' => [0b00000, 0b00000, 0b01110, 0b10000, 0b01110, 0b00001, 0b11110],
+        't' => [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110],
+        'u' => [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101],
+        'v' => [0b00000, 0b00000, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'w' => [0b00000, 0b00000, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010],
+        'x' => [0b00000, 0b00000, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001],
+        'y' => [0b00000, 0b00000, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'z' => [0b00000, 0b00000, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111],
+        'ア' => [0b11111, 0b00001, 0b00110, 0b00100, 0b00100, 0b01000, 0b10000],
+        'イ' => [0b00001, 0b00010, 0b00110, 0b01010, 0b00010, 0b00010, 0b00010],
+        'ウ' => [0b00100, 0b11111, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000],
+        'エ' => [0b00000, 0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b11111],
+        'オ' => [0b00010, 0b11111, 0b00110, 0b01010, 0b10010, 0b00010, 0b00110],
+        'カ' => [0b01000, 0b11111, 0b01001, 0b01001, 0b01001, 0b10001, 0b10110],
+        'キ' => [0b01000, 0b11111, 0b00100, 0b11111, 0b00100, 0b00100, 0b00100],
+        'ク' => [0b01000, 0b01111, 0b10001, 0b00001, 0b00010, 0b00100, 0b11000],
+        'ケ' => [0b01000, 0b01111, 0b10100, 0b00100, 0b00100, 0b01000, 0b10000],
+        'コ' => [0b00000, 0b11111, 0b00001, 0b00001, 0b00001, 0b00001, 0b11111],
+        'サ' => [0b01010, 0b11111, 0b01010, 0b01010, 0b00010, 0b00100, 0b01000],
+        'シ' => [0b11000, 0b00001, 0b11001, 0b00001, 0b00010, 0b00100, 0b11000],
+        'ス' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01010, 0b10001, 0b00000],
+        'セ' => [0b01000, 0b01000, 0b11111, 0b01001, 0b01010, 0b01000, 0b00111],
+        'ソ' => [0b10001, 0b10001, 0b01001, 0b00001, 0b00010, 0b00100, 0b11000],
+        'タ' => [0b01000, 0b01111, 0b10001, 0b01101, 0b00010, 0b00100, 0b11000],
+        'チ' => [0b00011, 0b11100, 0b00100, 0b11111, 0b00100, 0b01000, 0b10000],
+        'ツ' => [0b10101, 0b10101, 0b00001, 0b00001, 0b00010, 0b00100, 0b11000],
+        'テ' => [0b01110, 0b00000, 0b11111, 0b00100, 0b00100, 0b01000, 0b10000],
+        'ト' => [0b01000, 0b01000, 0b01100, 0b01010, 0b01000, 0b01000, 0b01000],
+        'ナ' => [0b00100, 0b11111, 0b00100, 0b00100, 0b00100, 0b01000, 0b10000],
+        'ニ' => [0b00000, 0b01110, 0b00000, 0b00000, 0b00000, 0b11111, 0b00000],
+        'ヌ' => [0b11111, 0b00001, 0b01010, 0b00100, 0b01010, 0b10000, 0b00000],
+        'ネ' => [0b00100, 0b11111, 0b00010, 0b00100, 0b01110, 0b10101, 0b00100],
+        'ノ' => [0b00001, 0b00001, 0b00010, 0b00010, 0b00100, 0b01000, 0b10000],
+        'ハ' => [0b00000, 0b01010, 0b01010, 0b10001, 0b10001, 0b10001, 0b00000],
+        'ヒ' => [0b10000, 0b10000, 0b10110, 0b11000, 0b10000, 0b10000, 0b01111],
+        'フ' => [0b11111, 0b00001, 0b00001, 0b00010, 0b00010, 0b00100, 0b11000],
+        'ヘ' => [0b00000, 0b01000, 0b10100, 0b00010, 0b00001, 0b00000, 0b00000],
+        'ホ' => [0b00100, 0b11111, 0b00100, 0b10101, 0b10101, 0b00100, 0b01100],
+        'マ' => [0b11111, 0b00001, 0b00010, 0b10100, 0b01000, 0b00100, 0b00000],
+        'ミ' => [0b11100, 0b00011, 0b00000, 0b11100, 0b00011, 0b11100, 0b00011],
+        'ム' => [0b00100, 0b00100, 0b01000, 0b01000, 0b10010, 0b11111, 0b00001],
+        'メ' => [0b00001, 0b10010, 0b01100, 0b00100, 0b01010, 0b10000, 0b00000],
+        'モ' => [0b11111, 0b00100, 0b11111, 0b00100, 0b00100, 0b00100, 0b00011],
+        'ヤ' => [0b01000, 0b11111, 0b01001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'ユ' => [0b00000, 0b11110, 0b00010, 0b00010, 0b00010, 0b11111, 0b00000],
+        'ヨ' => [0b11111, 0b00001, 0b00001, 0b01111, 0b00001, 0b00001, 0b11111],
+        'ラ' => [0b01110, 0b00000, 0b11111, 0b00001, 0b00010, 0b00100, 0b01000],
+        'リ' => [0b10001, 0b10001, 0b10001, 0b10001, 0b00001, 0b00010, 0b00100],
+        'ル' => [0b01010, 0b01010, 0b01010, 0b01010, 0b01011, 0b10010, 0b00000],
+        'レ' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10001, 0b10010, 0b11100],
+        'ロ' => [0b00000, 0b11111, 0b10001, 0b10001, 0b10001, 0b11111, 0b00000],
+        'ワ' => [0b11111, 0b10001, 0b00001, 0b00010, 0b00010, 0b00100, 0b11000],
+        'ン' => [0b11000, 0b00001, 0b00001, 0b00001, 0b00010, 0b00100, 0b11000],
+        _ => return None,
+    })
+}
+
+/// Table glyph for `ch`, falling back to the uppercase form for lowercase
+/// letters without their own glyph, then to the placeholder box.
+fn lookup(ch: char) -> [u8; 7] {
+    raw_glyph(ch)
+        .or_else(|| {
+            if ch.is_lowercase() {
+                ch.to_uppercase().next().and_then(raw_glyph)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(PLACEHOLDER)
+}
+
+/// Leftmost and rightmost lit columns, or `None` for a blank glyph.
+fn column_span(bits: &[u8; 7]) -> Option<(u32, u32)> {
+    let mask = bits.iter().fold(0u8, |acc, row| acc | row);
+    if mask == 0 {
+        return None;
+    }
+    let first = (0..FONT_WIDTH).find(|&c| mask & (1 << (FONT_WIDTH - 1 - c)) != 0)?;
+    let last = (0..FONT_WIDTH).rev().find(|&c| mask & (1 << (FONT_WIDTH - 1 - c)) != 0)?;
+    Some((first, last))
+}
+
+/// Glyph bitmap for `ch`, shifted so its first lit column is column 0.
+pub fn glyph(ch: char) -> [u8; 7] {
+    let mut bits = lookup(ch);
+    if let Some((first, _)) = column_span(&bits) {
+        for row in bits.iter_mut() {
+            *row = (*row << first) & 0b11111;
+        }
+    }
+    bits
+}
+
+/// Number of columns `ch` occupies, not counting inter-character spacing.
+pub fn glyph_width(ch: char) -> u32 {
+    match column_span(&lookup(ch)) {
+        Some((first, last)) => last - first + 1,
+        None => SPACE_WIDTH,
+    }
+}
+
+/// Whether pixel `(col, row)` of a glyph is lit.
+pub fn pixel_set(bits: &[u8; 7], col: u32, row: u32) -> bool {
+    bits[row as usize] & (1 << (FONT_WIDTH - 1 - col)) != 0
+}
@@ -31,8 +31,145 @@ impl PixelFramebuffer {
     }
 }
 
+/// A pixel with coverage, for effects drawn as a layer over others. Alpha 0
+/// is fully transparent and 255 fully opaque; the color channels are
+/// premultiplied by alpha, so additive drawing accumulates naturally and a
+/// layer over black is just its color.
+pub type Rgba = (u8, u8, u8, u8);
+
+/// Lay `layer` over `background` in place.
+pub fn flatten(layer: &[Rgba], background: &mut [(u8, u8, u8)]) {
+    for (&(r, g, b, a), px) in layer.iter().zip(background.iter_mut()) {
+        let keep = 255 - a as u16;
+        let under = |c: u8| (c as u16 * keep / 255) as u8;
+        *px = (
+            r.saturating_add(under(px.0)),
+            g.saturating_add(under(px.1)),
+            b.saturating_add(under(px.2)),
+        );
+    }
+}
+
+/// Trail post-process for effects that opt in via `Effect::wants_persistence`:
+/// each pixel keeps the brighter of its new value and the decayed previous output.
+#[derive(Default)]
+pub struct Persistence {
+    prev: Vec<(u8, u8, u8)>,
+}
+
+impl Persistence {
+    /// Blend `pixels` with the last output; `decay` is the fraction kept per
+    /// 1/60 s, scaled by `dt` so trail length doesn't depend on frame rate.
+    pub fn apply(&mut self, pixels: &mut [(u8, u8, u8)], decay: f64, dt: f64) {
+        if self.prev.len() != pixels.len() {
+            self.prev.clear();
+            self.prev.extend_from_slice(pixels);
+            return;
+        }
+        let keep = decay.clamp(0.0, 1.0).powf(dt * 60.0);
+        let fade = |c: u8| (c as f64 * keep) as u8;
+        for (p, q) in pixels.iter_mut().zip(self.prev.iter_mut()) {
+            *p = (p.0.max(fade(q.0)), p.1.max(fade(q.1)), p.2.max(fade(q.2)));
+            *q = *p;
+        }
+    }
+
+    /// Forget the trail, e.g. when the scene changes.
+    pub fn reset(&mut self) {
+        self.prev.clear();
+    }
+}
+
+/// How many colors the terminal can show, from `--color`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// The 16 ANSI colors with typical (VGA-like) RGB values.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (170, 0, 0)),
+    (Color::Green, (0, 170, 0)),
+    (Color::Yellow, (170, 85, 0)),
+    (Color::Blue, (0, 0, 170)),
+    (Color::Magenta, (170, 0, 170)),
+    (Color::Cyan, (0, 170, 170)),
+    (Color::Gray, (170, 170, 170)),
+    (Color::DarkGray, (85, 85, 85)),
+    (Color::LightRed, (255, 85, 85)),
+    (Color::LightGreen, (85, 255, 85)),
+    (Color::LightYellow, (255, 255, 85)),
+    (Color::LightBlue, (85, 85, 255)),
+    (Color::LightMagenta, (255, 85, 255)),
+    (Color::LightCyan, (85, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the xterm 256-color 6x6x6 cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn dist_sq(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+impl ColorDepth {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "16" => Some(Self::Ansi16),
+            "256" => Some(Self::Ansi256),
+            "truecolor" | "24bit" => Some(Self::TrueColor),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ansi16 => "16-color",
+            Self::Ansi256 => "256-color",
+            Self::TrueColor => "truecolor",
+        }
+    }
+
+    /// The closest color this depth can show.
+    pub fn color(self, px: (u8, u8, u8)) -> Color {
+        match self {
+            Self::TrueColor => Color::Rgb(px.0, px.1, px.2),
+            Self::Ansi256 => {
+                let level = |v: u8| match v {
+                    0..=47 => 0,
+                    48..=114 => 1,
+                    _ => (v - 35) / 40,
+                };
+                let (r, g, b) = (level(px.0), level(px.1), level(px.2));
+                let cube = (CUBE_LEVELS[r as usize], CUBE_LEVELS[g as usize], CUBE_LEVELS[b as usize]);
+                // The 24-step gray ramp beats the cube on near-neutral colors
+                let avg = (px.0 as u32 + px.1 as u32 + px.2 as u32) / 3;
+                let step = (avg.saturating_sub(3) / 10).min(23) as u8;
+                let gray = 8 + step * 10;
+                if dist_sq(px, (gray, gray, gray)) < dist_sq(px, cube) {
+                    Color::Indexed(232 + step)
+                } else {
+                    Color::Indexed(16 + 36 * r + 6 * g + b)
+                }
+            }
+            Self::Ansi16 => {
+                ANSI16
+                    .iter()
+                    .min_by_key(|(_, rgb)| dist_sq(px, *rgb))
+                    .map(|&(color, _)| color)
+                    .unwrap_or(Color::Black)
+            }
+        }
+    }
+}
+
 pub struct HalfBlockWidget<'a> {
     pub framebuffer: &'a PixelFramebuffer,
+    pub depth: ColorDepth,
 }
 
 impl<'a> Widget for HalfBlockWidget<'a> {
@@ -65,8 +202,8 @@ impl<'a> Widget for HalfBlockWidget<'a> {
                 cell.set_symbol("\u{2580}"); // ▀
                 cell.set_style(
                     Style::default()
-                        .fg(Color::Rgb(top_pixel.0, top_pixel.1, top_pixel.2))
-                        .bg(Color::Rgb(bot_pixel.0, bot_pixel.1, bot_pixel.2)),
+                        .fg(self.depth.color(top_pixel))
+                        .bg(self.depth.color(bot_pixel)),
                 );
             }
         }
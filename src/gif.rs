@@ -0,0 +1,189 @@
+//! Minimal animated GIF encoder for offscreen recordings.
+//!
+//! Frames are quantized to a fixed 6x7x6 color cube with ordered dithering,
+//! so every frame shares one global palette and no per-frame analysis is needed.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const R_LEVELS: u32 = 6;
+const G_LEVELS: u32 = 7;
+const B_LEVELS: u32 = 6;
+
+/// 4x4 Bayer matrix for ordered dithering
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+pub struct GifEncoder<W: Write> {
+    out: W,
+    width: u16,
+    height: u16,
+    /// Frame delay in hundredths of a second
+    delay_cs: u16,
+}
+
+impl<W: Write> GifEncoder<W> {
+    /// Write the header, global palette and looping extension.
+    pub fn new(mut out: W, width: u16, height: u16, fps: f64) -> io::Result<Self> {
+        out.write_all(b"GIF89a")?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        // Global color table present, 8 bits color resolution, 256 entries
+        out.write_all(&[0xF7, 0, 0])?;
+        for i in 0..256u32 {
+            let (r, g, b) = if i < R_LEVELS * G_LEVELS * B_LEVELS {
+                (
+                    i / (G_LEVELS * B_LEVELS),
+                    (i / B_LEVELS) % G_LEVELS,
+                    i % B_LEVELS,
+                )
+            } else {
+                (0, 0, 0)
+            };
+            out.write_all(&[
+                (r * 255 / (R_LEVELS - 1)) as u8,
+                (g * 255 / (G_LEVELS - 1)) as u8,
+                (b * 255 / (B_LEVELS - 1)) as u8,
+            ])?;
+        }
+        // NETSCAPE2.0 application extension: loop forever
+        out.write_all(&[0x21, 0xFF, 0x0B])?;
+        out.write_all(b"NETSCAPE2.0")?;
+        out.write_all(&[0x03, 0x01, 0x00, 0x00, 0x00])?;
+
+        let delay_cs = (100.0 / fps).round().clamp(1.0, 65535.0) as u16;
+        Ok(Self {
+            out,
+            width,
+            height,
+            delay_cs,
+        })
+    }
+
+    pub fn write_frame(&mut self, pixels: &[(u8, u8, u8)]) -> io::Result<()> {
+        let w = self.width as usize;
+        let indices: Vec<u8> = pixels
+            .iter()
+            .take(w * self.height as usize)
+            .enumerate()
+            .map(|(i, &px)| quantize(px, i % w, i / w))
+            .collect();
+
+        // Graphic control extension: frame delay, no transparency
+        self.out.write_all(&[0x21, 0xF9, 0x04, 0x00])?;
+        self.out.write_all(&self.delay_cs.to_le_bytes())?;
+        self.out.write_all(&[0x00, 0x00])?;
+
+        // Image descriptor covering the whole canvas, no local palette
+        self.out.write_all(&[0x2C, 0, 0, 0, 0])?;
+        self.out.write_all(&self.width.to_le_bytes())?;
+        self.out.write_all(&self.height.to_le_bytes())?;
+        self.out.write_all(&[0x00])?;
+
+        self.out.write_all(&[8])?;
+        let data = lzw_encode(&indices);
+        for block in data.chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0x00])
+    }
+
+    /// Write the trailer and flush.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&[0x3B])?;
+        self.out.flush()
+    }
+}
+
+fn quantize(px: (u8, u8, u8), x: usize, y: usize) -> u8 {
+    let threshold = (BAYER[y % 4][x % 4] as f64 + 0.5) / 16.0;
+    let level = |v: u8, levels: u32| {
+        let scaled = v as f64 / 255.0 * (levels - 1) as f64 + threshold - 0.5;
+        scaled.round().clamp(0.0, (levels - 1) as f64) as u32
+    };
+    let r = level(px.0, R_LEVELS);
+    let g = level(px.1, G_LEVELS);
+    let b = level(px.2, B_LEVELS);
+    (r * G_LEVELS * B_LEVELS + g * B_LEVELS + b) as u8
+}
+
+/// Packs variable-width codes LSB-first, as GIF expects.
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+/// GIF-flavored LZW with 8-bit minimum code size and 12-bit maximum codes.
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END: u16 = 257;
+    const MAX_CODE: u16 = 4095;
+
+    let mut out = BitWriter {
+        bytes: Vec::new(),
+        acc: 0,
+        bits: 0,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = END + 1;
+    let mut width = 9;
+
+    out.write(CLEAR, width);
+    let mut iter = indices.iter();
+    let mut prefix = match iter.next() {
+        Some(&first) => first as u16,
+        None => {
+            out.write(END, width);
+            return out.finish();
+        }
+    };
+
+    for &k in iter {
+        if let Some(&code) = table.get(&(prefix, k)) {
+            prefix = code;
+            continue;
+        }
+        out.write(prefix, width);
+        if next_code <= MAX_CODE {
+            table.insert((prefix, k), next_code);
+            // Widen once the just-assigned code no longer fits
+            if next_code == (1 << width) && width < 12 {
+                width += 1;
+            }
+            next_code += 1;
+        } else {
+            out.write(CLEAR, width);
+            table.clear();
+            next_code = END + 1;
+            width = 9;
+        }
+        prefix = k as u16;
+    }
+    out.write(prefix, width);
+    // The decoder grows its table after this last code too
+    if next_code >= (1 << width) && width < 12 {
+        width += 1;
+    }
+    out.write(END, width);
+    out.finish()
+}
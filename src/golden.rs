@@ -0,0 +1,98 @@
+//! Golden-image regression tests: every registered effect is stepped to a
+//! fixed time at a small size with a fixed seed, and a hash of its frame is
+//! compared against the one stored in `tests/goldens.txt`.
+//!
+//! After an intended visual change, regenerate the file with
+//! `UPDATE_GOLDENS=1 cargo test golden` and commit it with the change.
+//! Effects lean on `f64` transcendentals, so hashes are only expected to
+//! match on the platform that produced them.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::effects;
+
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 32;
+const SEED: u64 = 1;
+const DT: f64 = 1.0 / 30.0;
+const FRAMES: usize = 45;
+
+fn goldens_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/goldens.txt")
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+fn fnv1a(pixels: &[(u8, u8, u8)]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for &(r, g, b) in pixels {
+        for byte in [r, g, b] {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Hash of `name`'s frame after `FRAMES` fixed steps.
+fn render_hash(name: &str) -> u64 {
+    let mut effect = effects::create(name).expect("registered effect");
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut pixels = vec![(0, 0, 0); (WIDTH * HEIGHT) as usize];
+    effect.init(WIDTH, HEIGHT);
+    effect.randomize_init(&mut rng);
+    for frame in 0..FRAMES {
+        effect.update(frame as f64 * DT, DT, &mut pixels);
+    }
+    fnv1a(&pixels)
+}
+
+/// `name hash` lines, skipping blanks and `#` comments.
+fn load_goldens() -> BTreeMap<String, u64> {
+    let text = fs::read_to_string(goldens_path()).unwrap_or_default();
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, hash) = line.rsplit_once(' ')?;
+            Some((name.to_string(), u64::from_str_radix(hash, 16).ok()?))
+        })
+        .collect()
+}
+
+#[test]
+fn effects_match_goldens() {
+    let actual: Vec<(&str, u64)> = effects::names().map(|name| (name, render_hash(name))).collect();
+
+    if std::env::var_os("UPDATE_GOLDENS").is_some() {
+        let mut text = format!(
+            "# Frame hashes at {}x{}, seed {}, after {} steps of 1/30 s.\n\
+             # Regenerate with UPDATE_GOLDENS=1 cargo test golden\n",
+            WIDTH, HEIGHT, SEED, FRAMES
+        );
+        for (name, hash) in &actual {
+            text.push_str(&format!("{} {:016x}\n", name, hash));
+        }
+        fs::write(goldens_path(), text).expect("write goldens");
+        return;
+    }
+
+    let goldens = load_goldens();
+    let failures: Vec<String> = actual
+        .iter()
+        .filter_map(|&(name, hash)| match goldens.get(name) {
+            Some(&expected) if expected == hash => None,
+            Some(&expected) => Some(format!("{}: expected {:016x}, got {:016x}", name, expected, hash)),
+            None => Some(format!("{}: no golden hash", name)),
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "effect output changed (rerun with UPDATE_GOLDENS=1 if intended):\n  {}",
+        failures.join("\n  ")
+    );
+}
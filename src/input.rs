@@ -1,6 +1,7 @@
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
 use std::time::Duration;
 
+#[derive(Clone, Copy)]
 pub enum Action {
     Quit,
     TogglePause,
@@ -9,35 +10,209 @@ pub enum Action {
     PrevScene,
     GotoScene(usize),
     ToggleHud,
+    ToggleBorder,
+    ToggleHelp,
     ToggleHold,
+    ToggleReverse,
     ParamUp,
     ParamDown,
     ParamPrev,
     ParamNext,
+    EditMessage,
+    Trigger,
+    Reshuffle,
+    /// Left button pressed or dragged to this cell (column, row)
+    MouseDown(u16, u16),
+    MouseDrag(u16, u16),
+    MouseUp,
+    TextChar(char),
+    TextBackspace,
+    TextSubmit,
+    TextCancel,
     None,
 }
 
-pub fn poll_action() -> std::io::Result<Action> {
+/// A shortcut: the keys that trigger it, how the help overlay spells them,
+/// and what it does.
+pub struct Binding {
+    /// Empty for entries that aren't keys, listed for the help only
+    pub codes: &'static [KeyCode],
+    pub action: Action,
+    pub keys: &'static str,
+    pub help: &'static str,
+}
+
+/// Every shortcut, in the order the help overlay lists them. Both the key
+/// handler and the overlay read this table, so they can't drift apart.
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        codes: &[KeyCode::Char('?')],
+        action: Action::ToggleHelp,
+        keys: "?",
+        help: "Show or hide this key list",
+    },
+    Binding {
+        codes: &[KeyCode::Char('q'), KeyCode::Esc],
+        action: Action::Quit,
+        keys: "q / Esc",
+        help: "Quit (or close this list)",
+    },
+    Binding {
+        codes: &[KeyCode::Char(' ')],
+        action: Action::TogglePause,
+        keys: "Space",
+        help: "Pause / resume (in presentation mode, advance to the next effect)",
+    },
+    Binding {
+        codes: &[KeyCode::Tab],
+        action: Action::ToggleMode,
+        keys: "Tab",
+        help: "Toggle autoplay (or presenting) / interactive mode",
+    },
+    Binding {
+        codes: &[KeyCode::Char('n'), KeyCode::Right],
+        action: Action::NextScene,
+        keys: "n / Right",
+        help: "Skip to the next effect",
+    },
+    Binding {
+        codes: &[KeyCode::Char('p'), KeyCode::Left],
+        action: Action::PrevScene,
+        keys: "p / Left",
+        help: "Back to the previous effect",
+    },
+    Binding {
+        codes: &[
+            KeyCode::Char('1'),
+            KeyCode::Char('2'),
+            KeyCode::Char('3'),
+            KeyCode::Char('4'),
+            KeyCode::Char('5'),
+            KeyCode::Char('6'),
+            KeyCode::Char('7'),
+            KeyCode::Char('8'),
+            KeyCode::Char('9'),
+        ],
+        // The digit pressed picks the scene, see `key_action`
+        action: Action::GotoScene(0),
+        keys: "1-9",
+        help: "Jump to effect 1-9",
+    },
+    Binding {
+        codes: &[KeyCode::Char('f')],
+        action: Action::ToggleHold,
+        keys: "f",
+        help: "Hold the current scene (no auto-advance)",
+    },
+    Binding {
+        codes: &[KeyCode::Char('z')],
+        action: Action::ToggleReverse,
+        keys: "z",
+        help: "Run the effect's time backward / forward again",
+    },
+    Binding {
+        codes: &[KeyCode::Char('h')],
+        action: Action::ToggleHud,
+        keys: "h",
+        help: "Toggle the HUD overlay",
+    },
+    Binding {
+        codes: &[KeyCode::Char('b')],
+        action: Action::ToggleBorder,
+        keys: "b",
+        help: "Toggle the titled border",
+    },
+    Binding {
+        codes: &[KeyCode::Up],
+        action: Action::ParamUp,
+        keys: "Up",
+        help: "Raise the selected parameter (scroll this list)",
+    },
+    Binding {
+        codes: &[KeyCode::Down],
+        action: Action::ParamDown,
+        keys: "Down",
+        help: "Lower the selected parameter (scroll this list)",
+    },
+    Binding {
+        codes: &[KeyCode::Char('[')],
+        action: Action::ParamPrev,
+        keys: "[",
+        help: "Select the previous parameter",
+    },
+    Binding {
+        codes: &[KeyCode::Char(']')],
+        action: Action::ParamNext,
+        keys: "]",
+        help: "Select the next parameter",
+    },
+    Binding {
+        codes: &[KeyCode::Char('w')],
+        action: Action::Trigger,
+        keys: "w",
+        help: "Trigger the effect's burst",
+    },
+    Binding {
+        codes: &[KeyCode::Char('r'), KeyCode::Char('R')],
+        action: Action::Reshuffle,
+        keys: "r",
+        help: "Reshuffle the effect's random layout",
+    },
+    Binding {
+        codes: &[KeyCode::Char('m')],
+        action: Action::EditMessage,
+        keys: "m",
+        help: "Type a new scroller message",
+    },
+    Binding {
+        codes: &[],
+        action: Action::None,
+        keys: "Mouse drag",
+        help: "Orbit the camera in 3D scenes (interactive)",
+    },
+];
+
+/// The shortcut bound to `code`, if any.
+fn key_action(code: KeyCode) -> Action {
+    let binding = match BINDINGS.iter().find(|b| b.codes.contains(&code)) {
+        Some(binding) => binding,
+        None => return Action::None,
+    };
+    match (binding.action, code) {
+        (Action::GotoScene(_), KeyCode::Char(c)) => Action::GotoScene(c as usize - '1' as usize),
+        (action, _) => action,
+    }
+}
+
+/// Poll for the next key action. While `editing` text, printable keys are
+/// returned as `TextChar` instead of being treated as shortcuts.
+pub fn poll_action(editing: bool) -> std::io::Result<Action> {
     if event::poll(Duration::ZERO)? {
-        if let Event::Key(key) = event::read()? {
+        let event = event::read()?;
+        if let Event::Mouse(mouse) = event {
+            return Ok(match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    Action::MouseDown(mouse.column, mouse.row)
+                }
+                MouseEventKind::Drag(MouseButton::Left) => {
+                    Action::MouseDrag(mouse.column, mouse.row)
+                }
+                MouseEventKind::Up(MouseButton::Left) => Action::MouseUp,
+                _ => Action::None,
+            });
+        }
+        if let Event::Key(key) = event {
             if key.kind == KeyEventKind::Press {
-                return Ok(match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
-                    KeyCode::Char(' ') => Action::TogglePause,
-                    KeyCode::Tab => Action::ToggleMode,
-                    KeyCode::Char('n') | KeyCode::Right => Action::NextScene,
-                    KeyCode::Char('p') | KeyCode::Left => Action::PrevScene,
-                    KeyCode::Char('h') => Action::ToggleHud,
-                    KeyCode::Char('f') => Action::ToggleHold,
-                    KeyCode::Up => Action::ParamUp,
-                    KeyCode::Down => Action::ParamDown,
-                    KeyCode::Char('[') => Action::ParamPrev,
-                    KeyCode::Char(']') => Action::ParamNext,
-                    KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
-                        Action::GotoScene((c as usize) - ('1' as usize))
-                    }
-                    _ => Action::None,
-                });
+                if editing {
+                    return Ok(match key.code {
+                        KeyCode::Char(c) => Action::TextChar(c),
+                        KeyCode::Backspace => Action::TextBackspace,
+                        KeyCode::Enter => Action::TextSubmit,
+                        KeyCode::Esc => Action::TextCancel,
+                        _ => Action::None,
+                    });
+                }
+                return Ok(key_action(key.code));
             }
         }
     }
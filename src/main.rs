@@ -1,41 +1,70 @@
 mod app;
+mod bench;
+mod camera;
+mod click;
+mod color;
 mod effect;
 mod effects;
+mod font;
 mod framebuffer;
+mod gif;
+#[cfg(test)]
+mod golden;
 mod input;
+mod media;
+mod noise;
+mod palette;
+mod playlist;
+mod post;
+mod record;
+mod rng;
 mod scene;
 mod sequencer;
+mod smoke;
+mod splat;
+mod sunlight;
+mod texture;
 mod transition;
 mod ui;
 
-use std::io;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 use std::time::Duration;
 
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
 
 use app::{App, Mode};
+use camera::CameraPath;
+use effect::Effect;
 use effects::aurora::Aurora;
 use effects::boingball::BoingBall;
 use effects::boids::Boids;
 use effects::cellular::CellularAutomata;
+use effects::compose::{BlendMode, Compose};
 use effects::copperflag::CopperFlag;
 use effects::filledvector::FilledVector;
 use effects::fluidsim::FluidSim;
 use effects::fractalzoom::FractalZoom;
 use effects::lightning::Lightning;
-use effects::morph::Morph;
-use effects::oscilloscope::Oscilloscope;
+use effects::morph::{self, Morph};
+use effects::oscilloscope::{self, Oscilloscope, Samples};
 use effects::reaction::ReactionDiffusion;
 use effects::sinescroller::SineScroller;
 use effects::snowfall::Snowfall;
 use effects::spirograph::Spirograph;
 use effects::truchet::Truchet;
-use effects::wolfenstein::Wolfenstein;
+use effects::wolfenstein::{Map, Wolfenstein};
 use effects::clothsim::ClothSim;
 use effects::cubefield::CubeField;
 use effects::dottunnel::DotTunnel;
@@ -49,6 +78,7 @@ use effects::parallax::Parallax;
 use effects::pendulum::PendulumWave;
 use effects::pixelsort::PixelSort;
 use effects::rain::Rain;
+use effects::scripted::Scripted;
 use effects::sierpinski::Sierpinski;
 use effects::terrain::Terrain;
 use effects::bumpmapping::BumpMapping;
@@ -65,7 +95,7 @@ use effects::julia::Julia;
 use effects::lens::Lens;
 use effects::lissajous::Lissajous3D;
 use effects::mandelbrot::Mandelbrot;
-use effects::matrix::Matrix;
+use effects::matrix::{Charset, Matrix};
 use effects::metaballs::Metaballs;
 use effects::moire::Moire;
 use effects::plasma::Plasma;
@@ -82,28 +112,267 @@ use effects::voronoi::Voronoi;
 use effects::voxel::VoxelLandscape;
 use effects::water::Water;
 use effects::wireframe::Wireframe;
-use framebuffer::HalfBlockWidget;
-use ui::HudWidget;
+use framebuffer::{ColorDepth, HalfBlockWidget};
+use gif::GifEncoder;
+use media::SourceImage;
+use post::{Bloom, PostEffect, Theme};
+use ui::{BorderWidget, HelpWidget, HudWidget, MessagePromptWidget};
 use scene::Scene;
 use sequencer::Sequencer;
+use texture::Texture;
 use transition::TransitionKind;
 
 fn main() -> io::Result<()> {
     let args: Vec<String> = std::env::args().collect();
     let interactive = args.iter().any(|a| a == "-i" || a == "--interactive");
+    let presenting = args.iter().any(|a| a == "--hold");
+    // Status goes to its own descriptor so it never mixes with the terminal UI
+    let status_out = flag_value(&args, "--status-fd").map(|fd| {
+        let path = format!("/dev/fd/{}", fd);
+        OpenOptions::new().write(true).open(&path).unwrap_or_else(|e| {
+            eprintln!("termdemo: cannot open status fd {}: {}", fd, e);
+            std::process::exit(1);
+        })
+    });
+    // Whole-show post-processing, applied after any per-scene chain
+    let mut post = Vec::new();
+    if args.iter().any(|a| a == "--bloom") {
+        let threshold = flag_value(&args, "--bloom-threshold").map_or(0.6, |s| {
+            s.parse::<f64>()
+                .ok()
+                .filter(|v| (0.0..=1.0).contains(v))
+                .unwrap_or_else(|| {
+                    eprintln!("termdemo: invalid bloom threshold {:?} (expected 0 to 1)", s);
+                    std::process::exit(1);
+                })
+        });
+        let intensity = flag_value(&args, "--bloom-intensity").map_or(1.0, |s| {
+            s.parse::<f64>()
+                .ok()
+                .filter(|&v| v >= 0.0 && v.is_finite())
+                .unwrap_or_else(|| {
+                    eprintln!("termdemo: invalid bloom intensity {:?} (expected a non-negative strength)", s);
+                    std::process::exit(1);
+                })
+        });
+        post.push(PostEffect::Bloom(Bloom::new(threshold, intensity)));
+    }
+    if args.iter().any(|a| a == "--crt") {
+        post.push(PostEffect::Crt);
+    }
+    let theme_strength = flag_value(&args, "--theme-strength").map(|s| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|v| (0.0..=1.0).contains(v))
+            .unwrap_or_else(|| {
+                eprintln!("termdemo: invalid theme strength {:?} (expected 0 to 1)", s);
+                std::process::exit(1);
+            })
+    });
+    if let Some(s) = flag_value(&args, "--theme-hue") {
+        let hue = s.parse::<f64>().ok().filter(|h| h.is_finite()).unwrap_or_else(|| {
+            eprintln!("termdemo: invalid theme hue {:?} (expected degrees)", s);
+            std::process::exit(1);
+        });
+        post.push(PostEffect::Theme(Theme::new(hue, theme_strength.unwrap_or(0.6))));
+    } else if theme_strength.is_some() {
+        eprintln!("termdemo: ignoring --theme-strength without --theme-hue");
+    }
+    let depth = flag_value(&args, "--color").map_or(ColorDepth::TrueColor, |name| {
+        ColorDepth::from_name(name).unwrap_or_else(|| {
+            eprintln!("termdemo: unknown color depth {:?} (expected 16, 256 or truecolor)", name);
+            std::process::exit(1);
+        })
+    });
 
-    let seed = args
-        .iter()
-        .position(|a| a == "--seed")
-        .and_then(|i| args.get(i + 1))
-        .and_then(|s| s.parse::<u64>().ok());
+    let seed = flag_value(&args, "--seed").and_then(|s| s.parse::<u64>().ok());
+
+    let seed = match seed {
+        Some(seed) => seed,
+        // Benchmarks are compared across commits, so they default to a fixed seed
+        None if args.iter().any(|a| a == "--bench") => bench::DEFAULT_SEED,
+        None => {
+            let s: u64 = rand::random();
+            eprintln!("termdemo: seed {}", s);
+            s
+        }
+    };
 
-    let seed = seed.unwrap_or_else(|| {
-        let s: u64 = rand::random();
-        eprintln!("termdemo: seed {}", s);
-        s
+    let bpm = flag_value(&args, "--bpm").map(|s| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|&bpm| bpm > 0.0)
+            .unwrap_or_else(|| {
+                eprintln!("termdemo: invalid tempo {:?} (expected beats per minute)", s);
+                std::process::exit(1);
+            })
+    });
+
+    // The clicks follow the tempo over a recording's known length
+    let click_track = flag_value(&args, "--click-track");
+    if click_track.is_some() {
+        if bpm.is_none() {
+            eprintln!("termdemo: --click-track needs --bpm");
+            std::process::exit(1);
+        }
+        let recording = ["--record-gif", "--record-frames"]
+            .iter()
+            .any(|flag| flag_value(&args, flag).is_some());
+        if !recording {
+            eprintln!("termdemo: --click-track needs --record-gif or --record-frames");
+            std::process::exit(1);
+        }
+    }
+
+    let time_offset = flag_value(&args, "--time-offset").map_or(0.0, |s| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|&secs| secs >= 0.0 && secs.is_finite())
+            .unwrap_or_else(|| {
+                eprintln!("termdemo: invalid time offset {:?} (expected seconds)", s);
+                std::process::exit(1);
+            })
+    });
+    let duration_scale = flag_value(&args, "--duration-scale").map_or(1.0, |s| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|&scale| scale > 0.0 && scale.is_finite())
+            .unwrap_or_else(|| {
+                eprintln!("termdemo: invalid duration scale {:?} (expected a positive factor)", s);
+                std::process::exit(1);
+            })
     });
 
+    if args.iter().any(|a| a == "--bench") {
+        return bench(&args, seed);
+    }
+    if args.iter().any(|a| a == "--smoke-test") {
+        return smoke_test(&args, seed);
+    }
+
+    // Load assets before entering raw mode so errors print cleanly
+    let assets = Assets {
+        wall_texture: load_asset(&args, "--wall-texture", "wall texture", Texture::load_ppm),
+        tunnel_texture: load_asset(&args, "--tunnel-texture", "tunnel texture", Texture::load_ppm),
+        source_image: load_asset(&args, "--source-image", "source image", SourceImage::load),
+        map: load_asset(&args, "--map", "map", Map::load),
+        morph_target: load_asset(&args, "--morph-target", "morph target", morph::load_xyz),
+        scope_input: load_asset(&args, "--scope-input", "scope input", oscilloscope::load_samples),
+        message: flag_value(&args, "--message").map(str::to_string),
+        matrix_charset: flag_value(&args, "--matrix-charset").map(|name| {
+            Charset::from_name(name).unwrap_or_else(|| {
+                eprintln!(
+                    "termdemo: unknown matrix charset {:?} (expected katakana, ascii or binary)",
+                    name
+                );
+                std::process::exit(1);
+            })
+        }),
+        pixelsort_source: flag_value(&args, "--pixelsort-source").map(|name| {
+            effects::create(name).unwrap_or_else(|| {
+                eprintln!("termdemo: unknown pixel sort source {:?}; valid effects are:", name);
+                for name in effects::names() {
+                    eprintln!("  {}", name);
+                }
+                std::process::exit(1);
+            })
+        }),
+        lsystem_rule: flag_value(&args, "--lsystem-rule").map(|spec| {
+            LSystem::new().with_rule(spec).unwrap_or_else(|e| {
+                eprintln!("termdemo: invalid L-system rule {:?}: {}", spec, e);
+                std::process::exit(1);
+            })
+        }),
+    };
+
+    let playlist = load_asset(&args, "--playlist", "playlist", playlist::load);
+    if playlist.is_some() {
+        let ignored: Vec<&str> = ASSET_FLAGS
+            .iter()
+            .copied()
+            .filter(|flag| flag_value(&args, flag).is_some())
+            .collect();
+        if !ignored.is_empty() {
+            eprintln!(
+                "termdemo: ignoring {} with --playlist (they customize the built-in show)",
+                ignored.join(", ")
+            );
+        }
+    }
+    let mut scenes = playlist.unwrap_or_else(|| build_scenes(assets));
+    let keep_finale = args.iter().any(|a| a == "--shuffle-keep-finale");
+    if keep_finale || args.iter().any(|a| a == "--shuffle") {
+        shuffle_scenes(&mut scenes, seed, keep_finale);
+    }
+    if let Some(script) = load_asset(&args, "--script", "script", Scripted::load) {
+        let mut scene = Scene::new(Box::new(script));
+        scene.duration = None;
+        scenes = vec![scene];
+    } else if let Some(spec) = flag_value(&args, "--compose") {
+        scenes = vec![compose_scene(spec)];
+    } else if let Some(name) = flag_value(&args, "--effect") {
+        scenes = vec![single_effect_scene(scenes, name)];
+    }
+    if let Some(path) = load_asset(&args, "--camera-path", "camera path", CameraPath::load) {
+        for scene in &mut scenes {
+            scene.effect.set_camera_path(path.clone());
+        }
+    }
+
+    if let Some(path) = flag_value(&args, "--pipe") {
+        let size = size_arg(&args);
+        let fps = flag_value(&args, "--fps")
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|&f| f > 0.0)
+            .unwrap_or(30.0);
+        let duration = flag_value(&args, "--duration")
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|&d| d > 0.0);
+        let seq = sequencer(scenes, true, seed, bpm, duration_scale, time_offset);
+        return pipe_frames(Path::new(path), seq, size, fps, duration);
+    }
+
+    if let Some(path) = flag_value(&args, "--record-gif") {
+        let (width, height) = size_arg(&args);
+        let fps = flag_value(&args, "--fps")
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|&f| f > 0.0)
+            .unwrap_or(20.0);
+        let duration = flag_value(&args, "--duration")
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|&d| d > 0.0);
+        let seq = sequencer(scenes, false, seed, bpm, duration_scale, time_offset);
+        let secs = record_gif(Path::new(path), seq, (width, height), fps, duration)?;
+        return write_click_track(click_track, bpm, secs);
+    }
+
+    if let Some(dir) = flag_value(&args, "--record-frames") {
+        let size = size_arg(&args);
+        let fps = flag_value(&args, "--fps")
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|&f| f > 0.0)
+            .unwrap_or(30.0);
+        let duration = flag_value(&args, "--duration")
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|&d| d > 0.0);
+        let seq = sequencer(scenes, false, seed, bpm, duration_scale, time_offset);
+        let secs = record_frames(Path::new(dir), seq, size, fps, duration)?;
+        return write_click_track(click_track, bpm, secs);
+    }
+
+    warn_degraded(&scenes, depth);
+
+    // Stress test: no frame pacing, stop after `--frames` and report the rate
+    let mut rates = if args.iter().any(|a| a == "--uncapped") {
+        let frames = flag_value(&args, "--frames")
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(600);
+        Some(bench::FrameRates::new(frames))
+    } else {
+        None
+    };
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -111,215 +380,569 @@ fn main() -> io::Result<()> {
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
-    let result = run(&mut terminal, interactive, seed);
+    // Interactive mode stops at the last scene instead of looping
+    let seq = sequencer(scenes, !interactive, seed, bpm, duration_scale, time_offset);
+    let mode = if interactive {
+        Mode::Interactive
+    } else if presenting {
+        Mode::Presentation
+    } else {
+        Mode::AutoPlay
+    };
+    let mut app = App::new(seq, mode);
+    app.post = post;
+    app.status_out = status_out;
+    app.show_border = args.iter().any(|a| a == "--fullscreen-border");
+    let result = run(&mut terminal, app, depth, rates.as_mut());
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableMouseCapture, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
-    result
+    result?;
+    match rates {
+        Some(rates) => bench::write_frame_rates(&mut io::stdout(), &rates),
+        None => Ok(()),
+    }
+}
+
+/// Name the scenes that need more colors than the terminal was given, in
+/// one line before the show takes over the screen.
+fn warn_degraded(scenes: &[Scene], depth: ColorDepth) {
+    let names: Vec<&str> = scenes
+        .iter()
+        .filter(|scene| scene.effect.min_color_depth() > depth)
+        .map(|scene| scene.effect.name())
+        .collect();
+    if !names.is_empty() {
+        eprintln!(
+            "termdemo: {} will look blocky in {} mode",
+            names.join(", "),
+            depth.label()
+        );
+    }
+}
+
+/// The show's sequencer, on a beat grid when a tempo was given.
+fn sequencer(
+    scenes: Vec<Scene>,
+    looping: bool,
+    seed: u64,
+    bpm: Option<f64>,
+    duration_scale: f64,
+    time_offset: f64,
+) -> Sequencer {
+    let seq = Sequencer::new(scenes, looping, seed)
+        .with_duration_scale(duration_scale)
+        .with_time_offset(time_offset);
+    match bpm {
+        Some(bpm) => seq.with_bpm(bpm),
+        None => seq,
+    }
+}
+
+/// Value following `flag` on the command line, e.g. `--seed 42`.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+/// Load the file named by `flag`, exiting with a message if it can't be read.
+fn load_asset<T>(
+    args: &[String],
+    flag: &str,
+    what: &str,
+    load: impl Fn(&Path) -> io::Result<T>,
+) -> Option<T> {
+    let path = flag_value(args, flag)?;
+    match load(Path::new(path)) {
+        Ok(asset) => Some(asset),
+        Err(e) => {
+            eprintln!("termdemo: cannot load {} {}: {}", what, path, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Parse a `WxH` size such as `160x100`.
+fn parse_size(spec: &str) -> Option<(u32, u32)> {
+    let (w, h) = spec.split_once('x')?;
+    let w = w.parse::<u32>().ok().filter(|&w| w > 0 && w <= u16::MAX as u32)?;
+    let h = h.parse::<u32>().ok().filter(|&h| h > 0 && h <= u16::MAX as u32)?;
+    Some((w, h))
+}
+
+/// Offscreen size from `--size`, defaulting to 160x100.
+fn size_arg(args: &[String]) -> (u32, u32) {
+    match flag_value(args, "--size") {
+        Some(spec) => parse_size(spec).unwrap_or_else(|| {
+            eprintln!("termdemo: invalid --size {:?} (expected WxH)", spec);
+            std::process::exit(1);
+        }),
+        None => (160, 100),
+    }
+}
+
+/// Every registered effect, or just the one named by `--effect`.
+fn effect_names(args: &[String]) -> Vec<&'static str> {
+    match flag_value(args, "--effect") {
+        Some(name) => {
+            let key = effects::normalize_name(name);
+            let found: Vec<_> = effects::names()
+                .filter(|entry| effects::normalize_name(entry) == key)
+                .collect();
+            if found.is_empty() {
+                eprintln!("termdemo: unknown effect {:?}; valid effects are:", name);
+                for name in effects::names() {
+                    eprintln!("  {}", name);
+                }
+                std::process::exit(1);
+            }
+            found
+        }
+        None => effects::names().collect(),
+    }
+}
+
+/// Time every registered effect (or just `--effect`) and print the results.
+fn bench(args: &[String], seed: u64) -> io::Result<()> {
+    let (width, height) = size_arg(args);
+    let frames = flag_value(args, "--frames")
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(120);
+    let names = effect_names(args);
+
+    let timings = bench::run(&names, width, height, frames, seed);
+    let mut out = io::stdout();
+    if args.iter().any(|a| a == "--json") {
+        bench::write_json(&mut out, &timings)
+    } else {
+        writeln!(out, "{} frames at {}x{}\n", frames, width, height)?;
+        bench::write_table(&mut out, &timings)
+    }
+}
+
+/// Run every registered effect (or just `--effect`) at awkward sizes and
+/// exit non-zero naming any that panicked or went non-finite.
+fn smoke_test(args: &[String], seed: u64) -> io::Result<()> {
+    let names = effect_names(args);
+    let failures = smoke::run(&names, seed);
+    for failure in &failures {
+        let (width, height) = failure.size;
+        eprintln!(
+            "termdemo: {} failed at {}x{}: {}",
+            failure.name, width, height, failure.problem
+        );
+    }
+    println!(
+        "smoke test: {} effect{} at {} sizes, {} failed",
+        names.len(),
+        if names.len() == 1 { "" } else { "s" },
+        smoke::SIZES.len(),
+        failures.len()
+    );
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The scene for `name` from the show (keeping any customization), falling
+/// back to a default instance; it plays until quit rather than advancing.
+fn single_effect_scene(scenes: Vec<Scene>, name: &str) -> Scene {
+    let key = effects::normalize_name(name);
+    let found = scenes
+        .into_iter()
+        .find(|scene| effects::normalize_name(scene.effect.name()) == key);
+    let mut scene = match found.or_else(|| effects::create(name).map(Scene::new)) {
+        Some(scene) => scene,
+        None => {
+            eprintln!("termdemo: unknown effect {:?}; valid effects are:", name);
+            for name in effects::names() {
+                eprintln!("  {}", name);
+            }
+            std::process::exit(1);
+        }
+    };
+    scene.duration = None;
+    scene
+}
+
+/// A lone scene blending two effects from a spec like `plasma,matrix,screen`;
+/// the blend mode defaults to screen. Like `single_effect_scene`, it plays
+/// until quit rather than advancing.
+fn compose_scene(spec: &str) -> Scene {
+    let parts: Vec<&str> = spec.split(',').map(str::trim).collect();
+    let mode = match parts.get(2) {
+        Some(name) => BlendMode::from_name(name),
+        None => Some(BlendMode::Screen),
+    };
+    let effect = |name: &str| {
+        effects::create(name).unwrap_or_else(|| {
+            eprintln!("termdemo: unknown effect {:?}; valid effects are:", name);
+            for name in effects::names() {
+                eprintln!("  {}", name);
+            }
+            std::process::exit(1);
+        })
+    };
+    let mut scene = match (parts.len(), mode) {
+        (2..=3, Some(mode)) => Scene::new(Box::new(Compose::new(
+            effect(parts[0]),
+            effect(parts[1]),
+            mode,
+        ))),
+        _ => {
+            eprintln!(
+                "termdemo: invalid --compose {:?} (expected A,B[,MODE]; modes are add, \
+                 multiply, screen and alpha)",
+                spec
+            );
+            std::process::exit(1);
+        }
+    };
+    scene.duration = None;
+    scene
+}
+
+/// Render the show offscreen into an animated GIF.
+///
+/// A lone effect with a loop period is recorded for exactly one period so the
+/// GIF repeats seamlessly, unless `duration` is given. Returns the seconds
+/// recorded.
+fn record_gif(
+    path: &Path,
+    mut seq: Sequencer,
+    (width, height): (u32, u32),
+    fps: f64,
+    duration: Option<f64>,
+) -> io::Result<f64> {
+    let period = match seq.scenes.as_slice() {
+        [scene] => scene.effect.loop_period(),
+        _ => None,
+    };
+    let (frames, dt) = record::frame_plan(period, duration, fps);
+
+    let file = BufWriter::new(File::create(path)?);
+    let mut gif = GifEncoder::new(file, width as u16, height as u16, 1.0 / dt)?;
+    record::record(&mut seq, width, height, frames, dt, |pixels| {
+        gif.write_frame(pixels)
+    })?;
+    gif.finish()?;
+    eprintln!(
+        "termdemo: wrote {} frames ({:.1}s) to {}",
+        frames,
+        frames as f64 * dt,
+        path.display()
+    );
+    Ok(frames as f64 * dt)
+}
+
+/// Render the show offscreen into `dir` as numbered binary PPMs
+/// (`frame_00001.ppm`, ...), for stitching with ffmpeg. Lengths follow
+/// `record_gif`, and so does the return value.
+fn record_frames(
+    dir: &Path,
+    mut seq: Sequencer,
+    (width, height): (u32, u32),
+    fps: f64,
+    duration: Option<f64>,
+) -> io::Result<f64> {
+    let period = match seq.scenes.as_slice() {
+        [scene] => scene.effect.loop_period(),
+        _ => None,
+    };
+    let (frames, dt) = record::frame_plan(period, duration, fps);
+
+    fs::create_dir_all(dir)?;
+    let mut number = 0;
+    record::record(&mut seq, width, height, frames, dt, |pixels| {
+        number += 1;
+        let path = dir.join(format!("frame_{:05}.ppm", number));
+        let mut out = BufWriter::new(File::create(path)?);
+        record::write_ppm(&mut out, width, height, pixels)?;
+        out.flush()
+    })?;
+    eprintln!(
+        "termdemo: wrote {} frames ({:.1}s at {:.2} fps) to {}",
+        frames,
+        frames as f64 * dt,
+        1.0 / dt,
+        dir.display()
+    );
+    Ok(frames as f64 * dt)
+}
+
+/// Write the metronome for `secs` of recording to `path`, when one was
+/// asked for with `--click-track`.
+fn write_click_track(path: Option<&str>, bpm: Option<f64>, secs: f64) -> io::Result<()> {
+    let (path, bpm) = match (path, bpm) {
+        (Some(path), Some(bpm)) => (path, bpm),
+        _ => return Ok(()),
+    };
+    let mut out = BufWriter::new(File::create(path)?);
+    click::write_click_track(&mut out, bpm, secs)?;
+    out.flush()?;
+    eprintln!("termdemo: wrote a {} bpm click track ({:.1}s) to {}", bpm, secs, path);
+    Ok(())
+}
+
+/// Stream the show as concatenated binary PPM frames to `path` (typically a
+/// FIFO) in real time, until the reader goes away or `duration` runs out.
+fn pipe_frames(
+    path: &Path,
+    mut seq: Sequencer,
+    (width, height): (u32, u32),
+    fps: f64,
+    duration: Option<f64>,
+) -> io::Result<()> {
+    let frames = duration.map_or(usize::MAX, |secs| (secs * fps).round().max(1.0) as usize);
+    let dt = 1.0 / fps;
+    let frame_time = Duration::from_secs_f64(dt);
+
+    // Opening a FIFO blocks until a reader connects
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut next = std::time::Instant::now();
+    let result = record::record(&mut seq, width, height, frames, dt, |pixels| {
+        record::write_ppm(&mut out, width, height, pixels)?;
+        out.flush()?;
+        next += frame_time;
+        if let Some(wait) = next.checked_duration_since(std::time::Instant::now()) {
+            std::thread::sleep(wait);
+        }
+        Ok(())
+    });
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other,
+    }
+}
+
+/// User-supplied resources that customize the built-in scenes.
+/// Flags that fill in `Assets`, which only `build_scenes` applies.
+const ASSET_FLAGS: [&str; 10] = [
+    "--wall-texture",
+    "--tunnel-texture",
+    "--source-image",
+    "--map",
+    "--morph-target",
+    "--scope-input",
+    "--message",
+    "--matrix-charset",
+    "--pixelsort-source",
+    "--lsystem-rule",
+];
+
+struct Assets {
+    wall_texture: Option<Texture>,
+    tunnel_texture: Option<Texture>,
+    source_image: Option<SourceImage>,
+    map: Option<Map>,
+    morph_target: Option<Vec<[f64; 3]>>,
+    scope_input: Option<Samples>,
+    message: Option<String>,
+    matrix_charset: Option<Charset>,
+    pixelsort_source: Option<Box<dyn Effect>>,
+    lsystem_rule: Option<LSystem>,
+}
+
+/// Number of closing scenes in `build_scenes` (fireworks, scroller, outro plasma).
+const FINALE_SCENES: usize = 3;
+
+/// Permute the show using the run's seed, optionally leaving the finale last.
+fn shuffle_scenes(scenes: &mut [Scene], seed: u64, keep_finale: bool) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let end = if keep_finale {
+        scenes.len().saturating_sub(FINALE_SCENES)
+    } else {
+        scenes.len()
+    };
+    scenes[..end].shuffle(&mut rng);
 }
 
-fn build_scenes() -> Vec<Scene> {
+fn build_scenes(assets: Assets) -> Vec<Scene> {
+    let mut wolfenstein = Wolfenstein::new();
+    if let Some(tex) = assets.wall_texture {
+        wolfenstein = wolfenstein.with_texture(tex);
+    }
+    if let Some(map) = assets.map {
+        wolfenstein = wolfenstein.with_map(map);
+    }
+    let mut tunnel = Tunnel::new();
+    if let Some(tex) = assets.tunnel_texture {
+        tunnel = tunnel.with_texture(tex);
+    }
+    let mut kaleidoscope = Kaleidoscope::new();
+    let mut rotozoom = Rotozoom::new();
+    let mut lens = Lens::new();
+    if let Some(image) = assets.source_image {
+        kaleidoscope = kaleidoscope.with_image(image.clone());
+        rotozoom = rotozoom.with_image(image.clone());
+        lens = lens.with_image(image);
+    }
+    let mut matrix = Matrix::new();
+    if let Some(charset) = assets.matrix_charset {
+        matrix = matrix.with_charset(charset);
+    }
+    let mut morph = Morph::new();
+    if let Some(points) = assets.morph_target {
+        morph = morph.with_target(points);
+    }
+    let mut oscilloscope = Oscilloscope::new();
+    if let Some(samples) = assets.scope_input {
+        oscilloscope = oscilloscope.with_samples(samples);
+    }
+    let pixel_sort = match assets.pixelsort_source {
+        Some(source) => PixelSort::wrapping(source),
+        None => PixelSort::new(),
+    };
+    let lsystem = assets.lsystem_rule.unwrap_or_else(LSystem::new);
+    let mut sine_scroller = SineScroller::new();
+    let mut finale_text =
+        "63 EFFECTS IN YOUR TERMINAL *** TERMDEMO *** GREETS TO ALL DEMOSCENERS!   ".to_string();
+    if let Some(message) = assets.message {
+        finale_text = format!("{}   ", message);
+        sine_scroller = sine_scroller.with_text(&finale_text);
+    }
+
     vec![
         // ACT 1 — Classic Patterns
         Scene::new(Box::new(Plasma::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Moire::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(Kaleidoscope::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(kaleidoscope))
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Shadebobs::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(CopperBars::new()))
-            .with_duration(10.0)
             .with_transition(TransitionKind::WipeDown, 1.5),
         Scene::new(Box::new(RasterBars::new()))
-            .with_duration(10.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(CopperFlag::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(KefrensBars::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Truchet::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Interference::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         // ACT 2 — Heat & Motion
         Scene::new(Box::new(Fire::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::WipeDown, 1.5),
         Scene::new(Box::new(Twister::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(Tunnel::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(tunnel))
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(DotTunnel::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(Rotozoom::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(rotozoom))
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Lightning::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(LavaLamp::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         // ACT 3 — 3D Geometry
         Scene::new(Box::new(Starfield::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(Galaxy::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(DotSphere::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(BoingBall::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(FilledVector::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(Morph::new()))
-            .with_duration(14.0)
+        Scene::new(Box::new(morph))
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Glenz::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Lissajous3D::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(TorusKnot::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(Wireframe::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(CubeField::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Fade, 1.5),
-        Scene::new(Box::new(Wolfenstein::new()))
-            .with_duration(14.0)
+        Scene::new(Box::new(wolfenstein))
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(Raymarcher::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(Terrain::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(VoxelLandscape::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         // ACT 4 — Fractals
         Scene::new(Box::new(Mandelbrot::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(Julia::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(FractalZoom::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         Scene::new(Box::new(Sierpinski::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
         // ACT 5 — Simulations
         Scene::new(Box::new(Metaballs::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(Voronoi::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(ReactionDiffusion::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(FluidSim::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(ClothSim::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Water::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Fountain::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(Boids::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(CellularAutomata::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(GameOfLife::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         // ACT 6 — Natural / Atmospheric
         Scene::new(Box::new(Aurora::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Fade, 2.0),
         Scene::new(Box::new(Rain::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Snowfall::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Parallax::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 2.0),
-        Scene::new(Box::new(LSystem::new()))
-            .with_duration(14.0)
+        Scene::new(Box::new(lsystem))
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Neon::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Fade, 1.5),
         // ACT 7 — Retro / Text
-        Scene::new(Box::new(Lens::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(lens))
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(BumpMapping::new()))
-            .with_duration(12.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(SineScroller::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(sine_scroller))
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(Oscilloscope::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(oscilloscope))
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(PendulumWave::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
         Scene::new(Box::new(Spirograph::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Fade, 1.5),
         Scene::new(Box::new(FlowField::new()))
-            .with_duration(14.0)
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(PixelSort::new()))
-            .with_duration(12.0)
+        Scene::new(Box::new(pixel_sort))
             .with_transition(TransitionKind::Dissolve, 1.5),
-        Scene::new(Box::new(Matrix::new()))
-            .with_duration(14.0)
+        Scene::new(Box::new(matrix))
             .with_transition(TransitionKind::Fade, 2.0),
         // FINALE
         Scene::new(Box::new(Fireworks::new()))
-            .with_duration(14.0)
-            .with_transition(TransitionKind::Fade, 2.0),
-        Scene::new(Box::new(Scroller::new(
-            "63 EFFECTS IN YOUR TERMINAL *** TERMDEMO *** GREETS TO ALL DEMOSCENERS!   ",
-        )))
-            .with_duration(16.0)
+            .with_transition(TransitionKind::Fade, 2.0)
+            .with_post(vec![PostEffect::Bloom(Bloom::new(0.3, 3.0))]),
+        Scene::new(Box::new(Scroller::new(&finale_text)))
             .with_transition(TransitionKind::WipeLeft, 2.0),
         Scene::new(Box::new(Plasma::with_params(0.6, 2.5)))
             .with_duration(8.0)
@@ -327,34 +950,55 @@ fn build_scenes() -> Vec<Scene> {
     ]
 }
 
-fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, interactive: bool, seed: u64) -> io::Result<()> {
-    let mode = if interactive {
-        Mode::Interactive
-    } else {
-        Mode::AutoPlay
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut app: App,
+    depth: ColorDepth,
+    mut uncapped: Option<&mut bench::FrameRates>,
+) -> io::Result<()> {
+    // Effects render only inside the border, when it's shown
+    let effect_area = |area: Rect, border: bool| {
+        if border {
+            BorderWidget::inner(area)
+        } else {
+            area
+        }
     };
 
-    let scenes = build_scenes();
-    let seq = Sequencer::new(scenes, mode == Mode::AutoPlay, seed);
-    let mut app = App::new(seq, mode);
-
-    let size = terminal.size()?;
+    let size = effect_area(terminal.size()?, app.show_border);
     let fb_width = size.width as u32;
     let fb_height = (size.height as u32) * 2;
     app.init(fb_width, fb_height);
 
     let target_frame = Duration::from_secs_f64(1.0 / 60.0);
+    // Falling further behind than this drops the backlog instead of racing
+    let max_lag = target_frame * 4;
+    let mut deadline = std::time::Instant::now();
+    // Overlays were on screen last frame, so hiding them needs a redraw
+    let mut overlays_drawn = false;
+    // Mouse capture follows the mode so autoplay leaves text selection alone
+    let mut mouse_captured = false;
 
     loop {
         let frame_start = std::time::Instant::now();
-
         app.handle_input()?;
         if app.should_quit {
             return Ok(());
         }
 
-        // Handle resize (guard against zero-size)
-        let new_size = terminal.size()?;
+        let interactive = app.mode == Mode::Interactive;
+        if interactive != mouse_captured {
+            if interactive {
+                execute!(terminal.backend_mut(), EnableMouseCapture)?;
+            } else {
+                execute!(terminal.backend_mut(), DisableMouseCapture)?;
+            }
+            mouse_captured = interactive;
+        }
+
+        // Handle resize, including the border coming or going (guard
+        // against zero-size)
+        let new_size = effect_area(terminal.size()?, app.show_border);
         let new_w = new_size.width as u32;
         let new_h = (new_size.height as u32) * 2;
         if new_w > 0 && new_h > 0 && (new_w != app.fb.width || new_h != app.fb.height) {
@@ -365,19 +1009,54 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, interactive: bool,
             app.update();
 
             let show_hud = app.show_hud;
-            terminal.draw(|frame| {
-                let area = frame.size();
-                frame.render_widget(HalfBlockWidget { framebuffer: &app.fb }, area);
-                if show_hud {
-                    frame.render_widget(HudWidget { app: &app }, area);
-                }
-            })?;
+            let show_border = app.show_border;
+            let overlays = show_hud || app.show_help || app.message_input.is_some();
+            // An unchanged frame with no overlays leaves the terminal as it is,
+            // except under --uncapped, which measures the full draw every frame
+            if app.output_changed() || overlays || overlays_drawn || uncapped.is_some() {
+                terminal.draw(|frame| {
+                    let area = effect_area(frame.size(), show_border);
+                    if show_border {
+                        let title = app.sequencer.current_scene_name();
+                        frame.render_widget(BorderWidget { title }, frame.size());
+                    }
+                    frame.render_widget(
+                        HalfBlockWidget {
+                            framebuffer: app.output(),
+                            depth,
+                        },
+                        area,
+                    );
+                    if show_hud {
+                        frame.render_widget(HudWidget { app: &app }, area);
+                    }
+                    if app.show_help {
+                        frame.render_widget(HelpWidget { scroll: app.help_scroll }, area);
+                    }
+                    if let Some(text) = app.message_input.as_deref() {
+                        frame.render_widget(MessagePromptWidget { text }, area);
+                    }
+                })?;
+            }
+            overlays_drawn = overlays;
+        }
+
+        if let Some(rates) = uncapped.as_deref_mut() {
+            rates.record(app.sequencer.current_scene_name(), frame_start.elapsed());
+            if rates.total_frames() >= rates.limit {
+                return Ok(());
+            }
+            continue;
         }
 
-        // Frame pacing
-        let elapsed = frame_start.elapsed();
-        if elapsed < target_frame {
-            std::thread::sleep(target_frame - elapsed);
+        // Frame pacing: each deadline follows the previous one, so a late
+        // frame is made up by starting the next one straight away
+        deadline += target_frame;
+        let now = std::time::Instant::now();
+        if deadline > now {
+            std::thread::sleep(deadline - now);
+        } else if now - deadline > max_lag {
+            deadline = now;
         }
     }
 }
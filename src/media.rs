@@ -0,0 +1,73 @@
+//! Still images that stand in for an effect's procedural pattern.
+//!
+//! The image is centered on the frame and scaled to fit inside it whole,
+//! whatever its aspect. Sampling is bilinear and wraps at the edges by
+//! default, so an effect that zooms out sees the picture tile rather than a
+//! black border.
+
+use std::io;
+use std::path::Path;
+
+use crate::texture::{Edge, Texture};
+
+#[derive(Clone)]
+pub struct SourceImage {
+    texture: Texture,
+    /// Image pixels per frame pixel, set by `fit`
+    scale: f64,
+}
+
+impl SourceImage {
+    /// Load a binary (P6) or ASCII (P3) PPM image.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Texture::load_ppm(path).map(|texture| Self {
+            texture,
+            scale: 1.0,
+        })
+    }
+
+    /// Scale the image to fit a `width` x `height` frame, keeping its aspect.
+    pub fn fit(&mut self, width: u32, height: u32) {
+        let sx = self.texture.width as f64 / width.max(1) as f64;
+        let sy = self.texture.height as f64 / height.max(1) as f64;
+        self.scale = sx.max(sy);
+    }
+
+    /// Color at `(x, y)` frame pixels from the frame center.
+    pub fn sample(&self, x: f64, y: f64) -> (u8, u8, u8) {
+        self.sample_edge(x, y, Edge::Wrap)
+    }
+
+    /// `sample`, handling the area beyond the picture as `edge` says.
+    pub fn sample_edge(&self, x: f64, y: f64, edge: Edge) -> (u8, u8, u8) {
+        // Texel centers sit at half-pixel offsets
+        let u = x * self.scale + self.texture.width as f64 / 2.0 - 0.5;
+        let v = y * self.scale + self.texture.height as f64 / 2.0 - 0.5;
+        let (x0, y0) = (u.floor(), v.floor());
+        let (fx, fy) = (u - x0, v - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let lerp = |a: (u8, u8, u8), b: (u8, u8, u8), f: f64| {
+            (
+                a.0 as f64 + (b.0 as f64 - a.0 as f64) * f,
+                a.1 as f64 + (b.1 as f64 - a.1 as f64) * f,
+                a.2 as f64 + (b.2 as f64 - a.2 as f64) * f,
+            )
+        };
+        let top = lerp(
+            self.texture.texel_edge(x0, y0, edge),
+            self.texture.texel_edge(x0 + 1, y0, edge),
+            fx,
+        );
+        let bottom = lerp(
+            self.texture.texel_edge(x0, y0 + 1, edge),
+            self.texture.texel_edge(x0 + 1, y0 + 1, edge),
+            fx,
+        );
+        (
+            (top.0 + (bottom.0 - top.0) * fy).round() as u8,
+            (top.1 + (bottom.1 - top.1) * fy).round() as u8,
+            (top.2 + (bottom.2 - top.2) * fy).round() as u8,
+        )
+    }
+}
@@ -0,0 +1,127 @@
+//! Seedable value noise: random values on an integer lattice, blended
+//! smoothly between lattice points, and summed over octaves for fractal
+//! detail. The third axis is usually time, so the pattern can flow.
+
+use crate::rng::Hash;
+
+/// Seed shared by the landscape effects. Tiling noise wraps its lattice,
+/// so a tiling and a non-tiling field from this seed still differ.
+pub const TERRAIN_SEED: u32 = 0x7E44_A15D;
+
+/// Smooth field in about -1..1 from three layered sines, with features
+/// around a hundred units across, drifting as `t` advances. Much cheaper
+/// than `ValueNoise` where a wind only needs to wander.
+pub fn sine_field(x: f64, y: f64, t: f64) -> f64 {
+    let v1 = (x * 0.04 + t * 0.9).sin() * (y * 0.03 - t * 0.6).cos();
+    let v2 = (x * 0.025 - y * 0.02 + t * 0.5).sin();
+    let v3 = ((x + y) * 0.06 + t * 1.3).cos() * 0.5;
+    (v1 + v2 + v3) / 2.5
+}
+
+/// Lattice noise for one seed; two instances with the same seed agree.
+#[derive(Clone, Copy)]
+pub struct ValueNoise {
+    hash: Hash,
+    /// Lattice cells after which x and y repeat, for maps that wrap
+    period: Option<i32>,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            hash: Hash::new(seed),
+            period: None,
+        }
+    }
+
+    /// Noise that repeats every `period` units along x and y, at every
+    /// octave, so it can fill a wrapping map without a seam.
+    pub fn tiling(seed: u32, period: u32) -> Self {
+        Self {
+            hash: Hash::new(seed),
+            period: Some(period.max(1) as i32),
+        }
+    }
+
+    /// Bilinear blend of the random values in -1..1 at the lattice points
+    /// around cell (i, j) in plane `k`, at faded offsets (u, v).
+    fn plane(&self, (i, j, k): (i32, i32, i32), u: f64, v: f64, period: Option<i32>) -> f64 {
+        // Wrap once per cell rather than per corner; the far corner of the
+        // last cell is the first one again
+        let ((i0, i1), (j0, j1)) = match period {
+            Some(p) => {
+                let wrap = |n: i32| {
+                    let n = n.rem_euclid(p);
+                    (n, if n + 1 == p { 0 } else { n + 1 })
+                };
+                (wrap(i), wrap(j))
+            }
+            None => ((i, i + 1), (j, j + 1)),
+        };
+        let corner = |x: i32, y: i32| self.hash.lattice_f(x, y, k);
+        let bottom = lerp(corner(i0, j0), corner(i1, j0), u);
+        let top = lerp(corner(i0, j1), corner(i1, j1), u);
+        lerp(bottom, top, v)
+    }
+
+    /// Noise in -1..1, continuous with a continuous slope everywhere.
+    fn sample(&self, x: f64, y: f64, z: f64, period: Option<i32>) -> f64 {
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (i, j, k) = (x0 as i32, y0 as i32, z0 as i32);
+        let (u, v, w) = (fade(x - x0), fade(y - y0), fade(z - z0));
+        let below = self.plane((i, j, k), u, v, period);
+        let above = self.plane((i, j, k + 1), u, v, period);
+        lerp(below, above, w)
+    }
+
+    /// `sample` at z = 0, where the blend toward the next plane vanishes,
+    /// reading four lattice points instead of eight.
+    fn sample2(&self, x: f64, y: f64, period: Option<i32>) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (u, v) = (fade(x - x0), fade(y - y0));
+        self.plane((x0 as i32, y0 as i32, 0), u, v, period)
+    }
+
+    /// Sum of `octaves` layers, each at twice the frequency and half the
+    /// weight of the last, normalized back to -1..1. `layer` samples one
+    /// octave given its frequency, offset and lattice period.
+    fn octaves(&self, octaves: u32, mut layer: impl FnMut(f64, f64, Option<i32>) -> f64) -> f64 {
+        let mut sum = 0.0;
+        let mut weight = 1.0;
+        let mut total = 0.0;
+        let mut freq = 1;
+        for octave in 0..octaves.max(1) {
+            // Offsetting each octave keeps their lattice points from lining up
+            let shift = octave as f64 * 17.31;
+            let period = self.period.map(|p| p.saturating_mul(freq));
+            sum += layer(freq as f64, shift, period) * weight;
+            total += weight;
+            weight *= 0.5;
+            freq = freq.saturating_mul(2);
+        }
+        sum / total
+    }
+
+    /// Fractal sum of `octaves` layers of noise.
+    pub fn fbm(&self, x: f64, y: f64, z: f64, octaves: u32) -> f64 {
+        self.octaves(octaves, |f, shift, period| {
+            self.sample(x * f + shift, y * f - shift, z * f, period)
+        })
+    }
+
+    /// `fbm` over a still 2D plane, for heightfields and textures.
+    pub fn fbm2(&self, x: f64, y: f64, octaves: u32) -> f64 {
+        self.octaves(octaves, |f, shift, period| {
+            self.sample2(x * f + shift, y * f - shift, period)
+        })
+    }
+}
+
+/// Quintic fade: no visible creases along the lattice lines.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
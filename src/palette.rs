@@ -0,0 +1,39 @@
+//! 256-entry color lookup tables built from a few control points, for
+//! effects that map a scalar (heat, iteration count, height) to color.
+
+/// `(index, color)` control points for `Palette::from_stops`.
+pub type Stops = [(usize, (u8, u8, u8))];
+
+pub struct Palette {
+    colors: [(u8, u8, u8); 256],
+}
+
+impl Palette {
+    /// Linear ramps between `(index, color)` stops, which must start at 0,
+    /// end at 255 and be in increasing order.
+    pub fn from_stops(stops: &Stops) -> Self {
+        let mut colors = [(0u8, 0u8, 0u8); 256];
+        for window in stops.windows(2) {
+            let (i0, c0) = window[0];
+            let (i1, c1) = window[1];
+            for (i, color) in colors.iter_mut().enumerate().take(i1 + 1).skip(i0) {
+                let t = if i1 == i0 {
+                    0.0
+                } else {
+                    (i - i0) as f64 / (i1 - i0) as f64
+                };
+                *color = (
+                    (c0.0 as f64 + (c1.0 as f64 - c0.0 as f64) * t) as u8,
+                    (c0.1 as f64 + (c1.1 as f64 - c0.1 as f64) * t) as u8,
+                    (c0.2 as f64 + (c1.2 as f64 - c0.2 as f64) * t) as u8,
+                );
+            }
+        }
+        Self { colors }
+    }
+
+    /// Color for `v` in 0..1, clamping values outside.
+    pub fn lookup(&self, v: f64) -> (u8, u8, u8) {
+        self.colors[(v.clamp(0.0, 1.0) * 255.0) as usize]
+    }
+}
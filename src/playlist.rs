@@ -0,0 +1,142 @@
+//! Custom shows loaded from a TOML playlist instead of `build_scenes()`.
+//!
+//! ```toml
+//! [[scene]]
+//! effect = "Plasma"
+//! duration = 10
+//! transition = "Dissolve"
+//! transition_secs = 1.5
+//! easing = "InOutCubic"
+//! params = { speed = 2.0 }
+//! seed = 42
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use toml::{Table, Value};
+
+use crate::effects;
+use crate::scene::Scene;
+use crate::transition::{Easing, TransitionKind};
+
+pub fn load(path: &Path) -> io::Result<Vec<Scene>> {
+    let text = fs::read_to_string(path)?;
+    parse(&text).map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+}
+
+fn parse(text: &str) -> Result<Vec<Scene>, String> {
+    let doc: Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+    let entries = match doc.get("scene") {
+        Some(Value::Array(entries)) if !entries.is_empty() => entries,
+        _ => return Err("no [[scene]] entries".to_string()),
+    };
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let table = entry
+                .as_table()
+                .ok_or_else(|| format!("scene {}: expected a table", i + 1))?;
+            parse_scene(table).map_err(|msg| format!("scene {}: {}", i + 1, msg))
+        })
+        .collect()
+}
+
+fn parse_scene(table: &Table) -> Result<Scene, String> {
+    let name = table
+        .get("effect")
+        .and_then(Value::as_str)
+        .ok_or("missing effect name")?;
+    let effect = effects::create(name).ok_or_else(|| {
+        let valid: Vec<&str> = effects::names().collect();
+        format!("unknown effect {:?}; valid effects are: {}", name, valid.join(", "))
+    })?;
+    let mut scene = Scene::new(effect);
+
+    if let Some(value) = table.get("duration") {
+        scene = scene.with_duration(positive(value, "duration")?);
+    }
+
+    let kind = match table.get("transition") {
+        Some(value) => {
+            let kind_name = value.as_str().ok_or("transition must be a string")?;
+            TransitionKind::from_name(kind_name).ok_or_else(|| {
+                let valid: Vec<String> =
+                    TransitionKind::ALL.iter().map(|k| format!("{:?}", k)).collect();
+                format!(
+                    "unknown transition {:?}; valid transitions are: {}",
+                    kind_name,
+                    valid.join(", ")
+                )
+            })?
+        }
+        None => scene.transition_in,
+    };
+    let secs = match table.get("transition_secs") {
+        Some(value) => positive(value, "transition_secs")?,
+        None => scene.transition_duration,
+    };
+    let easing = match table.get("easing") {
+        Some(value) => easing(value)?,
+        None => scene.transition_easing,
+    };
+    scene = scene.with_transition_eased(kind, secs, easing);
+
+    if let Some(value) = table.get("seed") {
+        let seed = value
+            .as_integer()
+            .filter(|&seed| seed >= 0)
+            .ok_or("seed must be a non-negative integer")?;
+        scene = scene.with_seed(seed as u64);
+    }
+
+    if let Some(params) = table.get("params") {
+        let params = params.as_table().ok_or("params must be a table")?;
+        let known = scene.effect.params();
+        for (key, value) in params {
+            let desc = known.iter().find(|p| p.name == *key).ok_or_else(|| {
+                let valid: Vec<&str> = known.iter().map(|p| p.name.as_str()).collect();
+                format!(
+                    "{} has no param {:?} (params: {})",
+                    name,
+                    key,
+                    valid.join(", ")
+                )
+            })?;
+            let value = number(value).ok_or_else(|| format!("param {} must be a number", key))?;
+            scene
+                .effect
+                .set_param(key, value.clamp(desc.min, desc.max));
+        }
+    }
+
+    Ok(scene)
+}
+
+/// TOML integers and floats both read as numbers; shared with the camera
+/// path parser.
+pub fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(f) => Some(*f),
+        Value::Integer(i) => Some(*i as f64),
+        _ => None,
+    }
+}
+
+/// An `easing = "..."` value, naming the valid easings when it is unknown.
+pub fn easing(value: &Value) -> Result<Easing, String> {
+    let name = value.as_str().ok_or("easing must be a string")?;
+    Easing::from_name(name).ok_or_else(|| {
+        let valid: Vec<String> = Easing::ALL.iter().map(|e| format!("{:?}", e)).collect();
+        format!("unknown easing {:?}; valid easings are: {}", name, valid.join(", "))
+    })
+}
+
+fn positive(value: &Value, key: &str) -> Result<f64, String> {
+    number(value)
+        .filter(|&v| v > 0.0)
+        .ok_or_else(|| format!("{} must be a positive number", key))
+}
@@ -0,0 +1,230 @@
+//! Full-frame post-processing applied to the framebuffer after the effect renders.
+
+/// CRT look in a single pass: darker odd rows (scanlines), a faint
+/// per-column RGB shadow-mask tint and a soft vignette toward the edges.
+pub fn crt(pixels: &mut [(u8, u8, u8)], width: u32, height: u32) {
+    if width == 0 || height == 0 {
+        return;
+    }
+    let half_w = width as f64 / 2.0;
+    let half_h = height as f64 / 2.0;
+
+    for y in 0..height {
+        let scanline = if y & 1 == 1 { 0.78 } else { 1.0 };
+        let dy = (y as f64 + 0.5 - half_h) / half_h;
+        for x in 0..width {
+            let idx = (y * width + x) as usize;
+            if idx >= pixels.len() {
+                return;
+            }
+            let dx = (x as f64 + 0.5 - half_w) / half_w;
+            let vignette = (1.0 - (dx * dx + dy * dy) * 0.22).max(0.0);
+            let k = scanline * vignette;
+            // Aperture-grille style mask: each column favors one channel
+            let (mr, mg, mb) = match x % 3 {
+                0 => (1.0, 0.92, 0.92),
+                1 => (0.92, 1.0, 0.92),
+                _ => (0.92, 0.92, 1.0),
+            };
+            let (r, g, b) = pixels[idx];
+            pixels[idx] = (
+                (r as f64 * k * mr) as u8,
+                (g as f64 * k * mg) as u8,
+                (b as f64 * k * mb) as u8,
+            );
+        }
+    }
+}
+
+/// Bloom is computed at this fraction of the framebuffer on each axis.
+const BLOOM_DOWNSAMPLE: u32 = 4;
+
+/// Binomial approximation of a Gaussian, applied along each axis in turn.
+const BLOOM_KERNEL: [f32; 7] = [1.0, 6.0, 15.0, 20.0, 15.0, 6.0, 1.0];
+
+/// A post-process a scene can request for itself with `Scene::with_post`.
+pub enum PostEffect {
+    Bloom(Bloom),
+    Crt,
+    Theme(Theme),
+}
+
+impl PostEffect {
+    pub fn apply(&mut self, pixels: &mut [(u8, u8, u8)], width: u32, height: u32) {
+        match self {
+            PostEffect::Bloom(bloom) => bloom.apply(pixels, width, height),
+            PostEffect::Crt => crt(pixels, width, height),
+            PostEffect::Theme(theme) => theme.apply(pixels),
+        }
+    }
+}
+
+/// Glow around bright pixels: bright-pass, separable Gaussian blur at
+/// quarter resolution, then bilinear upsample added back onto the frame.
+pub struct Bloom {
+    /// Luminance (0–1) above which pixels start to glow
+    pub threshold: f64,
+    /// Strength of the glow added back
+    pub intensity: f64,
+    small: Vec<[f32; 3]>,
+    scratch: Vec<[f32; 3]>,
+}
+
+impl Bloom {
+    /// `threshold` is a luminance in 0–1 and `intensity` a finite,
+    /// non-negative factor; user-supplied values are checked before this.
+    pub fn new(threshold: f64, intensity: f64) -> Self {
+        debug_assert!((0.0..=1.0).contains(&threshold));
+        debug_assert!(intensity >= 0.0 && intensity.is_finite());
+        Self {
+            threshold,
+            intensity,
+            small: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn apply(&mut self, pixels: &mut [(u8, u8, u8)], width: u32, height: u32) {
+        let (w, h) = (width as usize, height as usize);
+        if w == 0 || h == 0 || pixels.len() < w * h {
+            return;
+        }
+        let d = BLOOM_DOWNSAMPLE as usize;
+        // Round up so partial blocks at the right/bottom edges still glow
+        let sw = (w - 1) / d + 1;
+        let sh = (h - 1) / d + 1;
+        self.small.clear();
+        self.small.resize(sw * sh, [0.0; 3]);
+        self.scratch.clear();
+        self.scratch.resize(sw * sh, [0.0; 3]);
+
+        // Bright-pass, averaged into the downsampled grid
+        let threshold = self.threshold.clamp(0.0, 0.99) as f32;
+        for y in 0..h {
+            let row = (y / d) * sw;
+            for x in 0..w {
+                let (r, g, b) = pixels[y * w + x];
+                let c = [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0];
+                let lum = 0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2];
+                if lum <= threshold {
+                    continue;
+                }
+                // Keep only the part above the threshold, preserving hue
+                let k = (lum - threshold) / (lum * (1.0 - threshold));
+                let cell = &mut self.small[row + x / d];
+                for i in 0..3 {
+                    cell[i] += c[i] * k;
+                }
+            }
+        }
+        let area = (d * d) as f32;
+        for cell in &mut self.small {
+            for v in cell.iter_mut() {
+                *v /= area;
+            }
+        }
+
+        // Separable blur: horizontal into scratch, vertical back into small
+        let norm: f32 = BLOOM_KERNEL.iter().sum();
+        let radius = (BLOOM_KERNEL.len() / 2) as isize;
+        for y in 0..sh {
+            for x in 0..sw {
+                let mut acc = [0.0f32; 3];
+                for (k, weight) in BLOOM_KERNEL.iter().enumerate() {
+                    let sx = (x as isize + k as isize - radius).clamp(0, sw as isize - 1) as usize;
+                    let src = self.small[y * sw + sx];
+                    for i in 0..3 {
+                        acc[i] += src[i] * weight;
+                    }
+                }
+                self.scratch[y * sw + x] = acc.map(|v| v / norm);
+            }
+        }
+        for y in 0..sh {
+            for x in 0..sw {
+                let mut acc = [0.0f32; 3];
+                for (k, weight) in BLOOM_KERNEL.iter().enumerate() {
+                    let sy = (y as isize + k as isize - radius).clamp(0, sh as isize - 1) as usize;
+                    let src = self.scratch[sy * sw + x];
+                    for i in 0..3 {
+                        acc[i] += src[i] * weight;
+                    }
+                }
+                self.small[y * sw + x] = acc.map(|v| v / norm);
+            }
+        }
+
+        // Bilinear upsample and add back
+        let gain = self.intensity as f32 * 255.0;
+        for y in 0..h {
+            let fy = ((y as f32 + 0.5) / d as f32 - 0.5).max(0.0);
+            let y0 = (fy as usize).min(sh - 1);
+            let y1 = (y0 + 1).min(sh - 1);
+            let ty = fy - y0 as f32;
+            for x in 0..w {
+                let fx = ((x as f32 + 0.5) / d as f32 - 0.5).max(0.0);
+                let x0 = (fx as usize).min(sw - 1);
+                let x1 = (x0 + 1).min(sw - 1);
+                let tx = fx - x0 as f32;
+                let small = &self.small;
+                let sample = |i: usize| {
+                    let top = small[y0 * sw + x0][i] * (1.0 - tx) + small[y0 * sw + x1][i] * tx;
+                    let bot = small[y1 * sw + x0][i] * (1.0 - tx) + small[y1 * sw + x1][i] * tx;
+                    top * (1.0 - ty) + bot * ty
+                };
+                let p = &mut pixels[y * w + x];
+                let add = |c: u8, i: usize| (c as f32 + sample(i) * gain).min(255.0) as u8;
+                *p = (add(p.0, 0), add(p.1, 1), add(p.2, 2));
+            }
+        }
+    }
+}
+
+/// Rec. 709 luma weights, as in the bloom bright-pass.
+const LUMA: [f64; 3] = [0.2126, 0.7152, 0.0722];
+
+/// Tint toward one hue: each pixel moves toward that hue at its own
+/// brightness, so the show reads as a single brand color while keeping its
+/// light and shade. The whole mapping is one 3x3 matrix per pixel.
+pub struct Theme {
+    matrix: [[f64; 3]; 3],
+}
+
+impl Theme {
+    /// `hue` in degrees (0 red, 120 green, 240 blue); `strength` 0 leaves
+    /// colors alone and 1 replaces them with the tint.
+    pub fn new(hue: f64, strength: f64) -> Self {
+        let s = strength.clamp(0.0, 1.0);
+        let target = hue_rgb(hue.rem_euclid(360.0) / 360.0);
+        let target_luma: f64 = (0..3).map(|i| target[i] * LUMA[i]).sum();
+        let mut matrix = [[0.0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            // Luma, pushed toward the target's chroma in proportion
+            let tint = 1.0 + target[i] - target_luma;
+            for (j, m) in row.iter_mut().enumerate() {
+                let identity = if i == j { 1.0 } else { 0.0 };
+                *m = identity * (1.0 - s) + LUMA[j] * tint * s;
+            }
+        }
+        Self { matrix }
+    }
+
+    pub fn apply(&self, pixels: &mut [(u8, u8, u8)]) {
+        let m = &self.matrix;
+        for p in pixels.iter_mut() {
+            let (r, g, b) = (p.0 as f64, p.1 as f64, p.2 as f64);
+            let channel =
+                |row: &[f64; 3]| (row[0] * r + row[1] * g + row[2] * b).clamp(0.0, 255.0) as u8;
+            *p = (channel(&m[0]), channel(&m[1]), channel(&m[2]));
+        }
+    }
+}
+
+/// Fully saturated color for `h` in 0..1, channels in 0..1.
+fn hue_rgb(h: f64) -> [f64; 3] {
+    let channel = |offset: f64| {
+        let k = (h * 6.0 + offset).rem_euclid(6.0);
+        1.0 - k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    [channel(5.0), channel(3.0), channel(1.0)]
+}
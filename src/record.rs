@@ -0,0 +1,52 @@
+//! Offscreen stepping of the sequencer at a fixed frame rate, for recordings.
+
+use std::io::{self, Write};
+
+use crate::framebuffer::PixelFramebuffer;
+use crate::sequencer::Sequencer;
+
+/// Seconds recorded when neither a duration nor a loop period is known.
+const DEFAULT_SECS: f64 = 10.0;
+
+/// Frame count and timestep for a recording.
+///
+/// An explicit `duration` wins; otherwise one full `period` is recorded,
+/// with the timestep stretched slightly so the last frame wraps onto the first.
+pub fn frame_plan(period: Option<f64>, duration: Option<f64>, fps: f64) -> (usize, f64) {
+    let secs = duration.or(period).unwrap_or(DEFAULT_SECS);
+    let frames = (secs * fps).round().max(1.0) as usize;
+    (frames, secs / frames as f64)
+}
+
+/// Render `frames` frames of `dt` seconds each, handing every frame to `sink`.
+pub fn record(
+    seq: &mut Sequencer,
+    width: u32,
+    height: u32,
+    frames: usize,
+    dt: f64,
+    mut sink: impl FnMut(&[(u8, u8, u8)]) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut fb = PixelFramebuffer::new(width, height);
+    seq.init(width, height);
+    for _ in 0..frames {
+        seq.update(dt, &mut fb.pixels);
+        sink(&fb.pixels)?;
+    }
+    Ok(())
+}
+
+/// Write one frame as a binary PPM (P6): a short text header, then RGB bytes.
+pub fn write_ppm(
+    out: &mut impl Write,
+    width: u32,
+    height: u32,
+    pixels: &[(u8, u8, u8)],
+) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    let mut rgb = Vec::with_capacity(pixels.len() * 3);
+    for &(r, g, b) in pixels.iter().take((width * height) as usize) {
+        rgb.extend_from_slice(&[r, g, b]);
+    }
+    out.write_all(&rgb)
+}
@@ -0,0 +1,135 @@
+//! Stateless pseudo-random numbers for effects.
+//!
+//! Each value comes from hashing a seed, so an effect can ask for "the
+//! random number for star 17" every frame and get the same answer without
+//! storing it. Effects mix a salt into the seed to get a fresh layout from
+//! `randomize_init`, or keep one in a `Hash`.
+
+/// Well-mixed 32 bits from `seed` (a PCG output permutation).
+pub fn hash_u32(seed: u32) -> u32 {
+    let h = seed.wrapping_mul(747796405).wrapping_add(2891336453);
+    let h = ((h >> ((h >> 28).wrapping_add(4))) ^ h).wrapping_mul(277803737);
+    h ^ (h >> 22)
+}
+
+/// The low 24 bits of `bits` as a float in 0.0..1.0.
+fn unit(bits: u32) -> f64 {
+    (bits & 0x00FF_FFFF) as f64 / 0x0100_0000 as f64
+}
+
+/// Deterministic float from `seed` in 0.0..1.0.
+pub fn hash_u(seed: u32) -> f64 {
+    unit(hash_u32(seed))
+}
+
+/// Deterministic float from `seed` in -1.0..1.0.
+pub fn hash_f(seed: u32) -> f64 {
+    unit(hash_u32(seed)) * 2.0 - 1.0
+}
+
+/// Deterministic float from a 64-bit `seed` in 0.0..1.0 (the splitmix64
+/// finalizer, taking the well-mixed high bits).
+pub fn hash_f64(seed: u64) -> f64 {
+    let mut x = seed;
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    (x >> 40) as f64 / 0x0100_0000 as f64
+}
+
+/// Well-mixed 32 bits for a 2D grid cell, such as a pixel.
+pub fn hash_cell(x: u32, y: u32) -> u32 {
+    hash_u32(x.wrapping_mul(0x9E37_79B9) ^ hash_u32(y))
+}
+
+/// The hash functions under one salt: the same seeds give a different,
+/// equally well-mixed set of values for each salt.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Hash {
+    salt: u32,
+}
+
+impl Hash {
+    pub fn new(salt: u32) -> Self {
+        Self { salt }
+    }
+
+    pub fn u32(&self, seed: u32) -> u32 {
+        hash_u32(seed ^ self.salt)
+    }
+
+    /// Float in 0.0..1.0, as `hash_u`.
+    pub fn u(&self, seed: u32) -> f64 {
+        unit(self.u32(seed))
+    }
+
+    /// Well-mixed 32 bits for an integer lattice point.
+    pub fn lattice(&self, x: i32, y: i32, z: i32) -> u32 {
+        hash_u32(
+            self.salt
+                ^ (x as u32).wrapping_mul(0x8DA6_B343)
+                ^ (y as u32).wrapping_mul(0xD816_3841)
+                ^ (z as u32).wrapping_mul(0xCB1A_B31F),
+        )
+    }
+
+    /// Float in -1.0..1.0 for an integer lattice point.
+    pub fn lattice_f(&self, x: i32, y: i32, z: i32) -> f64 {
+        unit(self.lattice(x, y, z)) * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_value() {
+        for seed in [0, 1, 17, 0xDEAD_BEEF, u32::MAX] {
+            assert_eq!(hash_u32(seed), hash_u32(seed));
+            assert_eq!(hash_u(seed), hash_u(seed));
+            assert_eq!(hash_f64(seed as u64), hash_f64(seed as u64));
+            assert_eq!(Hash::new(7).lattice(3, -4, 5), Hash::new(7).lattice(3, -4, 5));
+        }
+        assert_ne!(Hash::new(1).u32(42), Hash::new(2).u32(42));
+    }
+
+    #[test]
+    fn floats_stay_in_range() {
+        let hash = Hash::new(0x5EED);
+        for seed in 0..10_000u32 {
+            for u in [hash_u(seed), hash_f64(seed as u64), hash.u(seed)] {
+                assert!((0.0..1.0).contains(&u), "{} out of 0..1", u);
+            }
+            for f in [hash_f(seed), hash.lattice_f(seed as i32, 1, -1)] {
+                assert!((-1.0..1.0).contains(&f), "{} out of -1..1", f);
+            }
+        }
+    }
+
+    #[test]
+    fn values_spread_evenly() {
+        // Consecutive seeds, as effects use them, should fill every bucket
+        // about equally and average to the middle of the range
+        const N: u32 = 100_000;
+        let mut buckets = [0u32; 10];
+        let mut sum = 0.0;
+        for seed in 0..N {
+            let u = hash_u(seed);
+            buckets[(u * 10.0) as usize] += 1;
+            sum += u;
+        }
+        let expected = N / 10;
+        for (i, &count) in buckets.iter().enumerate() {
+            assert!(count.abs_diff(expected) < expected / 20, "bucket {} has {}", i, count);
+        }
+        assert!((sum / N as f64 - 0.5).abs() < 0.01);
+
+        let mut set_bits = 0u32;
+        for seed in 0..1000u32 {
+            set_bits += hash_cell(seed % 40, seed / 40).count_ones();
+        }
+        let mean = set_bits as f64 / 1000.0;
+        assert!((mean - 16.0).abs() < 1.0, "mean {} set bits", mean);
+    }
+}
@@ -1,20 +1,34 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
 use crate::effect::Effect;
-use crate::transition::TransitionKind;
+use crate::post::PostEffect;
+use crate::transition::{Easing, TransitionKind};
 
 pub struct Scene {
     pub effect: Box<dyn Effect>,
     pub duration: Option<f64>,
     pub transition_in: TransitionKind,
     pub transition_duration: f64,
+    pub transition_easing: Easing,
+    /// Applied in order to this scene's frames only, before any transition blend
+    pub post: Vec<PostEffect>,
+    /// Fixed seed for the effect's random setup, in place of the run's
+    pub seed: Option<u64>,
 }
 
 impl Scene {
+    /// Scene lasting the effect's preferred duration; `with_duration` overrides it.
     pub fn new(effect: Box<dyn Effect>) -> Self {
+        let duration = Some(effect.preferred_duration());
         Self {
             effect,
-            duration: None,
+            duration,
             transition_in: TransitionKind::Dissolve,
             transition_duration: 1.5,
+            transition_easing: Easing::Linear,
+            post: Vec::new(),
+            seed: None,
         }
     }
 
@@ -28,4 +42,32 @@ impl Scene {
         self.transition_duration = duration;
         self
     }
+
+    pub fn with_transition_eased(
+        self,
+        kind: TransitionKind,
+        duration: f64,
+        easing: Easing,
+    ) -> Self {
+        let mut scene = self.with_transition(kind, duration);
+        scene.transition_easing = easing;
+        scene
+    }
+
+    pub fn with_post(mut self, post: Vec<PostEffect>) -> Self {
+        self.post = post;
+        self
+    }
+
+    /// Set the effect up the same way every time the scene starts, whatever
+    /// the run's `--seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// A fresh RNG from the scene's own seed, when it has one.
+    pub fn seeded_rng(&self) -> Option<StdRng> {
+        self.seed.map(StdRng::seed_from_u64)
+    }
 }
@@ -1,9 +1,13 @@
 use crate::effect::Effect;
+use crate::framebuffer::Persistence;
 use crate::scene::Scene;
-use crate::transition::apply_transition;
+use crate::transition::Transition;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
+/// The beat grid assumes 4/4 time.
+pub const BEATS_PER_BAR: f64 = 4.0;
+
 pub struct Sequencer {
     pub scenes: Vec<Scene>,
     pub current: usize,
@@ -11,14 +15,32 @@ pub struct Sequencer {
     pub global_time: f64,
     pub paused: bool,
     pub held: bool,
+    /// Scenes change only on request, never when their time runs out
+    pub manual: bool,
+    /// The scene clock runs backward; effects see time fall but no elapsed
+    /// `dt`, so ones that integrate freeze instead of running in reverse
+    pub reversed: bool,
     pub looping: bool,
     transitioning: bool,
     transition_elapsed: f64,
     prev_frame: Vec<(u8, u8, u8)>,
-    next_frame: Vec<(u8, u8, u8)>,
+    /// The current effect's own canvas, kept free of post-processing so
+    /// effects that build on their previous frame never see it
+    scene_frame: Vec<(u8, u8, u8)>,
+    /// `scene_frame` after the scene's post-processes, when it has any
+    post_frame: Vec<(u8, u8, u8)>,
+    persistence: Persistence,
     width: u32,
     height: u32,
     rng: StdRng,
+    /// Seconds per beat when scene changes follow a tempo
+    beat: Option<f64>,
+    /// Global time of the downbeat that ends the current scene on the beat grid
+    scene_end: f64,
+    /// Skip requested on the beat grid, started on the next beat
+    pending: Option<usize>,
+    /// Factor applied to every scene and transition length
+    duration_scale: f64,
 }
 
 impl Sequencer {
@@ -30,14 +52,61 @@ impl Sequencer {
             global_time: 0.0,
             paused: false,
             held: false,
+            manual: false,
+            reversed: false,
             looping,
             transitioning: false,
             transition_elapsed: 0.0,
             prev_frame: Vec::new(),
-            next_frame: Vec::new(),
+            scene_frame: Vec::new(),
+            post_frame: Vec::new(),
+            persistence: Persistence::default(),
             width: 0,
             height: 0,
             rng: StdRng::seed_from_u64(seed),
+            beat: None,
+            scene_end: f64::INFINITY,
+            pending: None,
+            duration_scale: 1.0,
+        }
+    }
+
+    /// Quantize scene lengths to whole 4/4 bars at `bpm` and hold every scene
+    /// change until the next beat, so transitions land on the music.
+    pub fn with_bpm(mut self, bpm: f64) -> Self {
+        self.beat = Some(60.0 / bpm);
+        self
+    }
+
+    /// Play every scene, and its transition in, `scale` times as long.
+    pub fn with_duration_scale(mut self, scale: f64) -> Self {
+        self.duration_scale = scale;
+        self
+    }
+
+    /// Start the first scene `secs` seconds into its clock, so effects that
+    /// are a pure function of time open partway through.
+    pub fn with_time_offset(mut self, secs: f64) -> Self {
+        self.scene_time = secs;
+        self
+    }
+
+    /// How long scene `index` plays before moving on, after scaling.
+    fn scene_duration(&self, index: usize) -> Option<f64> {
+        self.scenes
+            .get(index)
+            .and_then(|s| s.duration)
+            .map(|d| d * self.duration_scale)
+    }
+
+    /// How long the transition into scene `index` lasts, after scaling.
+    /// It never outlasts the scene itself, which matters when the scaled
+    /// scenes get shorter than the built-in transitions.
+    fn transition_duration(&self, index: usize) -> f64 {
+        let duration = self.scenes[index].transition_duration * self.duration_scale;
+        match self.scene_duration(index) {
+            Some(scene) => duration.min(scene),
+            None => duration,
         }
     }
 
@@ -46,11 +115,16 @@ impl Sequencer {
         self.height = height;
         let len = (width * height) as usize;
         self.prev_frame.resize(len, (0, 0, 0));
-        self.next_frame.resize(len, (0, 0, 0));
+        self.scene_frame.resize(len, (0, 0, 0));
+        self.persistence.reset();
         if let Some(scene) = self.scenes.get_mut(self.current) {
+            let mut own = scene.seeded_rng();
             scene.effect.init(width, height);
-            scene.effect.randomize_init(&mut self.rng);
+            scene
+                .effect
+                .randomize_init(own.as_mut().unwrap_or(&mut self.rng));
         }
+        self.scene_end = self.bar_end();
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
@@ -58,10 +132,12 @@ impl Sequencer {
         self.height = height;
         let len = (width * height) as usize;
         self.prev_frame.resize(len, (0, 0, 0));
-        self.next_frame.resize(len, (0, 0, 0));
+        self.scene_frame.resize(len, (0, 0, 0));
+        self.persistence.reset();
         if let Some(scene) = self.scenes.get_mut(self.current) {
-            scene.effect.init(width, height);
-            scene.effect.randomize_init(&mut self.rng);
+            let mut own = scene.seeded_rng();
+            let rng = own.as_mut().unwrap_or(&mut self.rng);
+            scene.effect.on_resize(width, height, rng);
         }
     }
 
@@ -72,6 +148,23 @@ impl Sequencer {
             .unwrap_or("---")
     }
 
+    /// Machine-readable progress, e.g. `effect=Plasma t=3.42 scene=1/63`.
+    pub fn status_line(&self) -> String {
+        let name = self.current_scene_name();
+        let name = if name.contains(' ') {
+            format!("{:?}", name)
+        } else {
+            name.to_string()
+        };
+        format!(
+            "effect={} t={:.2} scene={}/{}",
+            name,
+            self.scene_time,
+            self.current + 1,
+            self.scenes.len()
+        )
+    }
+
     pub fn scene_count(&self) -> usize {
         self.scenes.len()
     }
@@ -84,12 +177,16 @@ impl Sequencer {
         self.held = !self.held;
     }
 
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
     pub fn goto_scene(&mut self, index: usize) {
         if index >= self.scenes.len() || index == self.current {
             return;
         }
         self.held = false;
-        self.start_transition(index);
+        self.request_transition(index);
     }
 
     pub fn next_scene(&mut self) {
@@ -106,7 +203,7 @@ impl Sequencer {
         } else {
             self.current + 1
         };
-        self.start_transition(next);
+        self.request_transition(next);
     }
 
     pub fn prev_scene(&mut self) {
@@ -123,21 +220,71 @@ impl Sequencer {
         } else {
             self.current - 1
         };
-        self.start_transition(prev);
+        self.request_transition(prev);
+    }
+
+    /// Start the transition now, or on the next beat when following a tempo.
+    fn request_transition(&mut self, next_index: usize) {
+        if self.beat.is_some() {
+            self.pending = Some(next_index);
+        } else {
+            self.start_transition(next_index);
+        }
+    }
+
+    /// The downbeat ending a scene that starts now: its duration rounded to
+    /// whole bars (at least one), counted from the nearest bar line.
+    fn bar_end(&self) -> f64 {
+        let (beat, duration) = match (self.beat, self.scene_duration(self.current)) {
+            (Some(beat), Some(duration)) => (beat, duration),
+            _ => return f64::INFINITY,
+        };
+        let bar = beat * BEATS_PER_BAR;
+        let bars = (duration / bar).round().max(1.0);
+        (self.global_time / bar).round() * bar + bars * bar
+    }
+
+    /// Whether the last `dt` of global time crossed a multiple of `period`.
+    fn crossed(&self, period: f64, dt: f64) -> bool {
+        (self.global_time / period).floor() > ((self.global_time - dt) / period).floor()
     }
 
     fn start_transition(&mut self, next_index: usize) {
+        if self.transitioning {
+            // Skipping mid-transition: restart from the blend currently on
+            // screen instead of the older scene's stale last frame
+            let progress =
+                (self.transition_elapsed / self.transition_duration(self.current)).min(1.0);
+            let scene = &self.scenes[self.current];
+            let mut shown = vec![(0, 0, 0); self.prev_frame.len()];
+            let transition = scene.transition_in.sized(self.width, self.height);
+            transition.eased(scene.transition_easing).blend(
+                &self.prev_frame,
+                self.scene_output(),
+                progress,
+                &mut shown,
+            );
+            self.prev_frame = shown;
+        }
+
         // Snapshot current frame into prev_frame
         self.transitioning = true;
         self.transition_elapsed = 0.0;
 
         // prev_frame already holds the last rendered output
-        // init next scene
+        // init next scene on a blank canvas
+        self.persistence.reset();
+        self.scene_frame.iter_mut().for_each(|px| *px = (0, 0, 0));
         let next_scene = &mut self.scenes[next_index];
+        let mut own = next_scene.seeded_rng();
         next_scene.effect.init(self.width, self.height);
-        next_scene.effect.randomize_init(&mut self.rng);
+        next_scene
+            .effect
+            .randomize_init(own.as_mut().unwrap_or(&mut self.rng));
         self.current = next_index;
         self.scene_time = 0.0;
+        self.reversed = false;
+        self.scene_end = self.bar_end();
     }
 
     pub fn update(&mut self, dt: f64, pixels: &mut [(u8, u8, u8)]) {
@@ -146,56 +293,103 @@ impl Sequencer {
         }
 
         self.global_time += dt;
-        self.scene_time += dt;
+        let effect_dt = if self.reversed {
+            // A scene can't rewind past its own start
+            self.scene_time = (self.scene_time - dt).max(0.0);
+            0.0
+        } else {
+            self.scene_time += dt;
+            dt
+        };
 
         let current = self.current;
+        let (width, height) = (self.width, self.height);
+
+        // Render the current scene into its own canvas
+        self.scene_frame.resize(pixels.len(), (0, 0, 0));
+        let scene = &mut self.scenes[current];
+        scene.effect.update(self.scene_time, effect_dt, &mut self.scene_frame);
+        if let Some(decay) = scene.effect.wants_persistence() {
+            self.persistence.apply(&mut self.scene_frame, decay, effect_dt);
+        }
+        if !scene.post.is_empty() {
+            self.post_frame.clear();
+            self.post_frame.extend_from_slice(&self.scene_frame);
+            for post in &mut scene.post {
+                post.apply(&mut self.post_frame, width, height);
+            }
+        }
 
         if self.transitioning {
             self.transition_elapsed += dt;
+            let progress = (self.transition_elapsed / self.transition_duration(current)).min(1.0);
             let scene = &self.scenes[current];
-            let duration = scene.transition_duration;
-            let progress = (self.transition_elapsed / duration).min(1.0);
 
-            // Render the new scene into next_frame
-            self.next_frame.resize(pixels.len(), (0, 0, 0));
-            self.scenes[current]
-                .effect
-                .update(self.scene_time, dt, &mut self.next_frame);
-
-            // Blend prev_frame -> next_frame into output
-            let kind = self.scenes[current].transition_in;
-            apply_transition(
-                kind,
+            // Blend prev_frame -> the new scene into output
+            let transition = scene.transition_in.sized(width, height);
+            transition.eased(scene.transition_easing).blend(
                 &self.prev_frame,
-                &self.next_frame,
-                pixels,
-                self.width,
-                self.height,
+                self.scene_output(),
                 progress,
+                pixels,
             );
 
             if progress >= 1.0 {
                 self.transitioning = false;
             }
         } else {
-            // Normal rendering
-            self.scenes[current]
-                .effect
-                .update(self.scene_time, dt, pixels);
+            pixels.copy_from_slice(self.scene_output());
 
             // Snapshot for potential upcoming transition
             self.prev_frame.resize(pixels.len(), (0, 0, 0));
             self.prev_frame.copy_from_slice(pixels);
 
             // Check if scene duration expired (skip when held)
-            if !self.held {
-                if let Some(dur) = self.scenes[current].duration {
-                    if self.scene_time >= dur {
+            if !self.held && !self.manual && !self.reversed {
+                if let Some(dur) = self.scene_duration(current) {
+                    let ended = match self.beat {
+                        Some(beat) => {
+                            self.global_time >= self.scene_end
+                                && self.crossed(beat * BEATS_PER_BAR, dt)
+                        }
+                        None => self.scene_time >= dur,
+                    };
+                    if ended {
                         self.next_scene();
                     }
                 }
             }
         }
+
+        if let (Some(beat), Some(next)) = (self.beat, self.pending) {
+            if self.crossed(beat, dt) {
+                self.pending = None;
+                self.start_transition(next);
+            }
+        }
+    }
+
+    /// The current scene's latest frame as displayed, post-processing included.
+    fn scene_output(&self) -> &[(u8, u8, u8)] {
+        match self.scenes.get(self.current) {
+            Some(scene) if !scene.post.is_empty() => &self.post_frame,
+            _ => &self.scene_frame,
+        }
+    }
+
+    /// Set the message on every scene that renders text.
+    pub fn set_text(&mut self, text: &str) {
+        for scene in &mut self.scenes {
+            scene.effect.set_text(text);
+        }
+    }
+
+    /// Give the current effect a fresh random arrangement from the run's RNG,
+    /// even in a scene with its own seed.
+    pub fn reshuffle_current(&mut self) {
+        if let Some(scene) = self.scenes.get_mut(self.current) {
+            scene.effect.randomize_init(&mut self.rng);
+        }
     }
 
     pub fn current_effect_mut(&mut self) -> Option<&mut Box<dyn Effect>> {
@@ -0,0 +1,99 @@
+//! Headless crash check for every registered effect.
+//!
+//! Each effect is built from the registry and run for a few frames at sizes
+//! a real terminal rarely gives it, down to a single pixel, so an indexing
+//! or divide-by-size slip shows up before a user's odd window does. Panics
+//! are caught per effect and size, so one failure doesn't hide the rest.
+//!
+//! The frame itself is bytes and can't hold a NaN; non-finite values are
+//! caught where an effect reports them, in its params and durations.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::effect::Effect;
+use crate::effects;
+
+/// Frame sizes each effect runs at: degenerate, odd and ordinary.
+pub const SIZES: [(u32, u32); 6] = [(1, 1), (1, 2), (2, 1), (3, 5), (17, 9), (160, 100)];
+/// Frames drawn at each size
+const FRAMES: usize = 10;
+const DT: f64 = 1.0 / 30.0;
+
+/// An effect that panicked or reported a non-finite value at one size.
+pub struct Failure {
+    pub name: &'static str,
+    pub size: (u32, u32),
+    pub problem: String,
+}
+
+/// Run every effect named in `names` at every size in `SIZES`, each with a
+/// fresh RNG from `seed`, and collect what went wrong.
+pub fn run(names: &[&'static str], seed: u64) -> Vec<Failure> {
+    let mut failures = Vec::new();
+    for &name in names {
+        for &size in &SIZES {
+            let checked = panic::catch_unwind(AssertUnwindSafe(|| exercise(name, size, seed)));
+            let problem = match checked {
+                Ok(Ok(())) => continue,
+                Ok(Err(problem)) => problem,
+                Err(payload) => format!("panicked: {}", panic_message(payload.as_ref())),
+            };
+            failures.push(Failure {
+                name,
+                size,
+                problem,
+            });
+        }
+    }
+    failures
+}
+
+/// Set up one effect at `width` x `height` and draw `FRAMES` frames, opaque
+/// and with transparency, then check the numbers it reports.
+fn exercise(name: &str, (width, height): (u32, u32), seed: u64) -> Result<(), String> {
+    let mut effect = match effects::create(name) {
+        Some(effect) => effect,
+        None => return Err("not in the registry".to_string()),
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let len = (width * height) as usize;
+    let mut pixels = vec![(0, 0, 0); len];
+    let mut layer = vec![(0, 0, 0, 0); len];
+
+    effect.init(width, height);
+    effect.randomize_init(&mut rng);
+    for frame in 0..FRAMES {
+        let t = frame as f64 * DT;
+        effect.update(t, DT, &mut pixels);
+        effect.update_rgba(t, DT, &mut layer);
+    }
+    check_finite(effect.as_ref())
+}
+
+fn check_finite(effect: &dyn Effect) -> Result<(), String> {
+    if !effect.preferred_duration().is_finite() {
+        return Err("preferred duration is not finite".to_string());
+    }
+    if let Some(period) = effect.loop_period().filter(|p| !p.is_finite()) {
+        return Err(format!("loop period is {}", period));
+    }
+    match effect.params().into_iter().find(|p| !p.value.is_finite()) {
+        Some(param) => Err(format!("param {} is {}", param.name, param.value)),
+        None => Ok(()),
+    }
+}
+
+/// The text a panic was raised with, when it was a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "(no message)"
+    }
+}
@@ -0,0 +1,127 @@
+//! Round dots with smooth edges: soft, defocusable dots for the point-cloud
+//! effects, anti-aliased solid circles for particles and bobs, and
+//! subpixel points for anything that moves slower than a pixel per frame.
+
+/// Blur radius in pixels for a point at depth `z`: zero on the focus plane,
+/// growing with distance from it. `scale` is the effect's projection radius
+/// in pixels, so the look doesn't change with the terminal size.
+pub fn defocus(z: f64, focus: f64, dof: f64, scale: f64) -> f64 {
+    dof * (z - focus).abs() * scale * 0.12
+}
+
+/// Max-blend a dot of `radius` pixels centered on `(x, y)`, fading out over
+/// `blur` more pixels. The blurred part spreads the same light further, so
+/// out-of-focus dots grow larger and dimmer.
+pub fn soft_dot(
+    pixels: &mut [(u8, u8, u8)],
+    width: u32,
+    height: u32,
+    (x, y): (f64, f64),
+    radius: f64,
+    blur: f64,
+    color: (u8, u8, u8),
+) {
+    let outer = radius + blur;
+    if outer <= 0.0 {
+        return;
+    }
+    let energy = (radius / outer).max(0.2);
+    let x0 = (x - outer).floor().max(0.0) as i64;
+    let y0 = (y - outer).floor().max(0.0) as i64;
+    let x1 = ((x + outer).ceil() as i64).min(width as i64 - 1);
+    let y1 = ((y + outer).ceil() as i64).min(height as i64 - 1);
+
+    for py in y0..=y1 {
+        for px in x0..=x1 {
+            let dx = px as f64 + 0.5 - x;
+            let dy = py as f64 + 0.5 - y;
+            let d = (dx * dx + dy * dy).sqrt();
+            let alpha = if d <= radius {
+                energy
+            } else if blur > 0.0 {
+                energy * (1.0 - (d - radius) / blur).max(0.0)
+            } else {
+                0.0
+            };
+            if alpha <= 0.0 {
+                continue;
+            }
+            if let Some(p) = pixels.get_mut((py as u32 * width + px as u32) as usize) {
+                p.0 = p.0.max((color.0 as f64 * alpha) as u8);
+                p.1 = p.1.max((color.1 as f64 * alpha) as u8);
+                p.2 = p.2.max((color.2 as f64 * alpha) as u8);
+            }
+        }
+    }
+}
+
+/// Alpha-blend a solid circle of `radius` pixels centered on `(x, y)`.
+/// Each pixel is covered by how far its center lies inside the rim, so the
+/// edge fades over one pixel and even tiny dots come out round.
+pub fn filled_circle(
+    pixels: &mut [(u8, u8, u8)],
+    width: u32,
+    height: u32,
+    (x, y): (f64, f64),
+    radius: f64,
+    color: (u8, u8, u8),
+) {
+    if radius <= 0.0 {
+        return;
+    }
+    let outer = radius + 0.5;
+    let x0 = (x - outer).floor().max(0.0) as i64;
+    let y0 = (y - outer).floor().max(0.0) as i64;
+    let x1 = ((x + outer).ceil() as i64).min(width as i64 - 1);
+    let y1 = ((y + outer).ceil() as i64).min(height as i64 - 1);
+
+    for py in y0..=y1 {
+        for px in x0..=x1 {
+            let dx = px as f64 + 0.5 - x;
+            let dy = py as f64 + 0.5 - y;
+            let alpha = (outer - (dx * dx + dy * dy).sqrt()).min(1.0);
+            if alpha <= 0.0 {
+                continue;
+            }
+            if let Some(p) = pixels.get_mut((py as u32 * width + px as u32) as usize) {
+                let mix = |old: u8, new: u8| (old as f64 + (new as f64 - old as f64) * alpha) as u8;
+                *p = (mix(p.0, color.0), mix(p.1, color.1), mix(p.2, color.2));
+            }
+        }
+    }
+}
+
+/// Max-blend a single point at `(x, y)`, spreading it over the four nearest
+/// pixels by how close their centers are. A point drifting between cells
+/// fades across instead of snapping, and max rather than additive blending
+/// keeps overlapping samples along a trail from piling up.
+pub fn splat_point(
+    pixels: &mut [(u8, u8, u8)],
+    width: u32,
+    height: u32,
+    (x, y): (f64, f64),
+    color: (u8, u8, u8),
+) {
+    // Pixel centers sit at half-pixel offsets
+    let (fx, fy) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (fx.floor(), fy.floor());
+    let (tx, ty) = (fx - x0, fy - y0);
+    let corners = [
+        (0, 0, (1.0 - tx) * (1.0 - ty)),
+        (1, 0, tx * (1.0 - ty)),
+        (0, 1, (1.0 - tx) * ty),
+        (1, 1, tx * ty),
+    ];
+    for &(dx, dy, weight) in &corners {
+        let px = x0 as i64 + dx;
+        let py = y0 as i64 + dy;
+        if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+            continue;
+        }
+        if let Some(p) = pixels.get_mut((py as u32 * width + px as u32) as usize) {
+            p.0 = p.0.max((color.0 as f64 * weight) as u8);
+            p.1 = p.1.max((color.1 as f64 * weight) as u8);
+            p.2 = p.2.max((color.2 as f64 * weight) as u8);
+        }
+    }
+}
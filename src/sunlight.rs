@@ -0,0 +1,22 @@
+//! Directional sunlight for heightfield effects.
+//!
+//! The sun sits at a fixed elevation and circles the horizon by azimuth.
+//! Shading is normalized so flat ground keeps its base color: slopes facing
+//! the sun come out brighter and slopes facing away darker.
+
+/// Sun elevation above the horizon, in radians
+const ELEVATION: f64 = 0.6;
+/// Light that still reaches faces turned fully away from the sun
+const AMBIENT: f64 = 0.35;
+
+/// Brightness factor for a surface whose height rises by `slope_a` and
+/// `slope_b` per unit along the two ground axes, lit from `azimuth`
+/// (radians from the first axis toward the second).
+pub fn shade(slope_a: f64, slope_b: f64, azimuth: f64) -> f64 {
+    // The surface normal is (-slope_a, -slope_b, 1) before normalizing
+    let len = (slope_a * slope_a + slope_b * slope_b + 1.0).sqrt();
+    let (sin_e, cos_e) = ELEVATION.sin_cos();
+    let (sin_a, cos_a) = azimuth.sin_cos();
+    let n_dot_l = ((sin_e - slope_a * cos_e * cos_a - slope_b * cos_e * sin_a) / len).max(0.0);
+    (AMBIENT + (1.0 - AMBIENT) * n_dot_l) / (AMBIENT + (1.0 - AMBIENT) * sin_e)
+}
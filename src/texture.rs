@@ -0,0 +1,221 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::rng;
+
+/// Key color treated as see-through when drawing sprites.
+pub const TRANSPARENT: (u8, u8, u8) = (255, 0, 255);
+
+/// Largest image `load_ppm` accepts, so a bad header can't ask for an
+/// enormous allocation.
+const MAX_TEXELS: usize = 4096 * 4096;
+
+/// An RGB image sampled with wrap-around coordinates.
+#[derive(Clone)]
+pub struct Texture {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<(u8, u8, u8)>,
+}
+
+/// How sampling treats coordinates past the edge of an image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    /// Tile the image
+    Wrap,
+    /// Tile with every other copy flipped, so neighbors meet without a seam
+    Mirror,
+    /// Repeat the outermost row or column
+    Clamp,
+}
+
+impl Edge {
+    /// Map coordinate `i` into `0..size`.
+    pub fn fold(self, i: i64, size: i64) -> i64 {
+        match self {
+            Edge::Wrap => i.rem_euclid(size),
+            Edge::Mirror => {
+                let m = i.rem_euclid(size * 2);
+                if m < size {
+                    m
+                } else {
+                    size * 2 - 1 - m
+                }
+            }
+            Edge::Clamp => i.clamp(0, size - 1),
+        }
+    }
+}
+
+impl Texture {
+    /// Procedural 64x64 red brick wall with mortar lines and per-brick tint.
+    pub fn brick() -> Self {
+        let size = 64u32;
+        let brick_w = 16;
+        let brick_h = 8;
+        let mut pixels = Vec::with_capacity((size * size) as usize);
+
+        for y in 0..size {
+            let row = y / brick_h;
+            let offset = (row & 1) * brick_w / 2;
+            for x in 0..size {
+                let bx = (x + offset) % size;
+                let col = bx / brick_w;
+                let (in_x, in_y) = (bx % brick_w, y % brick_h);
+                let mortar = in_x == 0 || in_y == 0;
+                let grain = (rng::hash_u32(y * size + x) & 0x1F) as f64 / 31.0;
+                if mortar {
+                    let v = (90.0 + grain * 20.0) as u8;
+                    pixels.push((v, v, v - 10));
+                } else {
+                    let tint = (rng::hash_u32(row * 131 + col) & 0xFF) as f64 / 255.0;
+                    let shade = 0.8 + tint * 0.2 - grain * 0.1;
+                    pixels.push((
+                        (180.0 * shade) as u8,
+                        (70.0 * shade) as u8,
+                        (50.0 * shade) as u8,
+                    ));
+                }
+            }
+        }
+
+        Self {
+            width: size,
+            height: size,
+            pixels,
+        }
+    }
+
+    /// Procedural 64x64 stone slabs in four quadrants, tinted by `base`.
+    pub fn tiles(base: (u8, u8, u8)) -> Self {
+        let size = 64u32;
+        let slab = 32;
+        let mut pixels = Vec::with_capacity((size * size) as usize);
+
+        for y in 0..size {
+            for x in 0..size {
+                let (in_x, in_y) = (x % slab, y % slab);
+                let grout = in_x == 0 || in_y == 0;
+                let grain = (rng::hash_u32(y * size + x + 7919) & 0x1F) as f64 / 31.0;
+                let shade = if grout {
+                    0.45
+                } else {
+                    let quadrant = (x / slab + y / slab) & 1;
+                    0.85 + quadrant as f64 * 0.15 - grain * 0.12
+                };
+                pixels.push((
+                    (base.0 as f64 * shade) as u8,
+                    (base.1 as f64 * shade) as u8,
+                    (base.2 as f64 * shade) as u8,
+                ));
+            }
+        }
+
+        Self {
+            width: size,
+            height: size,
+            pixels,
+        }
+    }
+
+    /// Blank texture filled with the transparent key color, for drawing sprites into.
+    pub fn transparent(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![TRANSPARENT; width as usize * height as usize],
+        }
+    }
+
+    /// Load a binary (P6) or ASCII (P3) PPM image.
+    pub fn load_ppm(path: &Path) -> io::Result<Self> {
+        let data = fs::read(path)?;
+        Self::parse_ppm(&data)
+    }
+
+    fn parse_ppm(data: &[u8]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+        // Header: magic, width, height, maxval — whitespace separated, '#' comments
+        let mut pos = 0;
+        let mut fields: Vec<String> = Vec::new();
+        while fields.len() < 4 {
+            while pos < data.len() && (data[pos].is_ascii_whitespace() || data[pos] == b'#') {
+                if data[pos] == b'#' {
+                    while pos < data.len() && data[pos] != b'\n' {
+                        pos += 1;
+                    }
+                } else {
+                    pos += 1;
+                }
+            }
+            let start = pos;
+            while pos < data.len() && !data[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            if start == pos {
+                return Err(invalid("truncated PPM header"));
+            }
+            fields.push(String::from_utf8_lossy(&data[start..pos]).into_owned());
+        }
+
+        let magic = fields[0].as_str();
+        if magic != "P6" && magic != "P3" {
+            return Err(invalid("not a PPM image (expected P3 or P6)"));
+        }
+        let parse = |s: &str| s.parse::<u32>().map_err(|_| invalid("bad PPM header value"));
+        let width = parse(&fields[1])?;
+        let height = parse(&fields[2])?;
+        let maxval = parse(&fields[3])?;
+        if width == 0 || height == 0 || maxval == 0 || maxval > 255 {
+            return Err(invalid("unsupported PPM dimensions or depth"));
+        }
+
+        let count = (width as usize)
+            .checked_mul(height as usize)
+            .filter(|&n| n <= MAX_TEXELS)
+            .ok_or_else(|| invalid("PPM image too large"))?;
+        let scale = |v: u32| (v.min(maxval) * 255 / maxval) as u8;
+        let mut pixels = Vec::with_capacity(count);
+
+        if magic == "P6" {
+            // Exactly one whitespace byte separates the header from raster data
+            let body = &data[(pos + 1).min(data.len())..];
+            if body.len() < count * 3 {
+                return Err(invalid("truncated PPM pixel data"));
+            }
+            for px in body[..count * 3].chunks_exact(3) {
+                pixels.push((scale(px[0] as u32), scale(px[1] as u32), scale(px[2] as u32)));
+            }
+        } else {
+            let text = String::from_utf8_lossy(&data[pos..]);
+            let mut values = text
+                .split_ascii_whitespace()
+                .map(|s| s.parse::<u32>().map_err(|_| invalid("bad PPM sample")));
+            for _ in 0..count {
+                let mut next = || values.next().unwrap_or_else(|| Err(invalid("truncated PPM pixel data")));
+                let (r, g, b) = (next()?, next()?, next()?);
+                pixels.push((scale(r), scale(g), scale(b)));
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Fetch a texel, wrapping coordinates so any image size tiles cleanly.
+    pub fn texel(&self, x: i64, y: i64) -> (u8, u8, u8) {
+        self.texel_edge(x, y, Edge::Wrap)
+    }
+
+    /// Fetch a texel, handling coordinates off the image as `edge` says.
+    pub fn texel_edge(&self, x: i64, y: i64, edge: Edge) -> (u8, u8, u8) {
+        let tx = edge.fold(x, self.width as i64) as usize;
+        let ty = edge.fold(y, self.height as i64) as usize;
+        self.pixels[ty * self.width as usize + tx]
+    }
+}
@@ -8,6 +8,127 @@ pub enum TransitionKind {
     WipeDown,
 }
 
+impl TransitionKind {
+    pub const ALL: [TransitionKind; 5] = [
+        TransitionKind::Cut,
+        TransitionKind::Fade,
+        TransitionKind::Dissolve,
+        TransitionKind::WipeLeft,
+        TransitionKind::WipeDown,
+    ];
+
+    /// Look up a transition by its variant name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| format!("{:?}", kind).eq_ignore_ascii_case(name))
+    }
+
+    /// This transition for frames of `width` x `height`, which the wipes need.
+    pub fn sized(self, width: u32, height: u32) -> SizedTransition {
+        SizedTransition {
+            kind: self,
+            width,
+            height,
+            easing: Easing::Linear,
+        }
+    }
+}
+
+/// Shapes transition progress before blending.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    /// Starts slow, speeds up through the middle, then settles
+    InOutCubic,
+    /// Rushes in and overshoots slightly before coming back to 1
+    OutBack,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 3] = [Easing::Linear, Easing::InOutCubic, Easing::OutBack];
+
+    /// Look up an easing by its variant name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|easing| format!("{:?}", easing).eq_ignore_ascii_case(name))
+    }
+
+    /// Map linear `progress` in 0..=1 onto the curve. `OutBack` goes past 1
+    /// near the end; the built-in transitions clamp that, so there it reads
+    /// as a quick ease-out.
+    pub fn apply(self, progress: f64) -> f64 {
+        let t = progress.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::InOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::OutBack => {
+                const C1: f64 = 1.70158;
+                const C3: f64 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+/// Blends one frame into another as `progress` runs from 0 to 1.
+///
+/// Nothing here depends on `Scene` or `Sequencer` (though, with no library
+/// target, only code in this crate can use it): to crossfade two effects
+/// driven by hand, render each into its own buffer every frame and `blend`
+/// them into the output, e.g.
+/// `TransitionKind::Dissolve.sized(w, h).blend(&a, &b, t / secs, &mut out)`.
+/// Custom transitions implement this and can be passed as `&dyn Transition`.
+/// Both frames are opaque; layered effects are flattened by `Compose`
+/// before they reach a transition.
+pub trait Transition {
+    fn blend(
+        &self,
+        a: &[(u8, u8, u8)],
+        b: &[(u8, u8, u8)],
+        progress: f64,
+        out: &mut [(u8, u8, u8)],
+    );
+}
+
+/// A built-in transition bound to a frame size.
+#[derive(Clone, Copy, Debug)]
+pub struct SizedTransition {
+    pub kind: TransitionKind,
+    pub width: u32,
+    pub height: u32,
+    pub easing: Easing,
+}
+
+impl SizedTransition {
+    pub fn eased(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl Transition for SizedTransition {
+    fn blend(
+        &self,
+        a: &[(u8, u8, u8)],
+        b: &[(u8, u8, u8)],
+        progress: f64,
+        out: &mut [(u8, u8, u8)],
+    ) {
+        let progress = self.easing.apply(progress);
+        apply_transition(self.kind, a, b, out, self.width, self.height, progress);
+    }
+}
+
 fn lerp_color(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
     let t = t.clamp(0.0, 1.0);
     let r = a.0 as f64 * (1.0 - t) + b.0 as f64 * t;
@@ -16,7 +137,7 @@ fn lerp_color(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
     (r as u8, g as u8, bl as u8)
 }
 
-pub fn apply_transition(
+fn apply_transition(
     kind: TransitionKind,
     from: &[(u8, u8, u8)],
     to: &[(u8, u8, u8)],
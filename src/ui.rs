@@ -1,9 +1,10 @@
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Alignment, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::widgets::Widget;
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget};
 
 use crate::app::{App, Mode};
+use crate::input::BINDINGS;
 
 pub struct HudWidget<'a> {
     pub app: &'a App,
@@ -19,21 +20,24 @@ impl<'a> Widget for HudWidget<'a> {
         let mode_str = match self.app.mode {
             Mode::AutoPlay => "AUTO",
             Mode::Interactive => "INTERACTIVE",
+            Mode::Presentation => "PRESENT",
         };
 
         let paused = if seq.paused { " [PAUSED]" } else { "" };
         let held = if seq.held { " [HELD]" } else { "" };
+        let reversed = if seq.reversed { " [REVERSE]" } else { "" };
 
         // Status bar at bottom
         let bar_y = area.y + area.height - 1;
         let status = format!(
-            " Scene {}/{}: {} | Mode: {}{}{} | t={:.1}s ",
+            " Scene {}/{}: {} | Mode: {}{}{}{} | t={:.1}s ",
             seq.current + 1,
             seq.scene_count(),
             seq.current_scene_name(),
             mode_str,
             paused,
             held,
+            reversed,
             seq.scene_time,
         );
 
@@ -60,7 +64,7 @@ impl<'a> Widget for HudWidget<'a> {
         }
 
         // Controls hint on the right side
-        let hint = "q:quit Space:pause f:hold Tab:mode h:hud [/]:param n/p:scene";
+        let hint = "?:keys q:quit Space:pause f:hold z:reverse Tab:mode h:hud b:border m:message w:warp r:reshuffle [/]:param n/p:scene";
         let hint_start = (area.x + area.width).saturating_sub(hint.len() as u16 + 1);
         let hint_style = Style::default()
             .fg(Color::Rgb(140, 140, 180))
@@ -138,3 +142,111 @@ impl<'a> Widget for HudWidget<'a> {
         }
     }
 }
+
+/// Decorative frame around the effect with the scene name centered in its
+/// top edge, for screen recordings.
+pub struct BorderWidget<'a> {
+    pub title: &'a str,
+}
+
+impl<'a> BorderWidget<'a> {
+    /// The part of `area` left for the effect inside the frame.
+    pub fn inner(area: Rect) -> Rect {
+        Block::default().borders(Borders::ALL).inner(area)
+    }
+}
+
+impl<'a> Widget for BorderWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Rgb(140, 140, 180)))
+            .title(format!(" {} ", self.title))
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+            .render(area, buf);
+    }
+}
+
+/// Centered list of every key binding, scrolled down by `scroll` lines.
+pub struct HelpWidget {
+    pub scroll: usize,
+}
+
+impl Widget for HelpWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let keys_w = BINDINGS.iter().map(|b| b.keys.len()).max().unwrap_or(0);
+        let lines: Vec<String> = BINDINGS
+            .iter()
+            .map(|b| format!(" {:<keys_w$}  {}", b.keys, b.help, keys_w = keys_w))
+            .collect();
+        let text_w = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+
+        // Borders take a cell on each side; shrink to fit small terminals
+        let width = (text_w + 3).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        if width < 10 || height < 3 {
+            return;
+        }
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let visible = (height - 2) as usize;
+        let scroll = self.scroll.min(lines.len().saturating_sub(visible));
+        let title = if visible < lines.len() {
+            " Keys (Up/Down scroll) "
+        } else {
+            " Keys "
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(title)
+            .title_alignment(Alignment::Center);
+
+        Clear.render(popup, buf);
+        Paragraph::new(lines.join("\n"))
+            .style(Style::default().fg(Color::White).bg(Color::Rgb(20, 20, 40)))
+            .block(block)
+            .scroll((scroll as u16, 0))
+            .render(popup, buf);
+    }
+}
+
+/// Single-line prompt shown at the top while typing a new scroller message.
+pub struct MessagePromptWidget<'a> {
+    pub text: &'a str,
+}
+
+impl<'a> Widget for MessagePromptWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 1 || area.width < 20 {
+            return;
+        }
+
+        let style = Style::default()
+            .fg(Color::Yellow)
+            .bg(Color::Rgb(20, 20, 40))
+            .add_modifier(Modifier::BOLD);
+        let line = format!(" Message (Enter apply, Esc cancel): {}_", self.text);
+
+        for x in area.x..area.x + area.width {
+            let cell = buf.get_mut(x, area.y);
+            cell.set_symbol(" ");
+            cell.set_style(style);
+        }
+        // Keep the end of long input (and the cursor) visible
+        let visible = line.chars().count().saturating_sub(area.width as usize);
+        for (i, ch) in line.chars().skip(visible).enumerate() {
+            let cell = buf.get_mut(area.x + i as u16, area.y);
+            cell.set_symbol(&ch.to_string());
+            cell.set_style(style);
+        }
+    }
+}